@@ -24,6 +24,12 @@ fn main() -> Result<()> {
                 BarcodePosition::Below,
             ),
         )?
+        // CODE128
+        .writeln("CODE128")?
+        .code128("ORDER-2024-123456")?
+        // GS1-128
+        .writeln("GS1-128")?
+        .gs1_128("(01)09501101530003(17)250101(10)ABC123")?
         // QR Code
         .writeln("QR Code")?
         .qrcode_option(
@@ -31,17 +37,20 @@ fn main() -> Result<()> {
             QRCodeOption::new(QRCodeModel::Model1, 6, QRCodeCorrectionLevel::M),
         )?
         // GS1 DataBar
-        .writeln("GS1 DataBar Expanded")?
+        .writeln("GS1 DataBar Omnidirectional")?
+        .gs1_databar("09501101530003")?
+        .writeln("GS1 DataBar Stacked")?
         .gs1_databar_2d("8245789658745")?
-        .writeln("GS1 DataBar ExpandedStacked")?
+        .writeln("GS1 DataBar StackedOmnidirectional")?
         .gs1_databar_2d_option(
             "1245789658745",
             GS1DataBar2DOption::new(GS1DataBar2DWidth::L, GS1DataBar2DType::StackedOmnidirectional),
         )?
-        .writeln("GS1 DataBar StackedOmnidirectional")?
+        .writeln("GS1 DataBar ExpandedStacked")?
         .gs1_databar_2d_option(
-            "1245789658745AC!4545A5151C12457896",
-            GS1DataBar2DOption::new(GS1DataBar2DWidth::S, GS1DataBar2DType::ExpandedStacked),
+            "(01)09501101530003(3103)000189(8020)AC!4545A5151",
+            GS1DataBar2DOption::new(GS1DataBar2DWidth::S, GS1DataBar2DType::ExpandedStacked)
+                .with_expanded_max_width(GS1DataBar2DExpandedWidth::Segments(4))?,
         )?
         // PDF417
         .writeln("PDF417")?
@@ -0,0 +1,826 @@
+//! Barcode image
+//!
+//! Render 1D barcodes as bit images, for printers not supporting a symbology
+//! and for features not available with the `GS k` command (EAN-2 and EAN-5 add-ons, ITF-14 bearer bars).
+
+#![cfg(all(feature = "graphics", feature = "barcodes"))]
+
+use super::{bit_image::*, codes::*, text_image::TextImageFont, types::JustifyMode};
+use crate::errors::{PrinterError, Result};
+use image::{DynamicImage, GrayImage, Luma};
+
+/// Quiet zone on each side of the symbol (in modules)
+const QUIET_ZONE: usize = 10;
+
+/// Wide element width of CODE39, ITF and CODABAR (in modules)
+const WIDE: usize = 3;
+
+/// Gap between the main symbol and its add-on (in modules)
+const ADD_ON_GAP: usize = 9;
+
+/// ITF-14 bearer bars thickness (in modules)
+const BEARER_BAR: usize = 5;
+
+/// Gap between the bars and the HRI characters (in modules)
+const HRI_GAP: usize = 2;
+
+/// EAN/UPC odd parity (L) digit patterns, even parity (G) and right (R) patterns are derived from them
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011", "0110111", "0001011",
+];
+
+/// EAN13 parity of the left digits, given by the first digit
+const EAN13_PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL", "LGGLGL",
+];
+
+/// UPC-E parity (number system 0), given by the check digit
+const UPCE_PARITY: [&str; 10] = [
+    "GGGLLL", "GGLGLL", "GGLLGL", "GGLLLG", "GLGGLL", "GLLGGL", "GLLLGG", "GLGLGL", "GLGLLG", "GLLGLG",
+];
+
+/// EAN-2 add-on parity, given by the value modulo 4
+const EAN2_PARITY: [&str; 4] = ["LL", "LG", "GL", "GG"];
+
+/// EAN-5 add-on parity, given by its checksum
+const EAN5_PARITY: [&str; 10] = [
+    "GGLLL", "GLGLL", "GLLGL", "GLLLG", "LGGLL", "LLGGL", "LLLGG", "LGLGL", "LGLLG", "LLGLG",
+];
+
+/// CODE39 characters and their wide elements (bar, space, bar, ...)
+const CODE39_PATTERNS: [(char, &str); 44] = [
+    ('0', "000110100"),
+    ('1', "100100001"),
+    ('2', "001100001"),
+    ('3', "101100000"),
+    ('4', "000110001"),
+    ('5', "100110000"),
+    ('6', "001110000"),
+    ('7', "000100101"),
+    ('8', "100100100"),
+    ('9', "001100100"),
+    ('A', "100001001"),
+    ('B', "001001001"),
+    ('C', "101001000"),
+    ('D', "000011001"),
+    ('E', "100011000"),
+    ('F', "001011000"),
+    ('G', "000001101"),
+    ('H', "100001100"),
+    ('I', "001001100"),
+    ('J', "000011100"),
+    ('K', "100000011"),
+    ('L', "001000011"),
+    ('M', "101000010"),
+    ('N', "000010011"),
+    ('O', "100010010"),
+    ('P', "001010010"),
+    ('Q', "000000111"),
+    ('R', "100000110"),
+    ('S', "001000110"),
+    ('T', "000010110"),
+    ('U', "110000001"),
+    ('V', "011000001"),
+    ('W', "111000000"),
+    ('X', "010010001"),
+    ('Y', "110010000"),
+    ('Z', "011010000"),
+    ('-', "010000101"),
+    ('.', "110000100"),
+    (' ', "011000100"),
+    ('$', "010101000"),
+    ('/', "010100010"),
+    ('+', "010001010"),
+    ('%', "000101010"),
+    ('*', "010010100"),
+];
+
+/// ITF digits wide elements
+const ITF_PATTERNS: [&str; 10] = [
+    "00110", "10001", "01001", "11000", "00101", "10100", "01100", "00011", "10010", "01010",
+];
+
+/// CODABAR characters and their wide elements (bar, space, bar, ...)
+const CODABAR_PATTERNS: [(char, &str); 20] = [
+    ('0', "0000011"),
+    ('1', "0000110"),
+    ('2', "0001001"),
+    ('3', "1100000"),
+    ('4', "0010010"),
+    ('5', "1000010"),
+    ('6', "0100001"),
+    ('7', "0100100"),
+    ('8', "0110000"),
+    ('9', "1001000"),
+    ('-', "0001100"),
+    ('$', "0011000"),
+    (':', "1000101"),
+    ('/', "1010001"),
+    ('.', "1010100"),
+    ('+', "0010101"),
+    ('A', "0011010"),
+    ('B', "0101001"),
+    ('C', "0001011"),
+    ('D', "0001110"),
+];
+
+/// CODE93 characters (values 0 to 42), the 4 shift characters are the values 43 to 46
+const CODE93_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+/// CODE93 modules by value (0 - 46) and start/stop character (47)
+const CODE93_PATTERNS: [&str; 48] = [
+    "100010100",
+    "101001000",
+    "101000100",
+    "101000010",
+    "100101000",
+    "100100100",
+    "100100010",
+    "101010000",
+    "100010010",
+    "100001010",
+    "110101000",
+    "110100100",
+    "110100010",
+    "110010100",
+    "110010010",
+    "110001010",
+    "101101000",
+    "101100100",
+    "101100010",
+    "100110100",
+    "100011010",
+    "101011000",
+    "101001100",
+    "101000110",
+    "100101100",
+    "100010110",
+    "110110100",
+    "110110010",
+    "110101100",
+    "110100110",
+    "110010110",
+    "110011010",
+    "101101100",
+    "101100110",
+    "100110110",
+    "100111010",
+    "100101110",
+    "111010100",
+    "111010010",
+    "111001010",
+    "101101110",
+    "101110110",
+    "110101110",
+    "100100110",
+    "111011010",
+    "111010110",
+    "100110010",
+    "101011110",
+];
+
+/// CODE128 element widths by symbol value (0 - 105) and stop character (106)
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213", "221312",
+    "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132", "221231", "213212",
+    "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211", "212123", "212321", "232121",
+    "111323", "131123", "131321", "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331",
+    "132131", "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131", "311123",
+    "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111", "111224", "111422", "121124",
+    "121421", "141122", "141221", "112214", "112412", "122114", "122411", "142112", "142211", "241211", "221114",
+    "413111", "241112", "134111", "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112",
+    "421211", "212141", "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+/// Barcode image option
+#[derive(Debug, Clone)]
+pub struct BarcodeImageOption {
+    /// Narrow bar width in dots
+    module_width: u32,
+    /// Bars height in dots
+    height: u32,
+    /// Font and size of the HRI characters (drawn below the bars)
+    hri: Option<(TextImageFont, f32)>,
+    /// EAN-2 or EAN-5 add-on (EAN8, EAN13, UPC-A and UPC-E)
+    add_on: Option<String>,
+    /// Bearer bars above and below the bars (ITF)
+    bearer_bars: bool,
+}
+
+impl BarcodeImageOption {
+    /// Create a new `BarcodeImageOption`
+    pub fn new(module_width: u32, height: u32) -> Result<Self> {
+        if module_width == 0 || height == 0 {
+            return Err(PrinterError::Input(format!(
+                "invalid barcode image module width or height: {module_width}, {height}"
+            )));
+        }
+
+        Ok(Self {
+            module_width,
+            height,
+            hri: None,
+            add_on: None,
+            bearer_bars: false,
+        })
+    }
+
+    /// Draw the HRI characters below the bars
+    pub fn with_hri(mut self, font: TextImageFont, size: f32) -> Self {
+        self.hri = Some((font, size));
+        self
+    }
+
+    /// Add an EAN-2 (2 digits) or EAN-5 (5 digits) add-on
+    pub fn with_add_on(mut self, add_on: &str) -> Result<Self> {
+        if ![2, 5].contains(&add_on.len()) || !add_on.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(PrinterError::Input(format!("invalid barcode add-on: {add_on}")));
+        }
+        self.add_on = Some(add_on.to_string());
+        Ok(self)
+    }
+
+    /// Draw bearer bars above and below the bars (ITF-14)
+    pub fn with_bearer_bars(mut self, bearer_bars: bool) -> Self {
+        self.bearer_bars = bearer_bars;
+        self
+    }
+
+    /// Get module width
+    pub fn module_width(&self) -> u32 {
+        self.module_width
+    }
+
+    /// Get height
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Get add-on
+    pub fn add_on(&self) -> Option<&str> {
+        self.add_on.as_deref()
+    }
+
+    /// Get bearer bars
+    pub fn bearer_bars(&self) -> bool {
+        self.bearer_bars
+    }
+}
+
+/// Digits of a numeric string
+fn digits(data: &str) -> Vec<usize> {
+    data.bytes().map(|d| usize::from(d - b'0')).collect()
+}
+
+/// Append modules written as `0` (space) and `1` (bar)
+fn push_bits(modules: &mut Vec<bool>, bits: &str) {
+    modules.extend(bits.bytes().map(|b| b == b'1'));
+}
+
+/// Append alternating bars and spaces (starting with a bar) with wide elements written as `1`
+fn push_wide_narrow(modules: &mut Vec<bool>, pattern: &str) {
+    for (i, wide) in pattern.bytes().enumerate() {
+        let width = if wide == b'1' { WIDE } else { 1 };
+        modules.extend(std::iter::repeat_n(i % 2 == 0, width));
+    }
+}
+
+/// Append an EAN/UPC digit with parity `L`, `G` or `R`
+fn push_ean_digit(modules: &mut Vec<bool>, digit: usize, parity: u8) {
+    let bits = EAN_L[digit].bytes().map(|b| b == b'1');
+    match parity {
+        b'L' => modules.extend(bits),
+        b'R' => modules.extend(bits.map(|b| !b)),
+        _ => modules.extend(bits.rev().map(|b| !b)),
+    }
+}
+
+/// EAN13 modules
+fn ean13_modules(data: &str) -> Vec<bool> {
+    let d = digits(data);
+    let mut modules = Vec::with_capacity(95);
+
+    push_bits(&mut modules, "101");
+    for (digit, parity) in d[1..7].iter().zip(EAN13_PARITY[d[0]].bytes()) {
+        push_ean_digit(&mut modules, *digit, parity);
+    }
+    push_bits(&mut modules, "01010");
+    for digit in &d[7..13] {
+        push_ean_digit(&mut modules, *digit, b'R');
+    }
+    push_bits(&mut modules, "101");
+
+    modules
+}
+
+/// EAN8 modules
+fn ean8_modules(data: &str) -> Vec<bool> {
+    let d = digits(data);
+    let mut modules = Vec::with_capacity(67);
+
+    push_bits(&mut modules, "101");
+    for digit in &d[..4] {
+        push_ean_digit(&mut modules, *digit, b'L');
+    }
+    push_bits(&mut modules, "01010");
+    for digit in &d[4..8] {
+        push_ean_digit(&mut modules, *digit, b'R');
+    }
+    push_bits(&mut modules, "101");
+
+    modules
+}
+
+/// UPC-E modules (8 digits: number system, 6 digits and check digit)
+fn upce_modules(data: &str) -> Vec<bool> {
+    let d = digits(data);
+    let mut modules = Vec::with_capacity(51);
+
+    push_bits(&mut modules, "101");
+    for (digit, parity) in d[1..7].iter().zip(UPCE_PARITY[d[7]].bytes()) {
+        // Parities are inverted with the number system 1
+        let parity = match (d[0], parity) {
+            (1, b'L') => b'G',
+            (1, _) => b'L',
+            (_, parity) => parity,
+        };
+        push_ean_digit(&mut modules, *digit, parity);
+    }
+    push_bits(&mut modules, "010101");
+
+    modules
+}
+
+/// EAN-2 or EAN-5 add-on modules
+fn add_on_modules(add_on: &str) -> Vec<bool> {
+    let d = digits(add_on);
+    let parities = match d.len() {
+        2 => EAN2_PARITY[(d[0] * 10 + d[1]) % 4],
+        _ => EAN5_PARITY[(3 * (d[0] + d[2] + d[4]) + 9 * (d[1] + d[3])) % 10],
+    };
+    let mut modules = Vec::with_capacity(47);
+
+    push_bits(&mut modules, "1011");
+    for (i, (digit, parity)) in d.iter().zip(parities.bytes()).enumerate() {
+        if i > 0 {
+            push_bits(&mut modules, "01");
+        }
+        push_ean_digit(&mut modules, *digit, parity);
+    }
+
+    modules
+}
+
+/// CODE39 modules (start and stop characters are added if needed)
+fn code39_modules(data: &str) -> Result<Vec<bool>> {
+    let data = data.trim_matches('*');
+    let mut modules = Vec::new();
+
+    for (i, c) in format!("*{data}*").chars().enumerate() {
+        let (_, pattern) = CODE39_PATTERNS
+            .iter()
+            .find(|(char, _)| *char == c)
+            .ok_or_else(|| PrinterError::Input(format!("invalid CODE39 character: {c}")))?;
+        if i > 0 {
+            modules.push(false);
+        }
+        push_wide_narrow(&mut modules, pattern);
+    }
+
+    Ok(modules)
+}
+
+/// ITF modules
+fn itf_modules(data: &str) -> Result<Vec<bool>> {
+    if !data.len().is_multiple_of(2) {
+        return Err(PrinterError::Input(format!(
+            "ITF data must have an even number of digits: {data}"
+        )));
+    }
+
+    let mut modules = Vec::new();
+    push_bits(&mut modules, "1010");
+    for pair in digits(data).chunks(2) {
+        let (bars, spaces) = (ITF_PATTERNS[pair[0]].as_bytes(), ITF_PATTERNS[pair[1]].as_bytes());
+        for i in 0..5 {
+            let pattern = [bars[i], spaces[i]];
+            push_wide_narrow(&mut modules, std::str::from_utf8(&pattern).unwrap_or_default());
+        }
+    }
+    push_wide_narrow(&mut modules, "100");
+
+    Ok(modules)
+}
+
+/// CODABAR modules
+fn codabar_modules(data: &str) -> Result<Vec<bool>> {
+    let data = data.to_ascii_uppercase();
+    let is_start_stop = |c: Option<char>| matches!(c, Some('A'..='D'));
+    if data.len() < 2 || !is_start_stop(data.chars().next()) || !is_start_stop(data.chars().last()) {
+        return Err(PrinterError::Input(format!(
+            "CODABAR data must start and end with A, B, C or D: {data}"
+        )));
+    }
+
+    let mut modules = Vec::new();
+    for (i, c) in data.chars().enumerate() {
+        let (_, pattern) = CODABAR_PATTERNS
+            .iter()
+            .find(|(char, _)| *char == c)
+            .ok_or_else(|| PrinterError::Input(format!("invalid CODABAR character: {c}")))?;
+        if i > 0 {
+            modules.push(false);
+        }
+        push_wide_narrow(&mut modules, pattern);
+    }
+
+    Ok(modules)
+}
+
+/// CODE93 values of an ASCII character (full ASCII with shift characters)
+fn code93_values(c: u8) -> Vec<usize> {
+    const DOLLAR: usize = 43;
+    const PERCENT: usize = 44;
+    const SLASH: usize = 45;
+    const PLUS: usize = 46;
+    let letter = |c: u8| usize::from(c - b'A') + 10;
+
+    if let Some(value) = CODE93_CHARS.bytes().position(|v| v == c) {
+        return vec![value];
+    }
+
+    match c {
+        0 => vec![PERCENT, letter(b'U')],
+        1..=26 => vec![DOLLAR, letter(c + b'A' - 1)],
+        27..=31 => vec![PERCENT, letter(c - 27 + b'A')],
+        b'!'..=b',' => vec![SLASH, letter(c - b'!' + b'A')],
+        b':' => vec![SLASH, letter(b'Z')],
+        b';'..=b'?' => vec![PERCENT, letter(c - b';' + b'F')],
+        b'@' => vec![PERCENT, letter(b'V')],
+        b'['..=b'_' => vec![PERCENT, letter(c - b'[' + b'K')],
+        b'`' => vec![PERCENT, letter(b'W')],
+        b'a'..=b'z' => vec![PLUS, letter(c - b'a' + b'A')],
+        _ => vec![PERCENT, letter(c - b'{' + b'P')],
+    }
+}
+
+/// CODE93 check character (weights cycling from 1 to `max_weight`, from the right)
+fn code93_check(values: &[usize], max_weight: usize) -> usize {
+    values
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, v)| v * (i % max_weight + 1))
+        .sum::<usize>()
+        % 47
+}
+
+/// CODE93 modules
+fn code93_modules(data: &str) -> Vec<bool> {
+    let mut values: Vec<usize> = data.bytes().flat_map(code93_values).collect();
+    values.push(code93_check(&values, 20));
+    values.push(code93_check(&values, 15));
+
+    let mut modules = Vec::new();
+    push_bits(&mut modules, CODE93_PATTERNS[47]);
+    for value in values {
+        push_bits(&mut modules, CODE93_PATTERNS[value]);
+    }
+    push_bits(&mut modules, CODE93_PATTERNS[47]);
+    push_bits(&mut modules, "1");
+
+    modules
+}
+
+/// CODE128 modules of symbol character values
+pub(crate) fn code128_values_modules(values: &[u8]) -> Vec<bool> {
+    let mut modules = Vec::new();
+
+    for value in values {
+        for (i, width) in CODE128_PATTERNS[usize::from(*value)].bytes().enumerate() {
+            modules.extend(std::iter::repeat_n(i % 2 == 0, usize::from(width - b'0')));
+        }
+    }
+
+    modules
+}
+
+/// CODE128 modules
+fn code128_modules(data: &str) -> Result<Vec<bool>> {
+    Ok(code128_values_modules(&code128_symbol_values(&code128_encode(data)?)))
+}
+
+/// Barcode modules (`true` for a bar)
+fn barcode_modules(barcode: &Barcode) -> Result<Vec<bool>> {
+    let data = barcode.data.as_str();
+
+    match barcode.system {
+        BarcodeSystem::UPCA => Ok(ean13_modules(&format!("0{data}"))),
+        BarcodeSystem::UPCE => Ok(upce_modules(data)),
+        BarcodeSystem::EAN13 => Ok(ean13_modules(data)),
+        BarcodeSystem::EAN8 => Ok(ean8_modules(data)),
+        BarcodeSystem::CODE39 => code39_modules(data),
+        BarcodeSystem::ITF => itf_modules(data),
+        BarcodeSystem::CODABAR => codabar_modules(data),
+        BarcodeSystem::CODE93 => Ok(code93_modules(data)),
+        BarcodeSystem::CODE128 => code128_modules(data),
+        BarcodeSystem::GS1128 => code128_modules(&format!("{{1{}", gs1_encode(&gs1_parse(data)?, "{1"))),
+    }
+}
+
+/// Barcode HRI characters
+fn barcode_hri(barcode: &Barcode) -> Result<String> {
+    match barcode.system {
+        BarcodeSystem::CODE39 => Ok(format!("*{}*", barcode.data.trim_matches('*'))),
+        BarcodeSystem::CODE93 => Ok(barcode.data.replace(|c: char| c.is_ascii_control(), " ")),
+        BarcodeSystem::CODE128 => Ok(code128_encode(&barcode.data)?
+            .iter()
+            .filter_map(|op| match op {
+                Code128Op::Char(c) if c.is_ascii_control() => Some(" ".to_string()),
+                Code128Op::Char(c) => Some((*c as char).to_string()),
+                Code128Op::Digits(n) => Some(format!("{n:02}")),
+                _ => None,
+            })
+            .collect()),
+        _ => Ok(barcode.data.clone()),
+    }
+}
+
+/// Draw a text centered in a region of the image
+fn draw_text(
+    img: &mut GrayImage,
+    text: &str,
+    font: &TextImageFont,
+    size: f32,
+    x: u32,
+    y: u32,
+    width: u32,
+) -> Result<()> {
+    let text = font.render(text, size, JustifyMode::CENTER, width)?.to_luma8();
+
+    for (tx, ty, pixel) in text.enumerate_pixels() {
+        if x + tx < img.width() && y + ty < img.height() {
+            let target = img.get_pixel_mut(x + tx, y + ty);
+            target.0[0] = target.0[0].min(pixel.0[0]);
+        }
+    }
+
+    Ok(())
+}
+
+impl BitImage {
+    /// Create a new image from a barcode
+    ///
+    /// The image includes the quiet zones and its width is rounded up to a multiple of 8.
+    pub fn from_barcode(barcode: &Barcode, option: &BarcodeImageOption) -> Result<Self> {
+        let is_ean_upc = matches!(
+            barcode.system,
+            BarcodeSystem::UPCA | BarcodeSystem::UPCE | BarcodeSystem::EAN13 | BarcodeSystem::EAN8
+        );
+        if option.add_on.is_some() && !is_ean_upc {
+            return Err(PrinterError::Input(format!(
+                "{} barcode cannot have an add-on",
+                barcode.system
+            )));
+        }
+        if option.bearer_bars && !matches!(barcode.system, BarcodeSystem::ITF) {
+            return Err(PrinterError::Input(format!(
+                "{} barcode cannot have bearer bars",
+                barcode.system
+            )));
+        }
+
+        let modules = barcode_modules(barcode)?;
+        let add_on = option.add_on.as_deref().map(add_on_modules).unwrap_or_default();
+
+        let module = option.module_width;
+        let dots = |modules: usize| module * modules as u32;
+        let add_on_width = match add_on.is_empty() {
+            true => 0,
+            false => dots(ADD_ON_GAP + add_on.len()),
+        };
+        let width = (dots(2 * QUIET_ZONE + modules.len()) + add_on_width).div_ceil(8) * 8;
+
+        let text_height = match &option.hri {
+            Some((font, size)) => font.render("0", *size, JustifyMode::LEFT, 8)?.height(),
+            None => 0,
+        };
+        let bearer = if option.bearer_bars { dots(BEARER_BAR) } else { 0 };
+        let hri_height = match option.hri {
+            Some(_) => dots(HRI_GAP) + text_height,
+            None => 0,
+        };
+        let height = bearer * 2 + option.height + hri_height;
+
+        let mut img = GrayImage::from_pixel(width, height, Luma([255]));
+        let mut draw_bars = |modules: &[bool], x: u32, top: u32| {
+            for (i, bar) in modules.iter().enumerate() {
+                if *bar {
+                    for dx in 0..module {
+                        for y in top..bearer + option.height {
+                            img.put_pixel(x + dots(i) + dx, y, Luma([0]));
+                        }
+                    }
+                }
+            }
+        };
+
+        let x = dots(QUIET_ZONE);
+        draw_bars(&modules, x, bearer);
+
+        // The add-on is shorter than the main symbol, its HRI characters are drawn above
+        let add_on_x = x + dots(modules.len() + ADD_ON_GAP);
+        let add_on_top = match option.hri {
+            Some(_) => text_height + dots(HRI_GAP),
+            None => 0,
+        };
+        draw_bars(&add_on, add_on_x, add_on_top.min(option.height / 2));
+
+        if bearer > 0 {
+            for y in (0..bearer).chain(bearer + option.height..bearer * 2 + option.height) {
+                for x in 0..dots(2 * QUIET_ZONE + modules.len()) {
+                    img.put_pixel(x, y, Luma([0]));
+                }
+            }
+        }
+
+        if let Some((font, size)) = &option.hri {
+            let text = barcode_hri(barcode)?;
+            let y = bearer * 2 + option.height + dots(HRI_GAP);
+            draw_text(&mut img, &text, font, *size, x, y, dots(modules.len()))?;
+
+            if let Some(add_on_text) = &option.add_on {
+                draw_text(&mut img, add_on_text, font, *size, add_on_x, 0, dots(add_on.len()))?;
+            }
+        }
+
+        Self::from_dynamic_image(
+            DynamicImage::ImageLuma8(img),
+            BitImageOption::new(None, None, BitImageSize::Normal)?,
+            "",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(modules: &[bool]) -> String {
+        modules.iter().map(|b| if *b { '1' } else { '0' }).collect()
+    }
+
+    fn barcode(system: BarcodeSystem, data: &str) -> Barcode {
+        Barcode::new(system, data, BarcodeOption::default()).unwrap()
+    }
+
+    #[test]
+    fn test_barcode_image_patterns() {
+        assert!(CODE39_PATTERNS
+            .iter()
+            .all(|(_, p)| p.len() == 9 && p.bytes().filter(|b| *b == b'1').count() == 3));
+        assert!(CODABAR_PATTERNS.iter().all(|(_, p)| p.len() == 7));
+        assert!(ITF_PATTERNS
+            .iter()
+            .all(|p| p.bytes().filter(|b| *b == b'1').count() == 2));
+        assert!(CODE93_PATTERNS
+            .iter()
+            .all(|p| p.len() == 9 && p.starts_with('1') && p.split('0').filter(|b| !b.is_empty()).count() == 3));
+        assert!(CODE128_PATTERNS[..106]
+            .iter()
+            .all(|p| p.bytes().map(|w| usize::from(w - b'0')).sum::<usize>() == 11));
+    }
+
+    #[test]
+    fn test_barcode_image_ean() {
+        let ean13 = barcode_modules(&barcode(BarcodeSystem::EAN13, "590123412345")).unwrap();
+        assert_eq!(ean13.len(), 95);
+        assert_eq!(
+            bits(&ean13[..24]),
+            // Guard, 9 (L), 0 (G), 1 (G)
+            "101".to_string() + "0001011" + "0100111" + "0110011"
+        );
+        assert_eq!(bits(&ean13[85..]), "1000100".to_string() + "101");
+
+        let ean8 = barcode_modules(&barcode(BarcodeSystem::EAN8, "9638507")).unwrap();
+        assert_eq!(ean8.len(), 67);
+        assert_eq!(bits(&ean8[..10]), "1010001011");
+
+        let upca = barcode_modules(&barcode(BarcodeSystem::UPCA, "03600029145")).unwrap();
+        assert_eq!(upca.len(), 95);
+        assert_eq!(bits(&upca[..10]), "1010001101");
+
+        let upce = barcode_modules(&barcode(BarcodeSystem::UPCE, "0425261")).unwrap();
+        assert_eq!(upce.len(), 51);
+        // Check digit 4: G L G G L L
+        assert_eq!(bits(&upce[3..17]), "0011101".to_string() + "0010011");
+        assert_eq!(bits(&upce[45..]), "010101");
+
+        assert_eq!(add_on_modules("12").len(), 20);
+        assert_eq!(add_on_modules("52495").len(), 47);
+        // 52495: checksum 1, parity G L G L L
+        assert_eq!(
+            bits(&add_on_modules("52495")[..13]),
+            "1011".to_string() + "0111001" + "01"
+        );
+    }
+
+    #[test]
+    fn test_barcode_image_code39_itf_codabar() {
+        let code39 = code39_modules("A").unwrap();
+        // Start, A and stop: 3 * 15 modules and 2 gaps
+        assert_eq!(code39.len(), 47);
+        assert_eq!(code39, code39_modules("*A*").unwrap());
+        assert!(code39_modules("a").is_err());
+
+        let itf = itf_modules("12").unwrap();
+        assert_eq!(bits(&itf), "1010".to_string() + "111010001010111000" + "11101");
+        assert!(itf_modules("123").is_err());
+
+        assert_eq!(codabar_modules("A1B").unwrap().len(), 13 + 11 + 13 + 2);
+        assert!(codabar_modules("1234").is_err());
+    }
+
+    #[test]
+    fn test_barcode_image_code93() {
+        // TEST93: check characters + and 6
+        let modules = code93_modules("TEST93");
+        assert_eq!(modules.len(), 9 * 10 + 1);
+        assert_eq!(bits(&modules[63..81]), "101110110".to_string() + "100100010");
+
+        assert_eq!(code93_values(b'a'), vec![46, 10]);
+        assert_eq!(code93_values(b'!'), vec![45, 10]);
+        assert_eq!(code93_values(0), vec![44, 30]);
+        assert_eq!(code93_values(b'\x7f'), vec![44, 29]);
+    }
+
+    #[test]
+    fn test_barcode_image_code128() {
+        let modules = code128_modules("123456").unwrap();
+        assert_eq!(modules.len(), 11 * 5 + 13);
+        assert_eq!(bits(&modules[..11]), "11010011100");
+        assert_eq!(bits(&modules[55..]), "1100011101011");
+
+        let gs1 = barcode_modules(&barcode(BarcodeSystem::GS1128, "(01)09501101530003")).unwrap();
+        // Start C, FNC1, 8 pairs of digits, check character and stop
+        assert_eq!(gs1.len(), 11 * 11 + 13);
+        assert_eq!(bits(&gs1[11..22]), "11110101110");
+    }
+
+    #[test]
+    fn test_barcode_image_hri() {
+        assert_eq!(barcode_hri(&barcode(BarcodeSystem::CODE39, "ABC")).unwrap(), "*ABC*");
+        assert_eq!(
+            barcode_hri(&barcode(BarcodeSystem::CODE128, "AB1234")).unwrap(),
+            "AB1234"
+        );
+        assert_eq!(barcode_hri(&barcode(BarcodeSystem::CODE128, "{10112")).unwrap(), "0112");
+        assert_eq!(
+            barcode_hri(&barcode(BarcodeSystem::EAN13, "590123412345")).unwrap(),
+            "5901234123457"
+        );
+    }
+
+    #[test]
+    fn test_bit_image_from_barcode() {
+        let font = TextImageFont::new("./resources/fonts/blocks.ttf").unwrap();
+        let option = BarcodeImageOption::new(2, 40).unwrap();
+
+        assert!(BarcodeImageOption::new(0, 40).is_err());
+        assert!(option.clone().with_add_on("123").is_err());
+
+        let ean13 = barcode(BarcodeSystem::EAN13, "590123412345");
+        let bit_image = BitImage::from_barcode(&ean13, &option).unwrap();
+        // (95 + 2 * 10) * 2 = 230 dots, rounded up to 232
+        assert_eq!(bit_image.width_bytes().unwrap(), 29);
+        assert_eq!(bit_image.height_u8().unwrap(), vec![40, 0]);
+
+        let option = option.with_hri(font, 10.0).with_add_on("12").unwrap();
+        let bit_image = BitImage::from_barcode(&ean13, &option).unwrap();
+        // (95 + 9 + 20 + 2 * 10) * 2 = 288 dots
+        assert_eq!(bit_image.width_bytes().unwrap(), 36);
+        // Bars, gap and HRI characters
+        assert_eq!(bit_image.height_u8().unwrap(), vec![54, 0]);
+        // Add-on HRI characters above the add-on bars, main symbol bars at the top
+        assert!(bit_image.pixel(20, 0).0[0] < 128);
+        assert!(bit_image.pixel(244, 4).0[0] < 128);
+        assert!(bit_image.pixel(229, 10).0[0] > 128);
+        assert!(bit_image.pixel(229, 20).0[0] < 128);
+
+        let itf = barcode(BarcodeSystem::ITF, "1540014128876");
+        assert!(BitImage::from_barcode(&itf, &option).is_err());
+        let itf14 = BarcodeImageOption::new(1, 40).unwrap().with_bearer_bars(true);
+        let bit_image = BitImage::from_barcode(
+            &Barcode::new(BarcodeSystem::ITF, "15400141288763", BarcodeOption::default()).unwrap(),
+            &itf14,
+        )
+        .unwrap();
+        assert_eq!(bit_image.height_u8().unwrap(), vec![50, 0]);
+        assert!(bit_image.pixel(0, 0).0[0] < 128);
+        assert!(bit_image.pixel(0, 10).0[0] > 128);
+        assert!(
+            BitImage::from_barcode(&ean13, &BarcodeImageOption::new(1, 40).unwrap().with_bearer_bars(true)).is_err()
+        );
+    }
+}
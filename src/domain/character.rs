@@ -80,6 +80,17 @@ pub enum PageCode {
     WPC1257,
     WPC1258,
     KZ1048,
+    Thai42,
+    Thai11,
+    Thai13,
+    Thai14,
+    Thai16,
+    Thai17,
+    /// Thai character code 18 (TIS-620), used by Thai models for three-pass printing
+    Thai18,
+    /// Page code registered at runtime (see [`CustomPageCode`](crate::utils::CustomPageCode)),
+    /// identified by its `ESC t` number
+    Custom(u8),
 }
 
 impl fmt::Display for PageCode {
@@ -123,10 +134,34 @@ impl fmt::Display for PageCode {
             PageCode::WPC1257 => write!(f, "WPC1257"),
             PageCode::WPC1258 => write!(f, "WPC1258"),
             PageCode::KZ1048 => write!(f, "KZ1048"),
+            PageCode::Thai42 => write!(f, "Thai42"),
+            PageCode::Thai11 => write!(f, "Thai11"),
+            PageCode::Thai13 => write!(f, "Thai13"),
+            PageCode::Thai14 => write!(f, "Thai14"),
+            PageCode::Thai16 => write!(f, "Thai16"),
+            PageCode::Thai17 => write!(f, "Thai17"),
+            PageCode::Thai18 => write!(f, "Thai18"),
+            PageCode::Custom(n) => write!(f, "Custom ({n})"),
         }
     }
 }
 
+impl PageCode {
+    /// Is it one of the Thai page codes?
+    pub fn is_thai(&self) -> bool {
+        matches!(
+            self,
+            PageCode::Thai42
+                | PageCode::Thai11
+                | PageCode::Thai13
+                | PageCode::Thai14
+                | PageCode::Thai16
+                | PageCode::Thai17
+                | PageCode::Thai18
+        )
+    }
+}
+
 impl From<PageCode> for u8 {
     fn from(value: PageCode) -> Self {
         match value {
@@ -168,6 +203,14 @@ impl From<PageCode> for u8 {
             PageCode::WPC1257 => 51,
             PageCode::WPC1258 => 52,
             PageCode::KZ1048 => 53,
+            PageCode::Thai42 => 20,
+            PageCode::Thai11 => 21,
+            PageCode::Thai13 => 22,
+            PageCode::Thai14 => 23,
+            PageCode::Thai16 => 24,
+            PageCode::Thai17 => 25,
+            PageCode::Thai18 => 26,
+            PageCode::Custom(n) => n,
         }
     }
 }
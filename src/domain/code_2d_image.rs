@@ -0,0 +1,500 @@
+//! 2D code image
+//!
+//! Render QR codes, DataMatrix, Aztec codes and PDF417 as bit images, for printers not supporting the `GS ( k`
+//! command.
+
+#![cfg(all(feature = "graphics", feature = "codes_2d"))]
+
+use super::{bit_image::*, codes::*};
+use crate::errors::{PrinterError, Result};
+use ::qrcode::{bits::Bits, types::Color, EcLevel, QrCode, Version};
+use image::{DynamicImage, GrayImage, Luma};
+
+/// QR code quiet zone (in modules)
+const QRCODE_QUIET_ZONE: usize = 4;
+
+/// Micro QR code quiet zone (in modules)
+const MICRO_QRCODE_QUIET_ZONE: usize = 2;
+
+/// DataMatrix quiet zone (in modules)
+const DATA_MATRIX_QUIET_ZONE: usize = 1;
+
+/// QR code module size when the size option is 0
+const QRCODE_DEFAULT_SIZE: u8 = 4;
+
+/// PDF417 quiet zone (in modules)
+const PDF417_QUIET_ZONE: usize = 2;
+
+/// PDF417 module width (in dots) when the width option is 0
+const PDF417_DEFAULT_WIDTH: u8 = 3;
+
+/// PDF417 row height (in module widths) when the row height option is 0
+const PDF417_DEFAULT_ROW_HEIGHT: u8 = 3;
+
+/// Composite quiet zone (in modules), required by the GS1-128 linear element
+#[cfg(feature = "barcodes")]
+const COMPOSITE_QUIET_ZONE: usize = 10;
+
+/// Composite linear element height (in modules)
+#[cfg(feature = "barcodes")]
+const COMPOSITE_LINEAR_HEIGHT: usize = 50;
+
+/// Offset of the GS1-128 linear element from the left of the CC-C component (in modules)
+#[cfg(feature = "barcodes")]
+const COMPOSITE_CC_C_OFFSET: usize = 7;
+
+/// Build an image from a matrix of modules (`true` for dark modules), the width is rounded up to a multiple of 8
+fn matrix_image(modules: &[Vec<bool>], quiet_zone: usize, module_size: u8) -> GrayImage {
+    let module_size = u32::from(module_size.max(1));
+    let dots = |modules: usize| module_size * modules as u32;
+    let rows = modules.len();
+    let columns = modules.first().map(Vec::len).unwrap_or_default();
+
+    let width = dots(columns + 2 * quiet_zone).div_ceil(8) * 8;
+    let height = dots(rows + 2 * quiet_zone);
+    let mut img = GrayImage::from_pixel(width, height, Luma([255]));
+
+    for (y, row) in modules.iter().enumerate() {
+        for (x, dark) in row.iter().enumerate() {
+            if *dark {
+                for dy in 0..module_size {
+                    for dx in 0..module_size {
+                        img.put_pixel(dots(quiet_zone + x) + dx, dots(quiet_zone + y) + dy, Luma([0]));
+                    }
+                }
+            }
+        }
+    }
+
+    img
+}
+
+/// Build a bit image from an image of a 2D code
+fn code_bit_image(img: GrayImage) -> Result<BitImage> {
+    BitImage::from_dynamic_image(
+        DynamicImage::ImageLuma8(img),
+        BitImageOption::new(None, None, BitImageSize::Normal)?,
+        "",
+    )
+}
+
+/// Build a bit image from a matrix of modules (`true` for dark modules), the width is rounded up to a multiple of 8
+fn matrix_bit_image(modules: &[Vec<bool>], quiet_zone: usize, module_size: u8) -> Result<BitImage> {
+    code_bit_image(matrix_image(modules, quiet_zone, module_size))
+}
+
+/// Build a bit image from the rows of a PDF417 symbol
+fn pdf417_bit_image(rows: &[Vec<bool>], option: &Pdf417Option) -> Result<BitImage> {
+    let width = match option.width() {
+        0 => PDF417_DEFAULT_WIDTH,
+        width => width,
+    };
+    let row_height = match option.row_height() {
+        0 => PDF417_DEFAULT_ROW_HEIGHT,
+        row_height => row_height,
+    };
+    let modules = rows
+        .iter()
+        .flat_map(|row| std::iter::repeat_n(row.clone(), usize::from(row_height)))
+        .collect::<Vec<_>>();
+    matrix_bit_image(&modules, PDF417_QUIET_ZONE, width)
+}
+
+/// Draw the Swiss cross in the center of a symbol of `size` dots starting at `offset` dots
+///
+/// The cross is 7 mm wide in a 46 mm symbol: a white border, a black square and a white cross
+/// with the proportions of the Swiss flag.
+fn draw_swiss_cross(img: &mut GrayImage, offset: u32, size: u32) {
+    // Rectangles centered in the symbol, from their half width and half height
+    let center = offset + size / 2;
+    let mut fill = |half_width: u32, half_height: u32, luma: u8| {
+        for y in center - half_height..center + half_height {
+            for x in center - half_width..center + half_width {
+                img.put_pixel(x, y, Luma([luma]));
+            }
+        }
+    };
+
+    let cross = size * 7 / 92;
+    fill(cross, cross, 255);
+
+    let square = cross - (cross / 14).max(1);
+    fill(square, square, 0);
+
+    let arm_length = square * 20 / 32;
+    let arm_width = (square * 6 / 32).max(1);
+    fill(arm_width, arm_length, 255);
+    fill(arm_length, arm_width, 255);
+}
+
+/// Encode a QR code with an ECI designator in the smallest version
+fn qrcode_with_eci(data: &[u8], eci: u32, ec_level: EcLevel) -> Result<QrCode> {
+    (1..=40)
+        .find_map(|version| {
+            let mut bits = Bits::new(Version::Normal(version));
+            bits.push_eci_designator(eci).ok()?;
+            bits.push_optimal_data(data).ok()?;
+            bits.push_terminator(ec_level).ok()?;
+            QrCode::with_bits(bits, ec_level).ok()
+        })
+        .ok_or_else(|| PrinterError::Input(format!("QR code cannot encode data with ECI designator {eci}")))
+}
+
+/// Encode a QR code (Model 2 or Micro QR code)
+fn qrcode_modules(code: &QRCode) -> Result<(Vec<Vec<bool>>, usize)> {
+    let ec_level = match code.option.correction_level() {
+        QRCodeCorrectionLevel::L => EcLevel::L,
+        QRCodeCorrectionLevel::M => EcLevel::M,
+        QRCodeCorrectionLevel::Q => EcLevel::Q,
+        QRCodeCorrectionLevel::H => EcLevel::H,
+    };
+
+    let (symbol, quiet_zone) = match code.option.model() {
+        QRCodeModel::Micro => (
+            (1..=4)
+                .find_map(|version| QrCode::with_version(&code.data, Version::Micro(version), ec_level).ok())
+                .ok_or_else(|| {
+                    PrinterError::Input(format!(
+                        "Micro QR code cannot encode data with {}",
+                        code.option.correction_level()
+                    ))
+                })?,
+            MICRO_QRCODE_QUIET_ZONE,
+        ),
+        QRCodeModel::Model1 => {
+            return Err(PrinterError::Input(
+                "QR code Model 1 cannot be printed as image, use Model 2 or disable software 2D codes".to_string(),
+            ))
+        }
+        QRCodeModel::Model2 => match code.option.eci() {
+            Some(eci) => (qrcode_with_eci(&code.data, eci, ec_level)?, QRCODE_QUIET_ZONE),
+            None => (
+                QrCode::with_error_correction_level(&code.data, ec_level)
+                    .map_err(|e| PrinterError::Input(format!("QR code cannot encode data: {e}")))?,
+                QRCODE_QUIET_ZONE,
+            ),
+        },
+    };
+
+    let modules = symbol
+        .to_colors()
+        .chunks(symbol.width())
+        .map(|row| row.iter().map(|color| *color == Color::Dark).collect())
+        .collect();
+    Ok((modules, quiet_zone))
+}
+
+impl BitImage {
+    /// Create a new `BitImage` from a QR code, each module being `size` dots wide (4 if size is 0)
+    ///
+    /// Model 1 QR codes are not supported, Micro QR codes use the smallest version fitting the data.
+    pub fn from_qrcode(code: &QRCode) -> Result<Self> {
+        let (modules, quiet_zone) = qrcode_modules(code)?;
+        let size = match code.option.size() {
+            0 => QRCODE_DEFAULT_SIZE,
+            size => size,
+        };
+        matrix_bit_image(&modules, quiet_zone, size)
+    }
+
+    /// Create a new `BitImage` from a QR code with the Swiss cross in its center (Swiss QR-bill)
+    pub fn from_qrcode_with_swiss_cross(code: &QRCode) -> Result<Self> {
+        let (modules, quiet_zone) = qrcode_modules(code)?;
+        let size = match code.option.size() {
+            0 => QRCODE_DEFAULT_SIZE,
+            size => size,
+        };
+        let mut img = matrix_image(&modules, quiet_zone, size);
+        let dots = |modules: usize| u32::from(size) * modules as u32;
+        draw_swiss_cross(&mut img, dots(quiet_zone), dots(modules.len()));
+        code_bit_image(img)
+    }
+
+    /// Create a new `BitImage` from a DataMatrix (ECC 200, ASCII encodation), each module being `size` dots wide
+    ///
+    /// GS1 DataMatrix starts with FNC1.
+    pub fn from_data_matrix(code: &DataMatrix) -> Result<Self> {
+        let option = &code.option;
+        let modules = data_matrix_modules(&code.data, option.code_type(), option.eci(), option.gs1())?;
+        matrix_bit_image(&modules, DATA_MATRIX_QUIET_ZONE, code.option.size())
+    }
+
+    /// Create a new `BitImage` from an Aztec code, each module being `size` dots wide
+    ///
+    /// The error correction level is the minimum percentage of the data used for error correction.
+    pub fn from_aztec(code: &Aztec) -> Result<Self> {
+        let option = &code.option;
+        let modules = aztec_modules(&code.data, option.mode(), option.correction_level(), option.eci())?;
+        matrix_bit_image(&modules, 0, code.option.size())
+    }
+
+    /// Create a new `BitImage` from a PDF417, each module being `width` dots wide and each row `row_height` modules
+    /// high (3 if 0)
+    ///
+    /// Without number of columns and rows, the symbol is about twice as wide as high.
+    pub fn from_pdf417(code: &Pdf417) -> Result<Self> {
+        let mut codewords = code.option.eci().map(pdf417_eci_codewords).unwrap_or_default();
+        codewords.extend(pdf417_data_codewords(&code.data));
+        let modules = pdf417_modules(&codewords, &code.option)?;
+        pdf417_bit_image(&modules, &code.option)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Create a new `BitImage` from a GS1 Composite code, each module being `width` dots wide
+    ///
+    /// Only GS1-128 composite codes are supported, with a CC-C component whatever the data length.
+    /// The component rows are 3 modules high, separated from the linear element by the inverse of its modules.
+    /// HRI characters are not printed.
+    pub fn from_composite(code: &Composite) -> Result<Self> {
+        let linear_type = code.option.linear_type();
+        if linear_type != CompositeLinearType::GS1128 {
+            return Err(PrinterError::Input(format!(
+                "{linear_type} composite cannot be printed as image, use GS1-128 or disable software 2D codes"
+            )));
+        }
+
+        let (_, linear_data) = linear_type.printer_data(&code.linear_data)?;
+        let linear = super::barcode_image::code128_values_modules(&gs1_128_composite_values(&linear_data)?);
+        let component = cc_c_modules(code, linear.len())?;
+
+        let width = component
+            .first()
+            .map(Vec::len)
+            .unwrap_or_default()
+            .max(COMPOSITE_CC_C_OFFSET + linear.len());
+        let align = |row: &[bool], offset: usize| {
+            let mut aligned = vec![false; width];
+            aligned[offset..offset + row.len()].copy_from_slice(row);
+            aligned
+        };
+
+        let mut modules = Vec::new();
+        for row in &component {
+            modules.extend(std::iter::repeat_n(
+                align(row, 0),
+                usize::from(PDF417_DEFAULT_ROW_HEIGHT),
+            ));
+        }
+        let separator = linear.iter().map(|bar| !bar).collect::<Vec<_>>();
+        modules.push(align(&separator, COMPOSITE_CC_C_OFFSET));
+        modules.extend(std::iter::repeat_n(
+            align(&linear, COMPOSITE_CC_C_OFFSET),
+            COMPOSITE_LINEAR_HEIGHT,
+        ));
+
+        matrix_bit_image(&modules, COMPOSITE_QUIET_ZONE, code.option.width())
+    }
+
+    /// Create a new `BitImage` from a segment of a Macro PDF417 sequence
+    ///
+    /// The segment data is encoded with byte compaction and followed by its control block.
+    pub fn from_macro_pdf417(code: &MacroPdf417, index: usize) -> Result<Self> {
+        let (Some(segment), Some(control_block)) = (code.segment(index), code.control_block(index)) else {
+            return Err(PrinterError::Input(format!(
+                "invalid Macro PDF417 segment index: {index} (segment count: {})",
+                code.segment_count()
+            )));
+        };
+
+        let mut codewords = code.option.eci().map(pdf417_eci_codewords).unwrap_or_default();
+        codewords.extend(byte_compaction(segment));
+        codewords.extend(control_block);
+        let modules = pdf417_modules(&codewords, &code.option)?;
+        pdf417_bit_image(&modules, &code.option)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_bit_image() {
+        let modules = vec![vec![true, false], vec![false, true]];
+        let bit_image = matrix_bit_image(&modules, 1, 3).unwrap();
+        // (2 + 2) * 3 dots rounded up to 16
+        assert_eq!((bit_image.image().width(), bit_image.image().height()), (16, 12));
+        assert_eq!(bit_image.pixel(3, 3).0[0], 0);
+        assert_eq!(bit_image.pixel(5, 5).0[0], 0);
+        assert_eq!(bit_image.pixel(6, 3).0[0], 255);
+        assert_eq!(bit_image.pixel(2, 3).0[0], 255);
+    }
+
+    #[test]
+    fn test_bit_image_from_qrcode() {
+        let code = QRCode::new(
+            "https://example.com",
+            Some(QRCodeOption::new(QRCodeModel::Model2, 2, QRCodeCorrectionLevel::M)),
+        )
+        .unwrap();
+        let (modules, quiet_zone) = qrcode_modules(&code).unwrap();
+        assert_eq!((modules.len(), quiet_zone), (25, 4));
+        // Finder pattern
+        assert!((0..7).all(|x| modules[0][x] && modules[6][x]));
+        assert!(!modules[0][7]);
+
+        let bit_image = BitImage::from_qrcode(&code).unwrap();
+        assert_eq!(bit_image.image().height(), (25 + 8) * 2);
+
+        let code = QRCode::new(
+            "12345",
+            Some(QRCodeOption::new(QRCodeModel::Micro, 0, QRCodeCorrectionLevel::L)),
+        )
+        .unwrap();
+        let (modules, quiet_zone) = qrcode_modules(&code).unwrap();
+        assert_eq!((modules.len(), quiet_zone), (11, 2));
+
+        let code = QRCode::new(
+            "https://example.com",
+            Some(QRCodeOption::new(QRCodeModel::Model1, 2, QRCodeCorrectionLevel::M)),
+        )
+        .unwrap();
+        assert!(BitImage::from_qrcode(&code).is_err());
+    }
+
+    #[test]
+    fn test_bit_image_from_qrcode_with_swiss_cross() {
+        let code = QRCode::new(
+            &"SPC".repeat(100),
+            Some(QRCodeOption::new(QRCodeModel::Model2, 2, QRCodeCorrectionLevel::M)),
+        )
+        .unwrap();
+        let plain = BitImage::from_qrcode(&code).unwrap();
+        let bit_image = BitImage::from_qrcode_with_swiss_cross(&code).unwrap();
+        assert_eq!(
+            (plain.image().width(), plain.image().height()),
+            (bit_image.image().width(), bit_image.image().height())
+        );
+
+        // White cross in a black square in the center of the symbol
+        let (modules, quiet_zone) = qrcode_modules(&code).unwrap();
+        let center = (2 * quiet_zone + modules.len()) as u32;
+        let cross = modules.len() as u32 * 2 * 7 / 92;
+        assert_eq!(bit_image.pixel(center, center).0[0], 255);
+        assert_eq!(bit_image.pixel(center - cross + 2, center - cross + 2).0[0], 0);
+        assert_eq!(bit_image.pixel(center - cross, center - cross).0[0], 255);
+        assert_eq!(bit_image.pixel(quiet_zone as u32 * 2, quiet_zone as u32 * 2).0[0], 0);
+    }
+
+    #[test]
+    fn test_bit_image_from_qrcode_eci() {
+        let option = QRCodeOption::new(QRCodeModel::Model2, 1, QRCodeCorrectionLevel::L)
+            .with_eci(3)
+            .unwrap();
+        let code = QRCode::from_bytes(&[0xA9; 17], Some(option)).unwrap();
+        let (modules, _) = qrcode_modules(&code).unwrap();
+        // Version 2: the ECI designator does not fit in version 1
+        assert_eq!(modules.len(), 25);
+    }
+
+    #[test]
+    fn test_bit_image_from_data_matrix() {
+        let code = DataMatrix::new("123456", DataMatrixOption::new(DataMatrixType::Square(0), 4).unwrap()).unwrap();
+        let bit_image = BitImage::from_data_matrix(&code).unwrap();
+        // (10 + 2) * 4 dots
+        assert_eq!((bit_image.image().width(), bit_image.image().height()), (48, 48));
+
+        let code = DataMatrix::new(
+            "123456",
+            DataMatrixOption::new(DataMatrixType::Rectangle(8, 0), 2).unwrap(),
+        )
+        .unwrap();
+        let bit_image = BitImage::from_data_matrix(&code).unwrap();
+        assert_eq!((bit_image.image().width(), bit_image.image().height()), (40, 20));
+
+        // The symbol size is the one of the rendered symbol
+        let code = DataMatrix::new(
+            "ABCDEF",
+            DataMatrixOption::new(DataMatrixType::Rectangle(8, 0), 2).unwrap(),
+        )
+        .unwrap();
+        let (rows, columns) = code.symbol_size().unwrap();
+        let bit_image = BitImage::from_data_matrix(&code).unwrap();
+        assert_eq!(bit_image.image().height(), (rows as u32 + 2) * 2);
+        assert_eq!(bit_image.image().width(), ((columns as u32 + 2) * 2).div_ceil(8) * 8);
+
+        // FNC1 and 4 codewords: 12x12
+        let option = DataMatrixOption::new(DataMatrixType::Square(0), 2)
+            .unwrap()
+            .with_gs1(true);
+        let code = DataMatrix::new("(17)250101", option).unwrap();
+        let bit_image = BitImage::from_data_matrix(&code).unwrap();
+        assert_eq!(bit_image.image().height(), (12 + 2) * 2);
+    }
+
+    #[test]
+    fn test_bit_image_from_aztec() {
+        let code = Aztec::new("ABC", AztecOption::new(AztecMode::Compact(0), 2, 23).unwrap());
+        let bit_image = BitImage::from_aztec(&code).unwrap();
+        assert_eq!((bit_image.image().width(), bit_image.image().height()), (32, 30));
+
+        let code = Aztec::new(
+            &"A".repeat(200),
+            AztecOption::new(AztecMode::Compact(0), 2, 23).unwrap(),
+        );
+        assert!(BitImage::from_aztec(&code).is_err());
+    }
+
+    #[test]
+    fn test_bit_image_from_pdf417() {
+        let option = Pdf417Option::new(3, 3, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1).unwrap();
+        let bit_image = BitImage::from_pdf417(&Pdf417::new("PDF417", option)).unwrap();
+        // 7 codewords and the stop pattern bar, 3 rows of 3 modules, quiet zones of 2 modules
+        assert_eq!((bit_image.image().width(), bit_image.image().height()), (248, 26));
+        // Start pattern
+        assert_eq!(bit_image.pixel(4, 4).0[0], 0);
+        assert_eq!(bit_image.pixel(4 + 8 * 2, 4).0[0], 255);
+
+        let option = Pdf417Option::new(3, 3, 2, 3, Pdf417Type::Truncated, Pdf417CorrectionLevel::Level1).unwrap();
+        let bit_image = BitImage::from_pdf417(&Pdf417::new("PDF417", option)).unwrap();
+        assert_eq!(bit_image.image().width(), 184);
+
+        let option = Pdf417Option::new(1, 3, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1).unwrap();
+        assert!(BitImage::from_pdf417(&Pdf417::new(&"A".repeat(10), option)).is_err());
+
+        // ECI designator (2 codewords): 4 rows
+        let option = Pdf417Option::new(3, 0, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1)
+            .unwrap()
+            .with_eci(26)
+            .unwrap();
+        let bit_image = BitImage::from_pdf417(&Pdf417::new("PDF417", option)).unwrap();
+        assert_eq!(bit_image.image().height(), 32);
+    }
+
+    #[test]
+    fn test_bit_image_from_macro_pdf417() {
+        let option = Pdf417Option::new(5, 10, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1).unwrap();
+        let macro_option = MacroPdf417Option::new("017053").unwrap();
+        let code = MacroPdf417::new(&"A".repeat(80), option, macro_option).unwrap();
+        assert_eq!(code.segment_count(), 2);
+
+        for index in 0..2 {
+            let bit_image = BitImage::from_macro_pdf417(&code, index).unwrap();
+            assert_eq!((bit_image.image().width(), bit_image.image().height()), (320, 68));
+        }
+        assert!(BitImage::from_macro_pdf417(&code, 2).is_err());
+    }
+
+    #[cfg(feature = "barcodes")]
+    #[test]
+    fn test_bit_image_from_composite() {
+        let option = CompositeOption::new(CompositeLinearType::GS1128, 2, CompositeFont::A).unwrap();
+        let code = Composite::new("(01)09501101530003", "(17)250101(10)ABC123", option).unwrap();
+        let bit_image = BitImage::from_composite(&code).unwrap();
+
+        // Start C, FNC1, 7 pairs of digits, linkage, check and stop characters: 12 * 11 + 13 modules
+        let linear_width = 12 * 11 + 13;
+        let component = cc_c_modules(&code, linear_width).unwrap();
+        assert_eq!(component[0].len(), 17 * 5 + 69);
+        let height = component.len() * 3 + 1 + COMPOSITE_LINEAR_HEIGHT + 2 * COMPOSITE_QUIET_ZONE;
+        assert_eq!(bit_image.image().height(), height as u32 * 2);
+        // The component is 2 modules wider than the linear element and its offset
+        assert_eq!(
+            bit_image.image().width(),
+            ((linear_width + 9 + 2 * COMPOSITE_QUIET_ZONE) as u32 * 2).div_ceil(8) * 8
+        );
+
+        let code = Composite::new("9501101530003", "(10)ABC", CompositeOption::default()).unwrap();
+        assert!(BitImage::from_composite(&code).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 //! Aztec code
 
-use crate::errors::{PrinterError, Result};
+use crate::{
+    domain::common::check_eci_designator,
+    errors::{PrinterError, Result},
+};
 use std::fmt;
 
 /// Aztec code mode
@@ -56,6 +59,7 @@ pub struct AztecOption {
     mode: AztecMode,
     size: u8,
     correction_level: u8,
+    eci: Option<u32>,
 }
 
 impl Default for AztecOption {
@@ -64,6 +68,7 @@ impl Default for AztecOption {
             mode: AztecMode::default(),
             size: 3,
             correction_level: 23,
+            eci: None,
         }
     }
 }
@@ -85,9 +90,20 @@ impl AztecOption {
             mode,
             size,
             correction_level,
+            eci: None,
         })
     }
 
+    /// Declare the character set of the data with an ECI designator (0-999999)
+    ///
+    /// ECI designators are only encoded in Aztec codes printed as images (software 2D codes, `graphics` feature):
+    /// the printer commands (`GS ( k`) do not support them, printing the code without software 2D codes fails.
+    pub fn with_eci(mut self, eci: u32) -> Result<Self> {
+        check_eci_designator(eci)?;
+        self.eci = Some(eci);
+        Ok(self)
+    }
+
     /// Get mode
     pub fn mode(&self) -> AztecMode {
         self.mode
@@ -102,20 +118,30 @@ impl AztecOption {
     pub fn correction_level(&self) -> u8 {
         self.correction_level
     }
+
+    /// Get ECI designator
+    pub fn eci(&self) -> Option<u32> {
+        self.eci
+    }
 }
 
 /// Aztec code
 #[derive(Debug)]
 pub struct Aztec {
-    pub data: String,
+    pub data: Vec<u8>,
     pub option: AztecOption,
 }
 
 impl Aztec {
     /// Create a new `Aztec`
     pub fn new(data: &str, option: AztecOption) -> Self {
+        Self::from_bytes(data.as_bytes(), option)
+    }
+
+    /// Create a new `Aztec` from bytes
+    pub fn from_bytes(data: &[u8], option: AztecOption) -> Self {
         Self {
-            data: data.to_string(),
+            data: data.to_vec(),
             option,
         }
     }
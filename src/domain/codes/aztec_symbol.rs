@@ -0,0 +1,460 @@
+//! Aztec code symbol encoding, used to render Aztec codes as image
+
+#![cfg(all(feature = "graphics", feature = "codes_2d"))]
+
+use super::{aztec::AztecMode, reed_solomon::GaloisField};
+use crate::errors::{PrinterError, Result};
+
+/// Codeword size (in bits) by number of layers
+const WORD_SIZE: [usize; 33] = [
+    4, 6, 6, 8, 8, 8, 8, 8, 8, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 10, 12, 12, 12, 12, 12, 12, 12, 12,
+    12, 12,
+];
+
+/// Binary shift maximum length
+const BINARY_SHIFT_MAX_LENGTH: usize = 2047 + 31;
+
+/// Text modes (punctuation and mixed modes are not used)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Upper,
+    Lower,
+    Digit,
+}
+
+/// Bit stream
+#[derive(Debug, Default)]
+struct Bits(Vec<bool>);
+
+impl Bits {
+    fn push(&mut self, value: usize, len: usize) {
+        self.0.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get(&self, i: usize) -> bool {
+        self.0.get(i).copied().unwrap_or_default()
+    }
+}
+
+/// Encode data (upper, lower and digit modes, binary shift for other bytes), preceded by an ECI designator if any
+fn encode_data(data: &[u8], eci: Option<u32>) -> Bits {
+    let mut bits = Bits::default();
+    let mut mode = Mode::Upper;
+    let mut i = 0;
+
+    if let Some(eci) = eci {
+        // P/S, FLG(n) and the n digits of the designator
+        let digits = eci.to_string();
+        bits.push(0, 5);
+        bits.push(0, 5);
+        bits.push(digits.len(), 3);
+        for digit in digits.bytes() {
+            bits.push(usize::from(digit - b'0') + 2, 4);
+        }
+    }
+
+    let code_width = |mode: Mode| if mode == Mode::Digit { 4 } else { 5 };
+
+    while i < data.len() {
+        let c = data[i];
+        match c {
+            b' ' => bits.push(1, code_width(mode)),
+            b'A'..=b'Z' => {
+                match mode {
+                    // U/S
+                    Mode::Lower => bits.push(28, 5),
+                    // U/L
+                    Mode::Digit => {
+                        bits.push(14, 4);
+                        mode = Mode::Upper;
+                    }
+                    Mode::Upper => (),
+                }
+                bits.push(usize::from(c - b'A') + 2, 5);
+            }
+            b'a'..=b'z' => {
+                if mode == Mode::Digit {
+                    bits.push(14, 4);
+                    mode = Mode::Upper;
+                }
+                // L/L
+                if mode == Mode::Upper {
+                    bits.push(28, 5);
+                    mode = Mode::Lower;
+                }
+                bits.push(usize::from(c - b'a') + 2, 5);
+            }
+            b'0'..=b'9' | b',' | b'.' => {
+                // D/L
+                if mode != Mode::Digit {
+                    bits.push(30, 5);
+                    mode = Mode::Digit;
+                }
+                bits.push(
+                    match c {
+                        b',' => 12,
+                        b'.' => 13,
+                        _ => usize::from(c - b'0') + 2,
+                    },
+                    4,
+                );
+            }
+            _ => {
+                if mode == Mode::Digit {
+                    bits.push(14, 4);
+                    mode = Mode::Upper;
+                }
+                let len = data[i..]
+                    .iter()
+                    .take_while(|c| !c.is_ascii_alphanumeric() && !b" ,.".contains(c))
+                    .take(BINARY_SHIFT_MAX_LENGTH)
+                    .count();
+
+                // B/S
+                bits.push(31, 5);
+                if len <= 31 {
+                    bits.push(len, 5);
+                } else {
+                    bits.push(0, 5);
+                    bits.push(len - 31, 11);
+                }
+                for byte in &data[i..i + len] {
+                    bits.push(usize::from(*byte), 8);
+                }
+                i += len;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    bits
+}
+
+/// Total number of bits in the layers
+fn total_bits_in_layers(layers: usize, compact: bool) -> usize {
+    ((if compact { 88 } else { 112 }) + 16 * layers) * layers
+}
+
+/// Split bits into codewords, avoiding all zeros and all ones codewords
+fn stuff_bits(bits: &Bits, word_size: usize) -> Bits {
+    let mut stuffed = Bits::default();
+    let mask = (1 << word_size) - 2;
+    let mut i = 0;
+
+    while i < bits.len() {
+        let mut word = 0;
+        for j in 0..word_size {
+            if i + j >= bits.len() || bits.get(i + j) {
+                word |= 1 << (word_size - 1 - j);
+            }
+        }
+
+        if word & mask == mask {
+            stuffed.push(word & mask, word_size);
+            i += word_size - 1;
+        } else if word & mask == 0 {
+            stuffed.push(word | 1, word_size);
+            i += word_size - 1;
+        } else {
+            stuffed.push(word, word_size);
+            i += word_size;
+        }
+    }
+
+    stuffed
+}
+
+/// Galois field of the codeword size
+fn galois_field(word_size: usize) -> GaloisField {
+    match word_size {
+        4 => GaloisField::new(0x13, 16),
+        6 => GaloisField::new(0x43, 64),
+        8 => GaloisField::new(0x12D, 256),
+        10 => GaloisField::new(0x409, 1024),
+        _ => GaloisField::new(0x1069, 4096),
+    }
+}
+
+/// Add error correction codewords to fill `total_bits` (padded at the start)
+fn add_check_words(bits: &Bits, total_bits: usize, word_size: usize) -> Bits {
+    let message_words = bits.len() / word_size;
+    let total_words = total_bits / word_size;
+
+    let words: Vec<u16> = (0..message_words)
+        .map(|w| (0..word_size).fold(0, |word, j| (word << 1) | u16::from(bits.get(w * word_size + j))))
+        .collect();
+    let check_words = galois_field(word_size).reed_solomon(&words, total_words - message_words);
+
+    let mut message = Bits::default();
+    message.push(0, total_bits % word_size);
+    for word in words.into_iter().chain(check_words) {
+        message.push(usize::from(word), word_size);
+    }
+    message
+}
+
+/// Choose the number of layers and stuff the data bits
+fn symbol_layers(bits: &Bits, mode: AztecMode, correction_level: u8) -> Result<(bool, usize, usize, Bits)> {
+    let ecc_bits = bits.len() * usize::from(correction_level) / 100 + 11;
+    let (compact, candidates) = match mode {
+        AztecMode::Compact(0) => (true, 1..=4),
+        AztecMode::Compact(n) => (true, usize::from(n)..=usize::from(n)),
+        AztecMode::FullRange(0) => (false, 4..=32),
+        AztecMode::FullRange(n) => (false, usize::from(n)..=usize::from(n)),
+    };
+
+    for layers in candidates {
+        let total_bits = total_bits_in_layers(layers, compact);
+        let word_size = WORD_SIZE[layers];
+        let usable_bits = total_bits - total_bits % word_size;
+        let stuffed = stuff_bits(bits, word_size);
+
+        let fits_mode_message = if compact {
+            stuffed.len() <= word_size * 64
+        } else {
+            stuffed.len() <= word_size * 2048
+        };
+        if fits_mode_message && stuffed.len() + ecc_bits <= usable_bits {
+            return Ok((compact, layers, word_size, stuffed));
+        }
+    }
+
+    Err(PrinterError::Input(format!("Aztec code data too long for {mode}")))
+}
+
+/// Mode message (number of layers and data codewords, with its error correction)
+fn mode_message(compact: bool, layers: usize, message_words: usize) -> Bits {
+    let mut bits = Bits::default();
+    if compact {
+        bits.push(layers - 1, 2);
+        bits.push(message_words - 1, 6);
+        add_check_words(&bits, 28, 4)
+    } else {
+        bits.push(layers - 1, 5);
+        bits.push(message_words - 1, 11);
+        add_check_words(&bits, 40, 4)
+    }
+}
+
+/// Square matrix of modules
+struct Matrix {
+    size: usize,
+    modules: Vec<Vec<bool>>,
+}
+
+impl Matrix {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            modules: vec![vec![false; size]; size],
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize) {
+        self.modules[y][x] = true;
+    }
+
+    fn draw_bulls_eye(&mut self, center: usize, size: usize) {
+        for i in (0..size).step_by(2) {
+            for j in center - i..=center + i {
+                self.set(j, center - i);
+                self.set(j, center + i);
+                self.set(center - i, j);
+                self.set(center + i, j);
+            }
+        }
+
+        // Orientation marks
+        self.set(center - size, center - size);
+        self.set(center - size + 1, center - size);
+        self.set(center - size, center - size + 1);
+        self.set(center + size, center - size);
+        self.set(center + size, center - size + 1);
+        self.set(center + size, center + size - 1);
+    }
+
+    fn draw_mode_message(&mut self, compact: bool, message: &Bits) {
+        let center = self.size / 2;
+        if compact {
+            for i in 0..7 {
+                let offset = center - 3 + i;
+                if message.get(i) {
+                    self.set(offset, center - 5);
+                }
+                if message.get(i + 7) {
+                    self.set(center + 5, offset);
+                }
+                if message.get(20 - i) {
+                    self.set(offset, center + 5);
+                }
+                if message.get(27 - i) {
+                    self.set(center - 5, offset);
+                }
+            }
+        } else {
+            for i in 0..10 {
+                let offset = center - 5 + i + i / 5;
+                if message.get(i) {
+                    self.set(offset, center - 7);
+                }
+                if message.get(i + 10) {
+                    self.set(center + 7, offset);
+                }
+                if message.get(29 - i) {
+                    self.set(offset, center + 7);
+                }
+                if message.get(39 - i) {
+                    self.set(center - 7, offset);
+                }
+            }
+        }
+    }
+}
+
+/// Encode data into an Aztec code symbol, `true` for dark modules (Aztec codes have no quiet zone)
+pub(crate) fn aztec_modules(
+    data: &[u8],
+    mode: AztecMode,
+    correction_level: u8,
+    eci: Option<u32>,
+) -> Result<Vec<Vec<bool>>> {
+    let bits = encode_data(data, eci);
+    let (compact, layers, word_size, stuffed) = symbol_layers(&bits, mode, correction_level)?;
+    let message = add_check_words(&stuffed, total_bits_in_layers(layers, compact), word_size);
+    let mode_message = mode_message(compact, layers, stuffed.len() / word_size);
+
+    // Full-range symbols have a reference grid every 16 modules from the center
+    let base_size = (if compact { 11 } else { 14 }) + layers * 4;
+    let (size, alignment) = if compact {
+        (base_size, (0..base_size).collect::<Vec<_>>())
+    } else {
+        let size = base_size + 1 + 2 * ((base_size / 2 - 1) / 15);
+        let (base_center, center) = (base_size / 2, size / 2);
+        let mut alignment = vec![0; base_size];
+        for i in 0..base_center {
+            let offset = i + i / 15;
+            alignment[base_center - i - 1] = center - offset - 1;
+            alignment[base_center + i] = center + offset + 1;
+        }
+        (size, alignment)
+    };
+
+    let mut matrix = Matrix::new(size);
+    let mut row_offset = 0;
+    for i in 0..layers {
+        let row_size = (layers - i) * 4 + if compact { 9 } else { 12 };
+        for j in 0..row_size {
+            let column_offset = j * 2;
+            for k in 0..2 {
+                let bit = |side: usize| message.get(row_offset + row_size * 2 * side + column_offset + k);
+                if bit(0) {
+                    matrix.set(alignment[i * 2 + k], alignment[i * 2 + j]);
+                }
+                if bit(1) {
+                    matrix.set(alignment[i * 2 + j], alignment[base_size - 1 - i * 2 - k]);
+                }
+                if bit(2) {
+                    matrix.set(
+                        alignment[base_size - 1 - i * 2 - k],
+                        alignment[base_size - 1 - i * 2 - j],
+                    );
+                }
+                if bit(3) {
+                    matrix.set(alignment[base_size - 1 - i * 2 - j], alignment[i * 2 + k]);
+                }
+            }
+        }
+        row_offset += row_size * 8;
+    }
+
+    matrix.draw_mode_message(compact, &mode_message);
+    let center = size / 2;
+    if compact {
+        matrix.draw_bulls_eye(center, 5);
+    } else {
+        matrix.draw_bulls_eye(center, 7);
+        for j in (0..base_size / 2 - 1).step_by(15).map(|i| i / 15 * 16) {
+            for k in (center & 1..size).step_by(2) {
+                matrix.set(center - j, k);
+                matrix.set(center + j, k);
+                matrix.set(k, center - j);
+                matrix.set(k, center + j);
+            }
+        }
+    }
+
+    Ok(matrix.modules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(bits: &Bits) -> String {
+        bits.0.iter().map(|b| if *b { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn test_aztec_encode_data() {
+        assert_eq!(bits(&encode_data(b"AB", None)), "0001000011");
+        assert_eq!(bits(&encode_data(b"a", None)), "1110000010");
+        assert_eq!(bits(&encode_data(b"1.", None)), "1111000111101");
+        assert_eq!(bits(&encode_data(b"1A", None)), "111100011111000010");
+        assert_eq!(bits(&encode_data(b"!", None)), "111110000100100001");
+
+        let data = [b'!'; 40];
+        let encoded = encode_data(&data, None);
+        assert_eq!(encoded.len(), 5 + 5 + 11 + 40 * 8);
+        assert_eq!(bits(&encoded)[..21], *"111110000000000001001");
+
+        // P/S FLG(2) 2 6
+        assert_eq!(bits(&encode_data(b"", Some(26))), "000000000001001001000");
+    }
+
+    #[test]
+    fn test_aztec_stuff_bits() {
+        let mut data = Bits::default();
+        data.push(0b0000_0011_1111, 12);
+        assert_eq!(bits(&stuff_bits(&data, 6)), "000001011111111110");
+    }
+
+    #[test]
+    fn test_aztec_layers() {
+        let data = encode_data(b"ABC", None);
+        let (compact, layers, word_size, _) = symbol_layers(&data, AztecMode::Compact(0), 23).unwrap();
+        assert_eq!((compact, layers, word_size), (true, 1, 6));
+
+        let (compact, layers, word_size, _) = symbol_layers(&data, AztecMode::FullRange(0), 23).unwrap();
+        assert_eq!((compact, layers, word_size), (false, 4, 8));
+
+        let data = encode_data(&[b'a'; 60], None);
+        assert!(symbol_layers(&data, AztecMode::Compact(1), 23).is_err());
+        assert_eq!(symbol_layers(&data, AztecMode::Compact(0), 23).unwrap().1, 3);
+        assert!(symbol_layers(&encode_data(&[b'a'; 100], None), AztecMode::Compact(0), 23).is_err());
+    }
+
+    #[test]
+    fn test_aztec_modules() {
+        // Compact: 15x15 with 1 layer
+        let symbol = aztec_modules(b"ABC", AztecMode::Compact(0), 23, None).unwrap();
+        assert_eq!((symbol.len(), symbol[0].len()), (15, 15));
+        // Bull's-eye rings
+        assert!(symbol[7][7] && !symbol[7][6] && symbol[7][5] && !symbol[7][4] && symbol[7][3]);
+        assert!((3..=11).all(|x| symbol[3][x] && symbol[11][x]));
+
+        // Full-range: 31x31 with 4 layers (reference grid on the central row and column)
+        let symbol = aztec_modules(b"ABC", AztecMode::FullRange(0), 23, None).unwrap();
+        assert_eq!(symbol.len(), 31);
+        assert!((1..31).step_by(2).all(|k| symbol[15][k] && symbol[k][15]));
+
+        // Full-range with 2 reference grid lines on each side
+        let symbol = aztec_modules(b"ABC", AztecMode::FullRange(8), 23, None).unwrap();
+        assert_eq!(symbol.len(), 49);
+        assert!((0..49).step_by(2).all(|k| symbol[8][k] && symbol[40][k]));
+    }
+}
@@ -3,6 +3,7 @@
 #![cfg(feature = "barcodes")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+use super::{code128::*, gs1::*};
 use crate::errors::{PrinterError, Result};
 use std::fmt;
 
@@ -10,13 +11,16 @@ const CODE39_VALID_CHARS: [char; 44] = [
     '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '$', '%', '*', '+', '-', '.', '/', 'A', 'B', 'C', 'D', 'E', 'F',
     'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', ' ',
 ];
+const CODE39_CHECK_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
 const CODABAR_VALID_CHARS: [char; 24] = [
     '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'a', 'b', 'c', 'd', '$', '+', '-', '.', '/',
     ':',
 ];
 
-/// Barcode system (function A used)
-#[derive(Debug, Clone, Copy)]
+/// Barcode system
+///
+/// CODE93, CODE128 and GS1-128 use the function B of the `GS k` command, other systems the function A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BarcodeSystem {
     UPCA,
     UPCE,
@@ -25,6 +29,10 @@ pub enum BarcodeSystem {
     CODE39,
     ITF,
     CODABAR,
+    CODE93,
+    CODE128,
+    /// GS1-128 (`m = 74`), data written as element strings: `(01)09501101530003(10)ABC123`
+    GS1128,
 }
 
 impl From<BarcodeSystem> for u8 {
@@ -37,6 +45,9 @@ impl From<BarcodeSystem> for u8 {
             BarcodeSystem::CODE39 => 4,
             BarcodeSystem::ITF => 5,
             BarcodeSystem::CODABAR => 6,
+            BarcodeSystem::CODE93 => 72,
+            BarcodeSystem::CODE128 => 73,
+            BarcodeSystem::GS1128 => 74,
         }
     }
 }
@@ -51,6 +62,9 @@ impl fmt::Display for BarcodeSystem {
             BarcodeSystem::CODE39 => write!(f, "CODE39"),
             BarcodeSystem::ITF => write!(f, "ITF"),
             BarcodeSystem::CODABAR => write!(f, "CODABAR"),
+            BarcodeSystem::CODE93 => write!(f, "CODE93"),
+            BarcodeSystem::CODE128 => write!(f, "CODE128"),
+            BarcodeSystem::GS1128 => write!(f, "GS1-128"),
         }
     }
 }
@@ -213,6 +227,7 @@ pub struct BarcodeOption {
     height: BarcodeHeight,
     font: BarcodeFont,
     position: BarcodePosition,
+    check_digit: bool,
 }
 
 impl Default for BarcodeOption {
@@ -222,6 +237,7 @@ impl Default for BarcodeOption {
             height: BarcodeHeight::default(),
             font: BarcodeFont::A,
             position: BarcodePosition::Below,
+            check_digit: false,
         }
     }
 }
@@ -234,9 +250,18 @@ impl BarcodeOption {
             height,
             font,
             position,
+            check_digit: false,
         }
     }
 
+    /// Append the optional check character to ITF (modulo 10) and CODE39 (modulo 43) data
+    ///
+    /// EAN8, EAN13, UPC-A and UPC-E check digits are mandatory and always computed or verified.
+    pub fn with_check_digit(mut self, check_digit: bool) -> Self {
+        self.check_digit = check_digit;
+        self
+    }
+
     /// Get width
     pub fn width(&self) -> BarcodeWidth {
         self.width
@@ -256,9 +281,20 @@ impl BarcodeOption {
     pub fn position(&self) -> BarcodePosition {
         self.position
     }
+
+    /// Get check digit
+    pub fn check_digit(&self) -> bool {
+        self.check_digit
+    }
 }
 
 /// Barcode
+///
+/// `data` is the data sent to the printer:
+/// - EAN8, EAN13 and UPC-A: the check digit is appended when only the payload is given, verified otherwise
+/// - UPC-E: the 6, 7 or 8 digits zero-suppressed form, or the 11 or 12 digits UPC-A form, normalised to the
+///   8 digits form (number system 0, 6 digits and check digit)
+/// - ITF and CODE39: the check character is appended if enabled in [`BarcodeOption`]
 #[derive(Debug, Clone)]
 pub struct Barcode {
     pub system: BarcodeSystem,
@@ -269,61 +305,71 @@ pub struct Barcode {
 impl Barcode {
     /// Create a new `Barcode`
     pub fn new(system: BarcodeSystem, data: &str, option: BarcodeOption) -> Result<Self> {
-        Self::validate(system, data)?;
+        let data = Self::normalize(system, data, option.check_digit)?;
+
+        Ok(Self { system, data, option })
+    }
 
-        Ok(Self {
-            system,
-            data: data.to_string(),
-            option,
-        })
+    /// Validate data (without the optional ITF and CODE39 check characters)
+    pub fn validate(system: BarcodeSystem, data: &str) -> Result<()> {
+        Self::normalize(system, data, false).map(|_| ())
     }
 
-    /// Validate data
-    fn validate(system: BarcodeSystem, data: &str) -> Result<()> {
+    /// Validate data and get the data sent to the printer
+    fn normalize(system: BarcodeSystem, data: &str, check_digit: bool) -> Result<String> {
         let data_len = data.len();
         let is_data_all_digits = data.chars().all(|c| c.is_ascii_digit());
 
         match system {
-            BarcodeSystem::UPCA => {
-                if is_data_all_digits && [11, 12].contains(&data_len) {
-                    Ok(())
-                } else {
-                    Err(PrinterError::Input(format!("invalid UPC-A data: {data}")))
-                }
-            }
+            BarcodeSystem::UPCA => match data_len {
+                11 | 12 if is_data_all_digits => with_check_digit(system, data, 11),
+                _ => Err(PrinterError::Input(format!("invalid UPC-A data: {data}"))),
+            },
             BarcodeSystem::UPCE => {
-                if is_data_all_digits && [6, 7, 8, 11, 12].contains(&data_len) && data_len == 6
-                    || data.chars().nth(0) == Some('0')
-                {
-                    Ok(())
-                } else {
-                    Err(PrinterError::Input(format!("invalid UPC-E data: {data}")))
-                }
-            }
-            BarcodeSystem::EAN8 => {
-                if is_data_all_digits && [7, 8].contains(&data_len) {
-                    Ok(())
-                } else {
-                    Err(PrinterError::Input(format!("invalid EAN8 data: {data}")))
+                if !is_data_all_digits {
+                    return Err(PrinterError::Input(format!("invalid UPC-E data: {data}")));
                 }
-            }
-            BarcodeSystem::EAN13 => {
-                if is_data_all_digits && [12, 13].contains(&data_len) {
-                    Ok(())
-                } else {
-                    Err(PrinterError::Input(format!("invalid EAN13 data: {data}")))
+                match data_len {
+                    6 => upce_with_check_digit(&format!("0{data}")),
+                    7 | 8 => upce_with_check_digit(data),
+                    11 | 12 => upca_to_upce(data),
+                    _ => Err(PrinterError::Input(format!("invalid UPC-E data: {data}"))),
                 }
+                .and_then(|upce| {
+                    // The printer only supports the number system 0
+                    if upce.starts_with('0') {
+                        Ok(upce)
+                    } else {
+                        Err(PrinterError::Input(format!(
+                            "invalid UPC-E data (number system must be 0): {data}"
+                        )))
+                    }
+                })
             }
+            BarcodeSystem::EAN8 => match data_len {
+                7 | 8 if is_data_all_digits => with_check_digit(system, data, 7),
+                _ => Err(PrinterError::Input(format!("invalid EAN8 data: {data}"))),
+            },
+            BarcodeSystem::EAN13 => match data_len {
+                12 | 13 if is_data_all_digits => with_check_digit(system, data, 12),
+                _ => Err(PrinterError::Input(format!("invalid EAN13 data: {data}"))),
+            },
             BarcodeSystem::ITF => {
-                if data_len >= 2 && is_data_all_digits {
-                    Ok(())
+                if check_digit && data_len >= 1 && data_len % 2 == 1 && is_data_all_digits {
+                    Ok(format!("{data}{}", gs1_check_digit(data)))
+                } else if !check_digit && data_len >= 2 && is_data_all_digits {
+                    Ok(data.to_string())
                 } else {
                     Err(PrinterError::Input(format!("invalid ITF data: {data}")))
                 }
             }
             BarcodeSystem::CODE39 => {
                 if data_len >= 1 && data.chars().all(|c| CODE39_VALID_CHARS.contains(&c)) {
-                    Ok(())
+                    if check_digit {
+                        code39_with_check_character(data)
+                    } else {
+                        Ok(data.to_string())
+                    }
                 } else {
                     Err(PrinterError::Input(format!("invalid CODE39 data: {data}")))
                 }
@@ -331,15 +377,146 @@ impl Barcode {
             BarcodeSystem::CODABAR => {
                 // (However, d1 = 65 – 68, dk = 65 – 68, d1 = 97 – 100, dk = 97 – 100)
                 if data_len >= 2 && data.chars().all(|c| CODABAR_VALID_CHARS.contains(&c)) {
-                    Ok(())
+                    Ok(data.to_string())
                 } else {
                     Err(PrinterError::Input(format!("invalid CODABAR data: {data}")))
                 }
             }
+            BarcodeSystem::CODE93 => {
+                if (1..=255).contains(&data_len) && data.is_ascii() {
+                    Ok(data.to_string())
+                } else {
+                    Err(PrinterError::Input(format!("invalid CODE93 data: {data}")))
+                }
+            }
+            BarcodeSystem::CODE128 => {
+                if code128_printer_data(&code128_encode(data)?).len() <= 255 {
+                    Ok(data.to_string())
+                } else {
+                    Err(PrinterError::Input(format!("CODE128 data too long: {data}")))
+                }
+            }
+            BarcodeSystem::GS1128 => {
+                if gs1_encode(&gs1_parse(data)?, "{1").len() <= 255 {
+                    Ok(data.to_string())
+                } else {
+                    Err(PrinterError::Input(format!("GS1-128 data too long: {data}")))
+                }
+            }
         }
     }
 }
 
+/// Append the check digit to a payload of `payload_len` digits, or verify it
+fn with_check_digit(system: BarcodeSystem, data: &str, payload_len: usize) -> Result<String> {
+    if data.len() == payload_len {
+        Ok(format!("{data}{}", gs1_check_digit(data)))
+    } else if gs1_is_check_digit_valid(data) {
+        Ok(data.to_string())
+    } else {
+        Err(PrinterError::Input(format!("invalid {system} check digit: {data}")))
+    }
+}
+
+/// Append the check digit to UPC-E data, or verify it
+fn upce_with_check_digit(upce: &str) -> Result<String> {
+    let upca = upce_to_upca(upce)?;
+    Ok(format!("{}{}", &upce[..7], &upca[11..]))
+}
+
+/// Append the modulo 43 check character to CODE39 data (before the stop character if any)
+fn code39_with_check_character(data: &str) -> Result<String> {
+    let (payload, stop) = match data.strip_suffix('*') {
+        Some(payload) if data.len() > 1 => (payload, "*"),
+        _ => (data, ""),
+    };
+    let sum = payload
+        .chars()
+        .filter(|&c| c != '*')
+        .map(|c| CODE39_CHECK_CHARS.find(c))
+        .sum::<Option<usize>>()
+        .ok_or_else(|| PrinterError::Input(format!("invalid CODE39 data: {data}")))?;
+    let check = CODE39_CHECK_CHARS.as_bytes()[sum % 43] as char;
+
+    Ok(format!("{payload}{check}{stop}"))
+}
+
+/// Expand UPC-E data to UPC-A data
+///
+/// `upce` is the 7 digits zero-suppressed form (number system and 6 digits) or the 8 digits form (with the
+/// check digit, which is verified). The result is the 12 digits UPC-A form with its check digit.
+///
+/// ```
+/// use escpos::utils::upce_to_upca;
+///
+/// assert_eq!(upce_to_upca("0425261").unwrap(), "042100005264");
+/// ```
+pub fn upce_to_upca(upce: &str) -> Result<String> {
+    let invalid = || PrinterError::Input(format!("invalid UPC-E data: {upce}"));
+
+    if ![7, 8].contains(&upce.len()) || !upce.bytes().all(|c| c.is_ascii_digit()) || !upce.starts_with(['0', '1']) {
+        return Err(invalid());
+    }
+
+    let (ns, d) = (&upce[..1], &upce[1..7]);
+    let payload = match d.as_bytes()[5] {
+        b'0'..=b'2' => format!("{ns}{}{}0000{}", &d[..2], &d[5..], &d[2..5]),
+        b'3' => format!("{ns}{}00000{}", &d[..3], &d[3..5]),
+        b'4' => format!("{ns}{}00000{}", &d[..4], &d[4..5]),
+        _ => format!("{ns}{}0000{}", &d[..5], &d[5..]),
+    };
+    let upca = format!("{payload}{}", gs1_check_digit(&payload));
+
+    if upce.len() == 8 && upce[7..] != upca[11..] {
+        return Err(PrinterError::Input(format!("invalid UPC-E check digit: {upce}")));
+    }
+
+    Ok(upca)
+}
+
+/// Compress UPC-A data to UPC-E data
+///
+/// `upca` is the 11 digits form (the check digit is computed) or the 12 digits form (the check digit is
+/// verified). The result is the 8 digits UPC-E form (number system, 6 digits and check digit).
+/// Only UPC-A codes with number system 0 or 1 and enough zeros can be compressed.
+///
+/// ```
+/// use escpos::utils::upca_to_upce;
+///
+/// assert_eq!(upca_to_upce("042100005264").unwrap(), "04252614");
+/// assert!(upca_to_upce("012345678905").is_err());
+/// ```
+pub fn upca_to_upce(upca: &str) -> Result<String> {
+    let upca = match upca.len() {
+        11 | 12 if upca.bytes().all(|c| c.is_ascii_digit()) => with_check_digit(BarcodeSystem::UPCA, upca, 11)?,
+        _ => return Err(PrinterError::Input(format!("invalid UPC-A data: {upca}"))),
+    };
+
+    let (ns, manufacturer, product, check) = (&upca[..1], &upca[1..6], &upca[6..11], &upca[11..]);
+    let digits = if !["0", "1"].contains(&ns) {
+        None
+    } else if ["000", "100", "200"].contains(&&manufacturer[2..]) && product.starts_with("00") {
+        Some(format!(
+            "{}{}{}",
+            &manufacturer[..2],
+            &product[2..],
+            &manufacturer[2..3]
+        ))
+    } else if manufacturer.ends_with("00") && product.starts_with("000") {
+        Some(format!("{}{}3", &manufacturer[..3], &product[3..]))
+    } else if manufacturer.ends_with('0') && product.starts_with("0000") {
+        Some(format!("{}{}4", &manufacturer[..4], &product[4..]))
+    } else if product.starts_with("0000") && product.as_bytes()[4] >= b'5' {
+        Some(format!("{manufacturer}{}", &product[4..]))
+    } else {
+        None
+    };
+
+    digits
+        .map(|digits| format!("{ns}{digits}{check}"))
+        .ok_or_else(|| PrinterError::Input(format!("UPC-A data cannot be compressed to UPC-E: {upca}")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,47 +533,118 @@ mod tests {
     }
 
     #[test]
-    fn test_barcode_validate_upca() {
-        assert!(Barcode::validate(BarcodeSystem::UPCA, "12587965874").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::UPCA, "125879658746").is_ok());
-
-        assert!(Barcode::validate(BarcodeSystem::UPCA, "1258796587").is_err());
-        assert!(Barcode::validate(BarcodeSystem::UPCA, "1258796587000").is_err());
-        assert!(Barcode::validate(BarcodeSystem::UPCA, "1d8796587000").is_err());
+    fn test_barcode_normalize_upca() {
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCA, "12587965874", false).unwrap(),
+            "125879658746"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCA, "125879658746", false).unwrap(),
+            "125879658746"
+        );
+
+        assert!(Barcode::normalize(BarcodeSystem::UPCA, "125879658745", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCA, "1258796587", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCA, "1258796587000", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCA, "1d8796587000", false).is_err());
     }
 
     #[test]
-    fn test_barcode_validate_upce() {
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "02587965874").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "025879658746").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "02980547").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "985487").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "085487").is_ok());
-
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "1f2-58").is_err());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "9805874").is_err());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "92587965874").is_err());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "925879658746").is_err());
-        assert!(Barcode::validate(BarcodeSystem::UPCE, "92980547").is_err());
+    fn test_barcode_normalize_upce() {
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCE, "02980000005", false).unwrap(),
+            "02980538"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCE, "029800000058", false).unwrap(),
+            "02980538"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCE, "02980548", false).unwrap(),
+            "02980548"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCE, "0298054", false).unwrap(),
+            "02980548"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCE, "985487", false).unwrap(),
+            "09854871"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::UPCE, "085487", false).unwrap(),
+            "00854870"
+        );
+
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "1f2-58", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "9805874", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "1298054", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "02980547", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "02587965874", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "029800000057", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "92587965874", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::UPCE, "0298054800", false).is_err());
     }
 
     #[test]
-    fn test_barcode_validate_ean8() {
-        assert!(Barcode::validate(BarcodeSystem::EAN8, "0325874").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::EAN8, "98574587").is_ok());
+    fn test_upce_to_upca() {
+        assert_eq!(upce_to_upca("0425261").unwrap(), "042100005264");
+        assert_eq!(upce_to_upca("0120453").unwrap(), "012000000454");
+        assert_eq!(upce_to_upca("0123454").unwrap(), "012340000053");
+        assert_eq!(upce_to_upca("0123457").unwrap(), "012345000072");
+        assert_eq!(upce_to_upca("01234572").unwrap(), "012345000072");
+
+        assert!(upce_to_upca("01234571").is_err());
+        assert!(upce_to_upca("2123457").is_err());
+        assert!(upce_to_upca("012345").is_err());
+        assert!(upce_to_upca("01a3457").is_err());
+    }
 
-        assert!(Barcode::validate(BarcodeSystem::EAN8, "5g47u29").is_err());
-        assert!(Barcode::validate(BarcodeSystem::EAN8, "980587407").is_err());
+    #[test]
+    fn test_upca_to_upce() {
+        assert_eq!(upca_to_upce("04210000526").unwrap(), "04252614");
+        assert_eq!(upca_to_upce("012000000454").unwrap(), "01204504");
+        assert_eq!(upca_to_upce("01230000045").unwrap(), "01234531");
+        assert_eq!(upca_to_upce("01234000005").unwrap(), "01234543");
+        assert_eq!(upca_to_upce("012345000072").unwrap(), "01234572");
+
+        assert!(upca_to_upce("012345000071").is_err());
+        assert!(upca_to_upce("012345678905").is_err());
+        assert!(upca_to_upce("21234500007").is_err());
+        assert!(upca_to_upce("0123450000").is_err());
     }
 
     #[test]
-    fn test_barcode_validate_ean13() {
-        assert!(Barcode::validate(BarcodeSystem::EAN13, "012403258746").is_ok());
-        assert!(Barcode::validate(BarcodeSystem::EAN13, "0124032587468").is_ok());
+    fn test_barcode_normalize_ean8() {
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::EAN8, "9857458", false).unwrap(),
+            "98574582"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::EAN8, "98574582", false).unwrap(),
+            "98574582"
+        );
+
+        assert!(Barcode::normalize(BarcodeSystem::EAN8, "98574587", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::EAN8, "5g47u29", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::EAN8, "980587407", false).is_err());
+    }
 
-        assert!(Barcode::validate(BarcodeSystem::EAN13, "01240325874").is_err());
-        assert!(Barcode::validate(BarcodeSystem::EAN13, "98058740701009").is_err());
-        assert!(Barcode::validate(BarcodeSystem::EAN13, "9805874070s09").is_err());
+    #[test]
+    fn test_barcode_normalize_ean13() {
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::EAN13, "012403258746", false).unwrap(),
+            "0124032587466"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::EAN13, "0124032587466", false).unwrap(),
+            "0124032587466"
+        );
+
+        assert!(Barcode::normalize(BarcodeSystem::EAN13, "0124032587462", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::EAN13, "01240325874", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::EAN13, "98058740701009", false).is_err());
+        assert!(Barcode::normalize(BarcodeSystem::EAN13, "9805874070s09", false).is_err());
     }
 
     #[test]
@@ -412,6 +660,16 @@ mod tests {
         assert!(Barcode::validate(BarcodeSystem::ITF, "98f874d0d70s09").is_err());
     }
 
+    #[test]
+    fn test_barcode_normalize_itf_check_character() {
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::ITF, "1540014128876", true).unwrap(),
+            "15400141288763"
+        );
+        assert_eq!(Barcode::normalize(BarcodeSystem::ITF, "3", true).unwrap(), "31");
+        assert!(Barcode::normalize(BarcodeSystem::ITF, "01", true).is_err());
+    }
+
     #[test]
     fn test_barcode_validate_code39() {
         assert!(Barcode::validate(BarcodeSystem::CODE39, "3").is_ok());
@@ -425,6 +683,18 @@ mod tests {
         assert!(Barcode::validate(BarcodeSystem::CODE39, "98f874d0d70s09").is_err());
     }
 
+    #[test]
+    fn test_barcode_normalize_code39_check_character() {
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::CODE39, "CODE39", true).unwrap(),
+            "CODE39W"
+        );
+        assert_eq!(
+            Barcode::normalize(BarcodeSystem::CODE39, "*ABC*", true).unwrap(),
+            "*ABCX*"
+        );
+    }
+
     #[test]
     fn test_barcode_validate_codabar() {
         assert!(Barcode::validate(BarcodeSystem::CODABAR, "01").is_ok());
@@ -437,4 +707,37 @@ mod tests {
         assert!(Barcode::validate(BarcodeSystem::CODABAR, "9805f8740701009").is_err());
         assert!(Barcode::validate(BarcodeSystem::CODABAR, "98f874d0d70s09").is_err());
     }
+
+    #[test]
+    fn test_barcode_validate_code128() {
+        assert!(Barcode::validate(BarcodeSystem::CODE128, "Hello 123").is_ok());
+        assert!(Barcode::validate(BarcodeSystem::CODE128, "{10112345678901").is_ok());
+        assert!(Barcode::validate(BarcodeSystem::CODE128, "{BAB{C1234").is_ok());
+        assert!(Barcode::validate(BarcodeSystem::CODE128, &"1".repeat(500)).is_ok());
+
+        assert!(Barcode::validate(BarcodeSystem::CODE128, "").is_err());
+        assert!(Barcode::validate(BarcodeSystem::CODE128, "Café").is_err());
+        assert!(Barcode::validate(BarcodeSystem::CODE128, "{C123").is_err());
+        assert!(Barcode::validate(BarcodeSystem::CODE128, &"a".repeat(300)).is_err());
+    }
+
+    #[test]
+    fn test_barcode_validate_code93() {
+        assert!(Barcode::validate(BarcodeSystem::CODE93, "A").is_ok());
+        assert!(Barcode::validate(BarcodeSystem::CODE93, "Code 93\t$").is_ok());
+
+        assert!(Barcode::validate(BarcodeSystem::CODE93, "").is_err());
+        assert!(Barcode::validate(BarcodeSystem::CODE93, "Café").is_err());
+        assert!(Barcode::validate(BarcodeSystem::CODE93, &"A".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn test_barcode_validate_gs1_128() {
+        assert!(Barcode::validate(BarcodeSystem::GS1128, "(01)09501101530003").is_ok());
+        assert!(Barcode::validate(BarcodeSystem::GS1128, "(01)09501101530003(17)250101(10)ABC123").is_ok());
+
+        assert!(Barcode::validate(BarcodeSystem::GS1128, "").is_err());
+        assert!(Barcode::validate(BarcodeSystem::GS1128, "0109501101530003").is_err());
+        assert!(Barcode::validate(BarcodeSystem::GS1128, "(01)0950110153").is_err());
+    }
 }
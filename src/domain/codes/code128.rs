@@ -0,0 +1,419 @@
+//! CODE128
+//!
+//! Data can contain control sequences (same syntax as the ESC/POS `GS k` command):
+//! - `{A`, `{B`, `{C`: select code set A, B or C (all the code sets must then be explicit)
+//! - `{S`: shift the next character between code sets A and B
+//! - `{1`, `{2`, `{3`, `{4`: function characters FNC1 to FNC4
+//! - `{{`: `{` character
+//!
+//! Without explicit code set, the shortest encoding is automatically chosen.
+
+#![cfg(feature = "barcodes")]
+
+use crate::errors::{PrinterError, Result};
+
+/// CODE128 code set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Code128Set {
+    A,
+    B,
+    C,
+}
+
+impl Code128Set {
+    /// Can the character be encoded in the code set (A or B)?
+    fn can_encode(&self, c: u8) -> bool {
+        match self {
+            Code128Set::A => c < 96,
+            Code128Set::B => (32..128).contains(&c),
+            Code128Set::C => false,
+        }
+    }
+
+    /// Get the other code set reachable with a shift
+    fn shifted(&self) -> Option<Self> {
+        match self {
+            Code128Set::A => Some(Code128Set::B),
+            Code128Set::B => Some(Code128Set::A),
+            Code128Set::C => None,
+        }
+    }
+
+    /// Get the code set selector (`{A`, `{B` or `{C`)
+    fn selector(&self) -> u8 {
+        match self {
+            Code128Set::A => b'A',
+            Code128Set::B => b'B',
+            Code128Set::C => b'C',
+        }
+    }
+}
+
+/// CODE128 encoding step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Code128Op {
+    /// Start with or switch to a code set
+    Set(Code128Set),
+    /// Shift the next character between code sets A and B
+    Shift,
+    /// Character in code set A or B
+    Char(u8),
+    /// Pair of digits in code set C (0 - 99)
+    Digits(u8),
+    /// Function character (FNC1 to FNC4)
+    Fnc(u8),
+}
+
+/// Input data token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Char(u8),
+    Fnc(u8),
+    Set(Code128Set),
+    Shift,
+}
+
+/// Split data into characters and control sequences
+fn tokenize(data: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::with_capacity(data.len());
+    let mut chars = data.chars();
+
+    while let Some(c) = chars.next() {
+        if !c.is_ascii() {
+            return Err(PrinterError::Input(format!("invalid CODE128 character: {c}")));
+        }
+
+        let token = match c {
+            '{' => match chars.next() {
+                Some('A') => Token::Set(Code128Set::A),
+                Some('B') => Token::Set(Code128Set::B),
+                Some('C') => Token::Set(Code128Set::C),
+                Some('S') => Token::Shift,
+                Some(n @ '1'..='4') => Token::Fnc(n as u8 - b'0'),
+                Some('{') => Token::Char(b'{'),
+                Some(c) => return Err(PrinterError::Input(format!("invalid CODE128 control sequence: {{{c}"))),
+                None => return Err(PrinterError::Input("incomplete CODE128 control sequence".to_owned())),
+            },
+            c => Token::Char(c as u8),
+        };
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Get the encoding of the token at `index` in a code set, if possible
+fn encode_token(tokens: &[Token], index: usize, set: Code128Set) -> Option<Code128Op> {
+    match (tokens[index], set) {
+        (Token::Fnc(1), _) => Some(Code128Op::Fnc(1)),
+        (Token::Fnc(n), Code128Set::A | Code128Set::B) => Some(Code128Op::Fnc(n)),
+        (Token::Char(c), Code128Set::A | Code128Set::B) if set.can_encode(c) => Some(Code128Op::Char(c)),
+        (Token::Char(high), Code128Set::C) if high.is_ascii_digit() => match tokens.get(index + 1) {
+            Some(Token::Char(low)) if low.is_ascii_digit() => Some(Code128Op::Digits((high - b'0') * 10 + low - b'0')),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Number of tokens used by an operation
+fn op_len(op: Code128Op) -> usize {
+    match op {
+        Code128Op::Digits(_) => 2,
+        _ => 1,
+    }
+}
+
+/// Choose the shortest encoding
+fn encode_auto(tokens: &[Token]) -> Vec<Code128Op> {
+    const SETS: [Code128Set; 3] = [Code128Set::B, Code128Set::C, Code128Set::A];
+
+    /// Step chosen at a position for a current code set
+    #[derive(Clone, Copy)]
+    enum Step {
+        Encode(Code128Set, Code128Op),
+        Shift(u8),
+    }
+
+    let len = tokens.len();
+    let index = |set: Code128Set| SETS.iter().position(|s| *s == set).unwrap_or_default();
+
+    // costs[i][s]: number of symbol characters needed to encode tokens[i..] starting in code set s
+    let mut costs = vec![[0usize; 3]; len + 1];
+    let mut steps: Vec<[Option<Step>; 3]> = vec![[None; 3]; len];
+
+    for i in (0..len).rev() {
+        for current in SETS {
+            let mut best: Option<(usize, Step)> = None;
+
+            for target in SETS {
+                if let Some(op) = encode_token(tokens, i, target) {
+                    let switch = usize::from(target != current);
+                    let cost = switch + 1 + costs[i + op_len(op)][index(target)];
+                    // On equal cost, stay in the current code set
+                    let better = best.is_none_or(|(best, _)| cost < best || (cost == best && switch == 0));
+                    if better {
+                        best = Some((cost, Step::Encode(target, op)));
+                    }
+                }
+            }
+
+            if let (Some(shifted), Token::Char(c)) = (current.shifted(), tokens[i]) {
+                if shifted.can_encode(c) {
+                    let cost = 2 + costs[i + 1][index(current)];
+                    if best.is_none_or(|(best, _)| cost < best) {
+                        best = Some((cost, Step::Shift(c)));
+                    }
+                }
+            }
+
+            // Every ASCII character can be encoded in code set A or B
+            let (cost, step) = best.unwrap_or((usize::MAX / 2, Step::Shift(0)));
+            costs[i][index(current)] = cost;
+            steps[i][index(current)] = Some(step);
+        }
+    }
+
+    // On equal cost, start with code set C (usual for GS1-128)
+    let mut current = [Code128Set::C, Code128Set::B, Code128Set::A]
+        .into_iter()
+        .min_by_key(|set| costs[0][index(*set)])
+        .unwrap_or(Code128Set::B);
+
+    let mut ops = vec![Code128Op::Set(current)];
+    let mut i = 0;
+    while i < len {
+        match steps[i][index(current)] {
+            Some(Step::Encode(set, op)) => {
+                if set != current {
+                    ops.push(Code128Op::Set(set));
+                    current = set;
+                }
+                ops.push(op);
+                i += op_len(op);
+            }
+            Some(Step::Shift(c)) => {
+                ops.push(Code128Op::Shift);
+                ops.push(Code128Op::Char(c));
+                i += 1;
+            }
+            None => break,
+        }
+    }
+
+    ops
+}
+
+/// Check an encoding with explicit code sets
+fn encode_manual(tokens: &[Token]) -> Result<Vec<Code128Op>> {
+    let mut current = match tokens.first() {
+        Some(Token::Set(set)) => *set,
+        _ => {
+            return Err(PrinterError::Input(
+                "CODE128 data must start with a code set ({A, {B or {C) when code sets are explicit".to_owned(),
+            ))
+        }
+    };
+
+    let mut ops = vec![Code128Op::Set(current)];
+    let mut i = 1;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Set(set) => {
+                ops.push(Code128Op::Set(set));
+                current = set;
+                i += 1;
+            }
+            Token::Shift => {
+                let shifted = current.shifted();
+                match (shifted, tokens.get(i + 1)) {
+                    (Some(shifted), Some(Token::Char(c))) if shifted.can_encode(*c) => {
+                        ops.push(Code128Op::Shift);
+                        ops.push(Code128Op::Char(*c));
+                        i += 2;
+                    }
+                    _ => return Err(PrinterError::Input("invalid CODE128 shift".to_owned())),
+                }
+            }
+            _ => {
+                let op = encode_token(tokens, i, current).ok_or_else(|| {
+                    PrinterError::Input(match tokens[i] {
+                        Token::Char(c) => format!("invalid character in CODE128 code set {current:?}: {}", c as char),
+                        _ => format!("invalid function character in CODE128 code set {current:?}"),
+                    })
+                })?;
+                ops.push(op);
+                i += op_len(op);
+            }
+        }
+    }
+
+    Ok(ops)
+}
+
+/// Encode CODE128 data
+pub(crate) fn code128_encode(data: &str) -> Result<Vec<Code128Op>> {
+    let tokens = tokenize(data)?;
+    if !tokens.iter().any(|t| matches!(t, Token::Char(_))) {
+        return Err(PrinterError::Input(format!("invalid CODE128 data: {data}")));
+    }
+
+    if tokens.iter().any(|t| matches!(t, Token::Set(_) | Token::Shift)) {
+        encode_manual(&tokens)
+    } else {
+        Ok(encode_auto(&tokens))
+    }
+}
+
+/// Get the data sent to the printer (`GS k` function B)
+pub(crate) fn code128_printer_data(ops: &[Code128Op]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(ops.len() + 2);
+
+    for op in ops {
+        match op {
+            Code128Op::Set(set) => data.extend([b'{', set.selector()]),
+            Code128Op::Shift => data.extend(b"{S"),
+            Code128Op::Char(b'{') => data.extend(b"{{"),
+            Code128Op::Char(c) | Code128Op::Digits(c) => data.push(*c),
+            Code128Op::Fnc(n) => data.extend([b'{', b'0' + n]),
+        }
+    }
+
+    data
+}
+
+/// Get the symbol character values (start character, data, check character and stop character)
+#[cfg(feature = "graphics")]
+pub(crate) fn code128_symbol_values(ops: &[Code128Op]) -> Vec<u8> {
+    let mut values = Vec::with_capacity(ops.len() + 2);
+    let mut current = Code128Set::B;
+    let mut shifted = false;
+
+    for op in ops {
+        let set = match (shifted, current.shifted()) {
+            (true, Some(set)) => set,
+            _ => current,
+        };
+        shifted = false;
+
+        match op {
+            Code128Op::Set(target) if values.is_empty() => {
+                values.push(match target {
+                    Code128Set::A => 103,
+                    Code128Set::B => 104,
+                    Code128Set::C => 105,
+                });
+                current = *target;
+            }
+            Code128Op::Set(target) => {
+                values.push(match target {
+                    Code128Set::A => 101,
+                    Code128Set::B => 100,
+                    Code128Set::C => 99,
+                });
+                current = *target;
+            }
+            Code128Op::Shift => {
+                values.push(98);
+                shifted = true;
+            }
+            Code128Op::Char(c) if set == Code128Set::A && *c < 32 => values.push(c + 64),
+            Code128Op::Char(c) => values.push(c - 32),
+            Code128Op::Digits(n) => values.push(*n),
+            Code128Op::Fnc(1) => values.push(102),
+            Code128Op::Fnc(2) => values.push(97),
+            Code128Op::Fnc(3) => values.push(96),
+            Code128Op::Fnc(_) if set == Code128Set::A => values.push(101),
+            Code128Op::Fnc(_) => values.push(100),
+        }
+    }
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| usize::from(*v) * i.max(1))
+        .sum::<usize>()
+        % 103;
+    values.push(checksum as u8);
+    values.push(106);
+
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(data: &str) -> Vec<u8> {
+        code128_printer_data(&code128_encode(data).unwrap())
+    }
+
+    #[test]
+    fn test_code128_invalid() {
+        assert!(code128_encode("").is_err());
+        assert!(code128_encode("{1").is_err());
+        assert!(code128_encode("é").is_err());
+        assert!(code128_encode("AB{").is_err());
+        assert!(code128_encode("AB{X").is_err());
+        assert!(code128_encode("AB{C12").is_err());
+        assert!(code128_encode("{C123").is_err());
+        assert!(code128_encode("{Cab").is_err());
+        assert!(code128_encode("{Aab").is_err());
+        assert!(code128_encode("{C{212").is_err());
+        assert!(code128_encode("{B{Sa").is_err());
+    }
+
+    #[test]
+    fn test_code128_auto() {
+        assert_eq!(encode("Hello"), b"{BHello".to_vec());
+        assert_eq!(encode("123456"), vec![b'{', b'C', 12, 34, 56]);
+        assert_eq!(encode("12"), vec![b'{', b'C', 12]);
+        assert_eq!(encode("AB{{"), b"{BAB{{".to_vec());
+
+        // Digits run long enough to switch to code set C
+        assert_eq!(encode("AB123456"), vec![b'{', b'B', b'A', b'B', b'{', b'C', 12, 34, 56]);
+        assert_eq!(encode("AB1234"), vec![b'{', b'B', b'A', b'B', b'{', b'C', 12, 34]);
+        assert_eq!(encode("AB12"), b"{BAB12".to_vec());
+
+        // Odd number of digits
+        assert_eq!(encode("12345"), vec![b'{', b'C', 12, 34, b'{', b'B', b'5']);
+
+        // Control characters
+        assert_eq!(encode("\tA"), b"{A\tA".to_vec());
+        assert_eq!(encode("ab\tcd"), b"{Bab{S\tcd".to_vec());
+        assert_eq!(encode("\t\ta\t\t"), b"{A\t\t{Sa\t\t".to_vec());
+    }
+
+    #[test]
+    fn test_code128_auto_fnc() {
+        assert_eq!(
+            encode("{10112345678901"),
+            vec![b'{', b'C', b'{', b'1', 1, 12, 34, 56, 78, 90, b'{', b'B', b'1']
+        );
+        assert_eq!(encode("A{4B"), b"{BA{4B".to_vec());
+        assert_eq!(
+            encode("12{212"),
+            vec![b'{', b'C', 12, b'{', b'B', b'{', b'2', b'1', b'2']
+        );
+    }
+
+    #[test]
+    fn test_code128_manual() {
+        assert_eq!(encode("{BHello"), b"{BHello".to_vec());
+        assert_eq!(encode("{C1234{BA"), vec![b'{', b'C', 12, 34, b'{', b'B', b'A']);
+        assert_eq!(encode("{A\t{Sa"), b"{A\t{Sa".to_vec());
+        assert_eq!(encode("{C{10112"), vec![b'{', b'C', b'{', b'1', 1, 12]);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn test_code128_symbol_values() {
+        let values = |data: &str| code128_symbol_values(&code128_encode(data).unwrap());
+
+        assert_eq!(values("PJJ123C"), vec![104, 48, 42, 42, 17, 18, 19, 35, 55, 106]);
+        assert_eq!(values("123456"), vec![105, 12, 34, 56, 44, 106]);
+        assert_eq!(values("\tA"), vec![103, 73, 33, 36, 106]);
+        assert_eq!(values("ab\tcd"), vec![104, 65, 66, 98, 73, 67, 68, 85, 106]);
+        assert_eq!(values("{10112"), vec![105, 102, 1, 12, 39, 106]);
+    }
+}
@@ -0,0 +1,439 @@
+//! GS1 Composite symbology
+//!
+//! A composite symbol is a linear element (EAN/UPC, GS1 DataBar or GS1-128) with a 2D composite element
+//! (CC-A, CC-B or CC-C) printed above it. The printer selects the smallest 2D component fitting the data.
+
+#![cfg(feature = "codes_2d")]
+
+use super::gs1::*;
+use crate::errors::{PrinterError, Result};
+use std::fmt;
+
+/// Composite linear element type
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum CompositeLinearType {
+    Ean8,
+    #[default]
+    Ean13,
+    UpcA,
+    UpcE,
+    GS1DataBarOmnidirectional,
+    GS1DataBarTruncated,
+    GS1DataBarStacked,
+    GS1DataBarStackedOmnidirectional,
+    GS1DataBarLimited,
+    GS1DataBarExpanded,
+    GS1DataBarExpandedStacked,
+    GS1128,
+}
+
+impl fmt::Display for CompositeLinearType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompositeLinearType::Ean8 => write!(f, "EAN8"),
+            CompositeLinearType::Ean13 => write!(f, "EAN13"),
+            CompositeLinearType::UpcA => write!(f, "UPC-A"),
+            CompositeLinearType::UpcE => write!(f, "UPC-E"),
+            CompositeLinearType::GS1DataBarOmnidirectional => write!(f, "GS1 DataBar Omnidirectional"),
+            CompositeLinearType::GS1DataBarTruncated => write!(f, "GS1 DataBar Truncated"),
+            CompositeLinearType::GS1DataBarStacked => write!(f, "GS1 DataBar Stacked"),
+            CompositeLinearType::GS1DataBarStackedOmnidirectional => {
+                write!(f, "GS1 DataBar Stacked Omnidirectional")
+            }
+            CompositeLinearType::GS1DataBarLimited => write!(f, "GS1 DataBar Limited"),
+            CompositeLinearType::GS1DataBarExpanded => write!(f, "GS1 DataBar Expanded"),
+            CompositeLinearType::GS1DataBarExpandedStacked => write!(f, "GS1 DataBar Expanded Stacked"),
+            CompositeLinearType::GS1128 => write!(f, "GS1-128"),
+        }
+    }
+}
+
+impl CompositeLinearType {
+    /// Get the largest 2D component which can be printed with the linear element
+    ///
+    /// CC-C is only used with GS1-128.
+    pub fn max_component(&self) -> CompositeComponent {
+        match self {
+            CompositeLinearType::GS1128 => CompositeComponent::CcC,
+            _ => CompositeComponent::CcB,
+        }
+    }
+
+    /// Check linear element data and get the element type and the data sent to the printer
+    ///
+    /// - EAN8, EAN13 and UPC-A: digits with or without check digit (verified when present)
+    /// - UPC-E: 6 digits (number system 0), or the 11 or 12 digits UPC-A form
+    /// - GS1 DataBar (except Expanded): GTIN of 13 digits, or 14 digits with a valid check digit
+    /// - GS1 DataBar Expanded and GS1-128: element strings with application identifiers between parentheses
+    pub(crate) fn printer_data(&self, data: &str) -> Result<(u8, String)> {
+        let invalid = || PrinterError::Input(format!("invalid {self} composite linear data: {data}"));
+        let is_data_all_digits = !data.is_empty() && data.chars().all(|c| c.is_ascii_digit());
+        let digits = |payload_len: usize| match data.len() {
+            len if is_data_all_digits && len == payload_len => Ok(data.to_string()),
+            len if is_data_all_digits && len == payload_len + 1 && gs1_is_check_digit_valid(data) => {
+                Ok(data[..payload_len].to_string())
+            }
+            _ => Err(invalid()),
+        };
+        let gtin = || gs1_gtin_data(data).map(str::to_string).ok_or_else(invalid);
+        let element_strings = || {
+            let data = gs1_encode(&gs1_parse(data)?, "{1");
+            match data.len() {
+                0..=255 => Ok(data),
+                _ => Err(PrinterError::Input(format!(
+                    "{self} composite linear data too long: {data}"
+                ))),
+            }
+        };
+
+        match self {
+            CompositeLinearType::Ean8 => Ok((65, digits(7)?)),
+            CompositeLinearType::Ean13 => Ok((66, digits(12)?)),
+            CompositeLinearType::UpcA => Ok((67, digits(11)?)),
+            CompositeLinearType::UpcE => match data.len() {
+                6 if is_data_all_digits => Ok((68, data.to_string())),
+                _ => Ok((69, digits(11)?)),
+            },
+            CompositeLinearType::GS1DataBarOmnidirectional => Ok((70, gtin()?)),
+            CompositeLinearType::GS1DataBarTruncated => Ok((71, gtin()?)),
+            CompositeLinearType::GS1DataBarStacked => Ok((72, gtin()?)),
+            CompositeLinearType::GS1DataBarStackedOmnidirectional => Ok((73, gtin()?)),
+            CompositeLinearType::GS1DataBarLimited => {
+                // Limited type only encodes GTIN with indicator digit 0 or 1
+                let gtin = gtin()?;
+                match gtin.starts_with(['0', '1']) {
+                    true => Ok((74, gtin)),
+                    false => Err(invalid()),
+                }
+            }
+            CompositeLinearType::GS1DataBarExpanded => Ok((75, element_strings()?)),
+            CompositeLinearType::GS1DataBarExpandedStacked => Ok((76, element_strings()?)),
+            CompositeLinearType::GS1128 => Ok((77, element_strings()?)),
+        }
+    }
+}
+
+/// Composite 2D component
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompositeComponent {
+    /// CC-A (up to 56 digits)
+    CcA,
+    /// CC-B (up to 338 digits)
+    CcB,
+    /// CC-C (up to 2361 digits, GS1-128 only)
+    CcC,
+}
+
+impl fmt::Display for CompositeComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompositeComponent::CcA => write!(f, "CC-A"),
+            CompositeComponent::CcB => write!(f, "CC-B"),
+            CompositeComponent::CcC => write!(f, "CC-C"),
+        }
+    }
+}
+
+impl CompositeComponent {
+    /// Maximum number of digits
+    pub fn max_digits(&self) -> usize {
+        match self {
+            CompositeComponent::CcA => 56,
+            CompositeComponent::CcB => 338,
+            CompositeComponent::CcC => 2361,
+        }
+    }
+
+    /// Capacity in bits of the general purpose data (numeric data is encoded with 7 bits per pair of digits)
+    fn capacity_bits(&self) -> usize {
+        self.max_digits() * 7 / 2
+    }
+}
+
+/// Estimate the number of bits of composite element data (encoded element strings with `{1` FNC1 separators)
+///
+/// Digits are encoded in numeric mode (3.5 bits), upper case letters and some symbols in alphanumeric mode
+/// (6 bits), other characters in ISO/IEC 646 mode (8 bits). Mode latches are counted when the mode changes.
+fn composite_data_bits(data: &str) -> usize {
+    let mut half_bits: usize = 0;
+    let mut mode = 0;
+    for c in data.replace("{1", "\x1D").chars() {
+        let (char_mode, bits) = match c {
+            '0'..='9' | '\x1D' => (0, 7),
+            'A'..='Z' | '*' | ',' | '-' | '.' | '/' => (1, 12),
+            _ => (2, 16),
+        };
+        if char_mode != mode {
+            // Latch (4 or 5 bits)
+            half_bits += 10;
+            mode = char_mode;
+        }
+        half_bits += bits;
+    }
+    half_bits.div_ceil(2)
+}
+
+/// Composite HRI font
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum CompositeFont {
+    /// HRI characters are not printed
+    None,
+    #[default]
+    A,
+    B,
+    C,
+}
+
+impl fmt::Display for CompositeFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompositeFont::None => write!(f, "no HRI"),
+            CompositeFont::A => write!(f, "HRI font A"),
+            CompositeFont::B => write!(f, "HRI font B"),
+            CompositeFont::C => write!(f, "HRI font C"),
+        }
+    }
+}
+
+impl From<CompositeFont> for u8 {
+    fn from(value: CompositeFont) -> Self {
+        match value {
+            CompositeFont::None => 0,
+            CompositeFont::A => 1,
+            CompositeFont::B => 2,
+            CompositeFont::C => 3,
+        }
+    }
+}
+
+/// Composite option
+#[derive(Debug, Clone)]
+pub struct CompositeOption {
+    linear_type: CompositeLinearType,
+    width: u8,
+    expanded_max_width: u16,
+    font: CompositeFont,
+}
+
+impl Default for CompositeOption {
+    fn default() -> Self {
+        Self {
+            linear_type: CompositeLinearType::default(),
+            width: 2,
+            expanded_max_width: 0,
+            font: CompositeFont::default(),
+        }
+    }
+}
+
+impl CompositeOption {
+    /// Create a new `CompositeOption`
+    ///
+    /// The module width is in dots (2 - 8).
+    pub fn new(linear_type: CompositeLinearType, width: u8, font: CompositeFont) -> Result<Self> {
+        if !(2..=8).contains(&width) {
+            return Err(PrinterError::Input(format!(
+                "invalid composite module width: {width} (2 - 8)"
+            )));
+        }
+
+        Ok(Self {
+            linear_type,
+            width,
+            expanded_max_width: 0,
+            font,
+        })
+    }
+
+    /// Set the maximum width in dots of GS1 DataBar Expanded Stacked (106 - 3072, 0 for no restriction)
+    ///
+    /// ```
+    /// use escpos::utils::*;
+    ///
+    /// let option = CompositeOption::new(CompositeLinearType::GS1DataBarExpandedStacked, 2, CompositeFont::A)
+    ///     .unwrap()
+    ///     .with_expanded_max_width(384)
+    ///     .unwrap();
+    /// assert_eq!(option.expanded_max_width(), 384);
+    ///
+    /// assert!(CompositeOption::default().with_expanded_max_width(50).is_err());
+    /// ```
+    pub fn with_expanded_max_width(mut self, max_width: u16) -> Result<Self> {
+        if max_width != 0 && !(106..=3072).contains(&max_width) {
+            return Err(PrinterError::Input(format!(
+                "invalid composite expanded stacked max width: {max_width} dots (106 - 3072)"
+            )));
+        }
+        self.expanded_max_width = max_width;
+        Ok(self)
+    }
+
+    /// Get linear element type
+    pub fn linear_type(&self) -> CompositeLinearType {
+        self.linear_type
+    }
+
+    /// Get module width
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    /// Get GS1 DataBar Expanded Stacked maximum width (0 for no restriction)
+    pub fn expanded_max_width(&self) -> u16 {
+        self.expanded_max_width
+    }
+
+    /// Get HRI font
+    pub fn font(&self) -> CompositeFont {
+        self.font
+    }
+}
+
+/// GS1 Composite code
+///
+/// The linear data depends on the linear element type (see [`CompositeLinearType`]), the composite data is
+/// written as element strings with application identifiers between parentheses (`(10)ABC123(17)250101`).
+///
+/// ```
+/// use escpos::utils::*;
+///
+/// let code = Composite::new("9501101530003", "(17)250101(10)ABC123", CompositeOption::default()).unwrap();
+/// assert_eq!(code.component(), CompositeComponent::CcA);
+///
+/// // CC-C is only available with GS1-128
+/// let data = format!("(91){}(92){}", "ab".repeat(45), "cd".repeat(45));
+/// assert!(Composite::new("9501101530003", &data, CompositeOption::default()).is_err());
+///
+/// let option = CompositeOption::new(CompositeLinearType::GS1128, 2, CompositeFont::A).unwrap();
+/// let code = Composite::new("(01)09501101530003", &data, option).unwrap();
+/// assert_eq!(code.component(), CompositeComponent::CcC);
+/// ```
+#[derive(Debug)]
+pub struct Composite {
+    pub linear_data: String,
+    pub composite_data: String,
+    pub option: CompositeOption,
+}
+
+impl Composite {
+    /// Create a new `Composite`
+    pub fn new(linear_data: &str, composite_data: &str, option: CompositeOption) -> Result<Self> {
+        let code = Self {
+            linear_data: linear_data.to_string(),
+            composite_data: composite_data.to_string(),
+            option,
+        };
+        code.option.linear_type.printer_data(linear_data)?;
+        code.composite_printer_data()?;
+
+        Ok(code)
+    }
+
+    /// Get the smallest 2D component fitting the composite data
+    pub fn component(&self) -> CompositeComponent {
+        let bits = self
+            .composite_printer_data()
+            .map(|data| composite_data_bits(&data))
+            .unwrap_or(usize::MAX);
+
+        [CompositeComponent::CcA, CompositeComponent::CcB]
+            .into_iter()
+            .find(|component| bits <= component.capacity_bits())
+            .unwrap_or(CompositeComponent::CcC)
+    }
+
+    /// Check the composite data and get the data sent to the printer
+    pub(crate) fn composite_printer_data(&self) -> Result<String> {
+        let data = gs1_encode(&gs1_parse(&self.composite_data)?, "{1");
+        let max_component = self.option.linear_type.max_component();
+        if composite_data_bits(&data) > max_component.capacity_bits() {
+            return Err(PrinterError::Input(format!(
+                "composite data too long for {max_component} component of {}: {}",
+                self.option.linear_type, self.composite_data
+            )));
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_linear_printer_data() {
+        let data = |linear_type: CompositeLinearType, data: &str| linear_type.printer_data(data).ok();
+
+        assert_eq!(
+            data(CompositeLinearType::Ean13, "9501101530003"),
+            Some((66, "950110153000".to_string()))
+        );
+        assert_eq!(
+            data(CompositeLinearType::Ean13, "950110153000"),
+            Some((66, "950110153000".to_string()))
+        );
+        assert_eq!(data(CompositeLinearType::Ean13, "9501101530004"), None);
+        assert_eq!(
+            data(CompositeLinearType::Ean8, "9638507"),
+            Some((65, "9638507".to_string()))
+        );
+        assert_eq!(
+            data(CompositeLinearType::UpcA, "04210000526"),
+            Some((67, "04210000526".to_string()))
+        );
+        assert_eq!(
+            data(CompositeLinearType::UpcE, "425261"),
+            Some((68, "425261".to_string()))
+        );
+        assert_eq!(
+            data(CompositeLinearType::UpcE, "042100005264"),
+            Some((69, "04210000526".to_string()))
+        );
+        assert_eq!(data(CompositeLinearType::UpcE, "42526"), None);
+        assert_eq!(
+            data(CompositeLinearType::GS1DataBarStacked, "(01)09501101530003"),
+            Some((72, "0950110153000".to_string()))
+        );
+        assert_eq!(data(CompositeLinearType::GS1DataBarLimited, "2950110153000"), None);
+        assert_eq!(
+            data(CompositeLinearType::GS1128, "(01)09501101530003(10)AB1"),
+            Some((77, "010950110153000310AB1".to_string()))
+        );
+        assert_eq!(data(CompositeLinearType::GS1DataBarExpanded, "09501101530003"), None);
+    }
+
+    #[test]
+    fn test_composite_data_bits() {
+        assert_eq!(composite_data_bits("12"), 7);
+        assert_eq!(composite_data_bits("10AB"), 7 + 5 + 12);
+        assert_eq!(composite_data_bits("10ab"), 7 + 5 + 16);
+    }
+
+    #[test]
+    fn test_composite_component() {
+        let option = CompositeOption::default();
+        let code = Composite::new("9501101530003", "(17)250101(10)ABC123", option.clone()).unwrap();
+        assert_eq!(code.component(), CompositeComponent::CcA);
+
+        let data = format!("(91){}", "ABCDEFGHIJ".repeat(9));
+        let code = Composite::new("9501101530003", &data, option.clone()).unwrap();
+        assert_eq!(code.component(), CompositeComponent::CcB);
+
+        // Too long for CC-B
+        let data = format!("(91){}(92){}", "ab".repeat(45), "cd".repeat(45));
+        assert!(Composite::new("9501101530003", &data, option).is_err());
+
+        let option = CompositeOption::new(CompositeLinearType::GS1128, 2, CompositeFont::None).unwrap();
+        let code = Composite::new("(01)09501101530003", &data, option).unwrap();
+        assert_eq!(code.component(), CompositeComponent::CcC);
+    }
+
+    #[test]
+    fn test_composite_option() {
+        assert!(CompositeOption::new(CompositeLinearType::Ean8, 1, CompositeFont::A).is_err());
+        assert!(CompositeOption::new(CompositeLinearType::Ean8, 9, CompositeFont::A).is_err());
+        assert!(Composite::new("9501101530003", "(17)251301", CompositeOption::default()).is_err());
+        assert!(Composite::new("9501101530003", "ABC", CompositeOption::default()).is_err());
+    }
+}
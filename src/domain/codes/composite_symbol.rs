@@ -0,0 +1,250 @@
+//! GS1 Composite symbol encoding, used to render GS1-128 composite codes (CC-C component) as image
+
+#![cfg(all(feature = "graphics", feature = "barcodes", feature = "codes_2d"))]
+
+use super::{code128::*, composite::*, pdf417::*, pdf417_symbol::*};
+use crate::errors::{PrinterError, Result};
+
+/// CC-C marker codeword (first data codeword)
+const CC_C_MARKER: u16 = 920;
+
+/// FNC1 in the general purpose data
+const FNC1: u8 = 0x1D;
+
+/// General purpose data compaction modes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Numeric,
+    Alphanumeric,
+    Iso646,
+}
+
+/// Push the `len` lowest bits of a value, most significant first
+fn push_bits(bits: &mut Vec<bool>, value: usize, len: usize) {
+    bits.extend((0..len).rev().map(|i| (value >> i) & 1 == 1));
+}
+
+/// Is a character encoded in numeric mode (digit or FNC1)?
+fn is_numeric(c: u8) -> bool {
+    c.is_ascii_digit() || c == FNC1
+}
+
+/// Get the value and length in bits of a character in alphanumeric mode
+fn alphanumeric_value(c: u8) -> Option<(usize, usize)> {
+    match c {
+        b'0'..=b'9' => Some((usize::from(c - b'0') + 5, 5)),
+        FNC1 => Some((15, 5)),
+        b'A'..=b'Z' => Some((usize::from(c - b'A') + 32, 6)),
+        b'*' => Some((58, 6)),
+        b',' => Some((59, 6)),
+        b'-' => Some((60, 6)),
+        b'.' => Some((61, 6)),
+        b'/' => Some((62, 6)),
+        _ => None,
+    }
+}
+
+/// Get the value and length in bits of a character in ISO/IEC 646 mode
+fn iso646_value(c: u8) -> Option<(usize, usize)> {
+    const SYMBOLS: &[u8] = b"!\"%&'()*+,-./:;<=>?_ ";
+
+    match c {
+        b'0'..=b'9' | FNC1 => alphanumeric_value(c),
+        b'A'..=b'Z' => Some((usize::from(c - b'A') + 64, 7)),
+        b'a'..=b'z' => Some((usize::from(c - b'a') + 90, 7)),
+        _ => SYMBOLS.iter().position(|s| *s == c).map(|i| (i + 232, 8)),
+    }
+}
+
+/// Encode element strings (with `{1` FNC1 separators) with the general purpose encodation method
+///
+/// The bit stream starts with the encodation method flag (0) and is padded to a multiple of 8 bits.
+fn general_purpose_bits(data: &str) -> Result<Vec<bool>> {
+    let data = data.replace("{1", "\x1D").into_bytes();
+    let numeric_run = |i: usize| data[i..].iter().take_while(|c| is_numeric(**c)).count();
+
+    let mut bits = vec![false];
+    let mut mode = Mode::Numeric;
+    let mut i = 0;
+
+    while i < data.len() {
+        let c = data[i];
+        match mode {
+            Mode::Numeric => {
+                // Pairs of digits or FNC1 (a single FNC1 at most)
+                match data.get(i + 1) {
+                    Some(next) if is_numeric(c) && is_numeric(*next) && (c, *next) != (FNC1, FNC1) => {
+                        let value = |c: u8| if c == FNC1 { 10 } else { usize::from(c - b'0') };
+                        push_bits(&mut bits, 11 * value(c) + value(*next) + 8, 7);
+                        i += 2;
+                    }
+                    _ => {
+                        push_bits(&mut bits, 0, 4);
+                        mode = Mode::Alphanumeric;
+                    }
+                }
+            }
+            Mode::Alphanumeric | Mode::Iso646 => {
+                let remaining = data.len() - i;
+                if is_numeric(c) && numeric_run(i) >= remaining.min(4) && remaining >= 2 {
+                    push_bits(&mut bits, 0, 3);
+                    mode = Mode::Numeric;
+                    continue;
+                }
+
+                let value = match mode {
+                    Mode::Alphanumeric => alphanumeric_value(c),
+                    _ => iso646_value(c),
+                };
+                match value {
+                    Some((value, len)) => {
+                        push_bits(&mut bits, value, len);
+                        i += 1;
+                        if c == FNC1 {
+                            // FNC1 also latches to numeric mode
+                            mode = Mode::Numeric;
+                        } else if mode == Mode::Iso646
+                            && i < data.len()
+                            && data[i..].iter().take(5).all(|c| alphanumeric_value(*c).is_some())
+                        {
+                            push_bits(&mut bits, 0b00100, 5);
+                            mode = Mode::Alphanumeric;
+                        }
+                    }
+                    None if mode == Mode::Alphanumeric && iso646_value(c).is_some() => {
+                        push_bits(&mut bits, 0b00100, 5);
+                        mode = Mode::Iso646;
+                    }
+                    None => {
+                        return Err(PrinterError::Input(format!(
+                            "invalid composite data character: {:?}",
+                            char::from(c)
+                        )))
+                    }
+                }
+            }
+        }
+    }
+
+    // Padding: latch to alphanumeric mode then alternate latches to ISO/IEC 646 and alphanumeric modes
+    let padded_len = bits.len().div_ceil(8) * 8;
+    if mode == Mode::Numeric && bits.len() < padded_len {
+        push_bits(&mut bits, 0, 4);
+    }
+    while bits.len() < padded_len {
+        push_bits(&mut bits, 0b00100, 5);
+    }
+    bits.truncate(padded_len);
+
+    Ok(bits)
+}
+
+/// Get the data codewords of a CC-C component: marker codeword and general purpose bits with byte compaction
+pub(crate) fn cc_c_codewords(data: &str) -> Result<Vec<u16>> {
+    let bytes = general_purpose_bits(data)?
+        .chunks(8)
+        .map(|bits| bits.iter().fold(0, |byte, bit| byte << 1 | u8::from(*bit)))
+        .collect::<Vec<_>>();
+
+    let mut codewords = vec![CC_C_MARKER];
+    codewords.extend(byte_compaction(&bytes));
+    Ok(codewords)
+}
+
+/// Get the CC-C error correction level from the number of data codewords
+fn cc_c_correction_level(data_codewords: usize) -> Pdf417CorrectionLevel {
+    match data_codewords {
+        0..=40 => Pdf417CorrectionLevel::Level2,
+        41..=160 => Pdf417CorrectionLevel::Level3,
+        161..=320 => Pdf417CorrectionLevel::Level4,
+        321..=833 => Pdf417CorrectionLevel::Level5,
+        _ => Pdf417CorrectionLevel::Level6,
+    }
+}
+
+/// Get the symbol character values of the GS1-128 linear element of a composite code
+///
+/// A CC-C component is linked by a code set A character (code set B when the data ends in code set A) before the
+/// check character.
+pub(crate) fn gs1_128_composite_values(data: &str) -> Result<Vec<u8>> {
+    let ops = code128_encode(&format!("{{1{data}"))?;
+    let set = ops
+        .iter()
+        .rev()
+        .find_map(|op| match op {
+            Code128Op::Set(set) => Some(*set),
+            _ => None,
+        })
+        .unwrap_or(Code128Set::B);
+
+    // Remove the check and stop characters
+    let mut values = code128_symbol_values(&ops);
+    values.truncate(values.len() - 2);
+    values.push(match set {
+        Code128Set::A => 100,
+        _ => 101,
+    });
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| usize::from(*v) * i.max(1))
+        .sum::<usize>()
+        % 103;
+    values.extend([checksum as u8, 106]);
+
+    Ok(values)
+}
+
+/// Encode the CC-C component of a GS1-128 composite code, `true` for dark modules, one vector by row
+///
+/// The number of columns is chosen from the width of the linear element (in modules).
+pub(crate) fn cc_c_modules(code: &Composite, linear_width: usize) -> Result<Vec<Vec<bool>>> {
+    let codewords = cc_c_codewords(&code.composite_printer_data()?)?;
+    let columns = (linear_width.saturating_sub(53) / 17).clamp(1, 30) as u8;
+    let correction_level = cc_c_correction_level(codewords.len() + 1);
+    let option = Pdf417Option::new(columns, 0, 0, 0, Pdf417Type::Standard, correction_level)?;
+    pdf417_modules(&codewords, &option)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(data: &str) -> String {
+        general_purpose_bits(data)
+            .unwrap()
+            .iter()
+            .map(|b| if *b { '1' } else { '0' })
+            .collect()
+    }
+
+    #[test]
+    fn test_composite_general_purpose_bits() {
+        // Method 0, 1 and 0 (19), 1 and 7 (26), padding (latch alphanumeric)
+        assert_eq!(bits("1017"), "0".to_string() + "0010011" + "0011010" + "0");
+        // Method 0, 1 and 0, latch alphanumeric, A, B
+        assert_eq!(bits("10AB"), "0".to_string() + "0010011" + "0000" + "100000" + "100001");
+        // Latch ISO/IEC 646 for lower case letters
+        assert_eq!(bits("10a"), "0".to_string() + "0010011" + "0000" + "00100" + "1011010");
+        // Latch numeric before digits, FNC1 and 1 (119)
+        assert!(bits("10A{11234").starts_with(&("0".to_string() + "0010011" + "0000" + "100000" + "000" + "1110111")));
+        // FNC1 in alphanumeric mode latches back to numeric mode
+        assert!(bits("10A{1B").starts_with(&("0".to_string() + "0010011" + "0000" + "100000" + "01111" + "0000")));
+        assert!(general_purpose_bits("10é").is_err());
+    }
+
+    #[test]
+    fn test_composite_cc_c_codewords() {
+        // 2 bytes with byte compaction
+        assert_eq!(cc_c_codewords("1017").unwrap(), vec![920, 901, 0x13, 0x34]);
+    }
+
+    #[test]
+    fn test_composite_gs1_128_values() {
+        // Start C, FNC1, 01, 09, 50, 11, 01, 53, 00, 03, code A (linkage), check character, stop
+        let values = gs1_128_composite_values("0109501101530003").unwrap();
+        assert_eq!(values[..11], [105, 102, 1, 9, 50, 11, 1, 53, 0, 3, 101]);
+        assert_eq!(values[12], 106);
+    }
+}
@@ -1,6 +1,12 @@
 //! DataMatrix
 
-use crate::errors::{PrinterError, Result};
+#![cfg(feature = "codes_2d")]
+
+use super::gs1::*;
+use crate::{
+    domain::common::check_eci_designator,
+    errors::{PrinterError, Result},
+};
 use std::fmt;
 
 /// DataMatrix type
@@ -65,11 +71,211 @@ impl TryFrom<DataMatrixType> for (u8, u8, u8) {
     }
 }
 
+impl DataMatrixType {
+    /// Create a `DataMatrixType` from a symbol size (square from 10x10 to 144x144, rectangle from 8x18 to 16x48)
+    ///
+    /// ```
+    /// use escpos::utils::*;
+    ///
+    /// assert!(matches!(DataMatrixType::from_size(24, 24), Ok(DataMatrixType::Square(24))));
+    /// assert!(matches!(DataMatrixType::from_size(12, 36), Ok(DataMatrixType::Rectangle(12, 36))));
+    /// assert!(DataMatrixType::from_size(12, 24).is_err());
+    /// ```
+    pub fn from_size(rows: u8, columns: u8) -> Result<Self> {
+        let code_type = match rows == columns {
+            true => Self::Square(rows),
+            false => Self::Rectangle(rows, columns),
+        };
+        if rows == 0 || columns == 0 {
+            return Err(PrinterError::Input(format!(
+                "invalid DataMatrix number of rows and columns: ({rows}, {columns})"
+            )));
+        }
+        <(u8, u8, u8)>::try_from(code_type)?;
+        Ok(code_type)
+    }
+}
+
+/// ECC 200 symbol size
+///
+/// Regions and error correction are only used to render DataMatrix as images.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(feature = "graphics"), allow(dead_code))]
+pub(crate) struct SymbolSize {
+    pub(crate) rows: usize,
+    pub(crate) columns: usize,
+    pub(crate) region_rows: usize,
+    pub(crate) region_columns: usize,
+    pub(crate) data_codewords: usize,
+    /// Error correction codewords per block
+    pub(crate) ec_codewords: usize,
+    pub(crate) blocks: usize,
+}
+
+impl SymbolSize {
+    const fn new(
+        rows: usize,
+        columns: usize,
+        region_rows: usize,
+        region_columns: usize,
+        data_codewords: usize,
+        ec_codewords: usize,
+        blocks: usize,
+    ) -> Self {
+        Self {
+            rows,
+            columns,
+            region_rows,
+            region_columns,
+            data_codewords,
+            ec_codewords,
+            blocks,
+        }
+    }
+
+    pub(crate) fn is_square(&self) -> bool {
+        self.rows == self.columns
+    }
+}
+
+/// ECC 200 symbol sizes (squares, then rectangles)
+pub(crate) const SYMBOL_SIZES: [SymbolSize; 30] = [
+    SymbolSize::new(10, 10, 8, 8, 3, 5, 1),
+    SymbolSize::new(12, 12, 10, 10, 5, 7, 1),
+    SymbolSize::new(14, 14, 12, 12, 8, 10, 1),
+    SymbolSize::new(16, 16, 14, 14, 12, 12, 1),
+    SymbolSize::new(18, 18, 16, 16, 18, 14, 1),
+    SymbolSize::new(20, 20, 18, 18, 22, 18, 1),
+    SymbolSize::new(22, 22, 20, 20, 30, 20, 1),
+    SymbolSize::new(24, 24, 22, 22, 36, 24, 1),
+    SymbolSize::new(26, 26, 24, 24, 44, 28, 1),
+    SymbolSize::new(32, 32, 14, 14, 62, 36, 1),
+    SymbolSize::new(36, 36, 16, 16, 86, 42, 1),
+    SymbolSize::new(40, 40, 18, 18, 114, 48, 1),
+    SymbolSize::new(44, 44, 20, 20, 144, 56, 1),
+    SymbolSize::new(48, 48, 22, 22, 174, 68, 1),
+    SymbolSize::new(52, 52, 24, 24, 204, 42, 2),
+    SymbolSize::new(64, 64, 14, 14, 280, 56, 2),
+    SymbolSize::new(72, 72, 16, 16, 368, 36, 4),
+    SymbolSize::new(80, 80, 18, 18, 456, 48, 4),
+    SymbolSize::new(88, 88, 20, 20, 576, 56, 4),
+    SymbolSize::new(96, 96, 22, 22, 696, 68, 4),
+    SymbolSize::new(104, 104, 24, 24, 816, 56, 6),
+    SymbolSize::new(120, 120, 18, 18, 1050, 68, 6),
+    SymbolSize::new(132, 132, 20, 20, 1304, 62, 8),
+    SymbolSize::new(144, 144, 22, 22, 1558, 62, 10),
+    SymbolSize::new(8, 18, 6, 16, 5, 7, 1),
+    SymbolSize::new(8, 32, 6, 14, 10, 11, 1),
+    SymbolSize::new(12, 26, 10, 24, 16, 14, 1),
+    SymbolSize::new(12, 36, 10, 16, 22, 18, 1),
+    SymbolSize::new(16, 36, 14, 16, 32, 24, 1),
+    SymbolSize::new(16, 48, 14, 22, 49, 28, 1),
+];
+
+/// Upper shift (next codeword is an extended ASCII character)
+pub(crate) const UPPER_SHIFT: u16 = 235;
+
+/// ECI character
+pub(crate) const ECI: u16 = 241;
+
+/// ASCII encodation (digit pairs are encoded in one codeword)
+pub(crate) fn encode_ascii(data: &[u8]) -> Vec<u16> {
+    let mut codewords = Vec::with_capacity(data.len());
+    let mut i = 0;
+
+    while i < data.len() {
+        match (data[i], data.get(i + 1)) {
+            (high, Some(low)) if high.is_ascii_digit() && low.is_ascii_digit() => {
+                codewords.push(130 + u16::from(high - b'0') * 10 + u16::from(low - b'0'));
+                i += 2;
+                continue;
+            }
+            (c, _) if c >= 128 => codewords.extend([UPPER_SHIFT, u16::from(c) - 127]),
+            (c, _) => codewords.push(u16::from(c) + 1),
+        }
+        i += 1;
+    }
+
+    codewords
+}
+
+/// Number of C40 (or Text, with lower case letters) values of a character
+fn c40_values(c: u8, basic: fn(&u8) -> bool) -> usize {
+    match c {
+        c if c == b' ' || c.is_ascii_digit() || basic(&c) => 1,
+        0..=127 => 2,
+        c => 2 + c40_values(c - 128, basic),
+    }
+}
+
+/// Get the smallest number of data codewords of the data encoded with a single encodation (ASCII, C40, Text,
+/// EDIFACT or Base256)
+///
+/// Printers choose the encodation themselves, this is the best case of DataMatrix printed with `GS ( k` commands.
+pub(crate) fn min_codewords(data: &[u8]) -> usize {
+    let latched = |values: usize, per_codewords: (usize, usize)| 1 + values.div_ceil(per_codewords.0) * per_codewords.1;
+    let c40 = latched(
+        data.iter().map(|c| c40_values(*c, u8::is_ascii_uppercase)).sum(),
+        (3, 2),
+    );
+    let text = latched(
+        data.iter().map(|c| c40_values(*c, u8::is_ascii_lowercase)).sum(),
+        (3, 2),
+    );
+    let edifact = match data.iter().all(|c| (32..=94).contains(c)) {
+        true => latched(data.len(), (4, 3)),
+        false => usize::MAX,
+    };
+    let base256 = 1 + if data.len() < 250 { 1 } else { 2 } + data.len();
+
+    [encode_ascii(data).len(), c40, text, edifact, base256]
+        .into_iter()
+        .min()
+        .unwrap_or_default()
+}
+
+/// ECI designator codewords
+pub(crate) fn encode_eci(eci: u32) -> Vec<u16> {
+    match eci {
+        0..=126 => vec![ECI, eci as u16 + 1],
+        127..=16382 => vec![ECI, ((eci - 127) / 254 + 128) as u16, ((eci - 127) % 254 + 1) as u16],
+        _ => {
+            let eci = eci - 16383;
+            vec![
+                ECI,
+                (eci / 64516 + 192) as u16,
+                ((eci / 254) % 254 + 1) as u16,
+                (eci % 254 + 1) as u16,
+            ]
+        }
+    }
+}
+
+/// Choose the smallest symbol size of the type fitting the codewords
+pub(crate) fn symbol_size(code_type: DataMatrixType, len: usize) -> Result<SymbolSize> {
+    let (rows, columns) = match code_type {
+        DataMatrixType::Square(d) => (usize::from(d), usize::from(d)),
+        DataMatrixType::Rectangle(r, c) => (usize::from(r), usize::from(c)),
+    };
+    let is_square = matches!(code_type, DataMatrixType::Square(_));
+
+    SYMBOL_SIZES
+        .iter()
+        .filter(|size| size.is_square() == is_square)
+        .filter(|size| rows == 0 || size.rows == rows)
+        .filter(|size| columns == 0 || size.columns == columns)
+        .find(|size| size.data_codewords >= len)
+        .copied()
+        .ok_or_else(|| PrinterError::Input(format!("DataMatrix data too long for {code_type}: {len} codewords")))
+}
+
 /// DataMatrix option
 #[derive(Debug)]
 pub struct DataMatrixOption {
     code_type: DataMatrixType,
     size: u8,
+    eci: Option<u32>,
+    gs1: bool,
 }
 
 impl Default for DataMatrixOption {
@@ -77,6 +283,8 @@ impl Default for DataMatrixOption {
         Self {
             code_type: DataMatrixType::default(),
             size: 3,
+            eci: None,
+            gs1: false,
         }
     }
 }
@@ -87,8 +295,33 @@ impl DataMatrixOption {
         if !(2..=16).contains(&size) {
             return Err(PrinterError::Input(format!("DataMatrix size must in 2 - 16: {size}")));
         }
+        <(u8, u8, u8)>::try_from(code_type)?;
+
+        Ok(Self {
+            code_type,
+            size,
+            eci: None,
+            gs1: false,
+        })
+    }
 
-        Ok(Self { code_type, size })
+    /// Encode GS1 element strings (FNC1 in first position), written with application identifiers between
+    /// parentheses (`(01)09501101530003(17)250101(10)ABC123`)
+    ///
+    /// GS1 DataMatrix is only encoded in DataMatrix printed as images.
+    pub fn with_gs1(mut self, gs1: bool) -> Self {
+        self.gs1 = gs1;
+        self
+    }
+
+    /// Declare the character set of the data with an ECI designator (0-999999)
+    ///
+    /// ECI designators are only encoded in DataMatrix printed as images (software 2D codes, `graphics` feature):
+    /// the printer commands (`GS ( k`) do not support them, printing the code without software 2D codes fails.
+    pub fn with_eci(mut self, eci: u32) -> Result<Self> {
+        check_eci_designator(eci)?;
+        self.eci = Some(eci);
+        Ok(self)
     }
 
     /// Get type, number of rows and columns
@@ -100,22 +333,80 @@ impl DataMatrixOption {
     pub fn size(&self) -> u8 {
         self.size
     }
+
+    /// Get ECI designator
+    pub fn eci(&self) -> Option<u32> {
+        self.eci
+    }
+
+    /// Is GS1 DataMatrix enabled?
+    pub fn gs1(&self) -> bool {
+        self.gs1
+    }
 }
 
 /// DataMatrix
+///
+/// The data must fit in the symbol size of the option (the largest one when the number of rows or columns is 0)
+/// with the most compact encodation, as printers choose it. DataMatrix codes printed as images are encoded with
+/// ASCII encodation, which can need a larger symbol (see [`symbol_size`](DataMatrix::symbol_size)).
+/// With GS1 enabled, `data` holds the element strings separated by `GS`.
 #[derive(Debug)]
 pub struct DataMatrix {
-    pub data: String,
+    pub data: Vec<u8>,
     pub option: DataMatrixOption,
 }
 
 impl DataMatrix {
     /// Create a new `DataMatrix`
-    pub fn new(data: &str, option: DataMatrixOption) -> Self {
-        Self {
-            data: data.to_string(),
-            option,
-        }
+    ///
+    /// ```
+    /// use escpos::utils::*;
+    ///
+    /// let option = DataMatrixOption::new(DataMatrixType::Square(12), 3).unwrap();
+    /// assert_eq!(DataMatrix::new("0123456789", option).unwrap().symbol_size().unwrap(), (12, 12));
+    ///
+    /// let option = DataMatrixOption::new(DataMatrixType::Square(12), 3).unwrap();
+    /// assert!(DataMatrix::new("0123456789ABC", option).is_err());
+    ///
+    /// let option = DataMatrixOption::new(DataMatrixType::Square(0), 3).unwrap().with_gs1(true);
+    /// let code = DataMatrix::new("(01)09501101530003(10)ABC123(21)42", option).unwrap();
+    /// assert_eq!(code.data, b"010950110153000310ABC123\x1D2142".to_vec());
+    /// ```
+    pub fn new(data: &str, option: DataMatrixOption) -> Result<Self> {
+        Self::from_bytes(data.as_bytes(), option)
+    }
+
+    /// Create a new `DataMatrix` from bytes
+    pub fn from_bytes(data: &[u8], option: DataMatrixOption) -> Result<Self> {
+        let data = match option.gs1 {
+            true => {
+                let element_strings = std::str::from_utf8(data)
+                    .map_err(|_| PrinterError::Input("invalid GS1 DataMatrix data: not UTF-8".to_string()))?;
+                gs1_encode(&gs1_parse(element_strings)?, "\x1D").into_bytes()
+            }
+            false => data.to_vec(),
+        };
+
+        let code = Self { data, option };
+        symbol_size(
+            code.option.code_type,
+            code.header_codewords() + min_codewords(&code.data),
+        )?;
+        Ok(code)
+    }
+
+    /// Get the number of codewords of the ECI designator and FNC1
+    fn header_codewords(&self) -> usize {
+        let eci = self.option.eci.map(|eci| encode_eci(eci).len()).unwrap_or_default();
+        eci + usize::from(self.option.gs1)
+    }
+
+    /// Get the smallest symbol size (rows and columns) of the option fitting the data with ASCII encodation,
+    /// the symbol size of the DataMatrix printed as image
+    pub fn symbol_size(&self) -> Result<(usize, usize)> {
+        let codewords = self.header_codewords() + encode_ascii(&self.data).len();
+        symbol_size(self.option.code_type, codewords).map(|size| (size.rows, size.columns))
     }
 }
 
@@ -145,10 +436,103 @@ mod tests {
         assert!(t.is_err());
     }
 
+    #[test]
+    fn test_data_matrix_encode_ascii() {
+        assert_eq!(encode_ascii(b"123456"), vec![142, 164, 186]);
+        assert_eq!(encode_ascii(b"A1b"), vec![66, 50, 99]);
+        assert_eq!(encode_ascii(&[0xE9]), vec![235, 106]);
+    }
+
+    #[test]
+    fn test_data_matrix_min_codewords() {
+        // ASCII (digit pairs)
+        assert_eq!(min_codewords(b"123456"), 3);
+        // C40: latch and 2 codewords per 3 values
+        assert_eq!(min_codewords(b"ABCDEF"), 5);
+        // Text
+        assert_eq!(min_codewords(b"abcdefghi"), 7);
+        // EDIFACT: latch and 3 codewords per 4 values
+        assert_eq!(min_codewords(b"A-B.C/D-"), 7);
+        // Base256: latch and length
+        assert_eq!(min_codewords(&[0xFF; 10]), 12);
+        assert_eq!(min_codewords(&[0xFF; 300]), 303);
+    }
+
+    #[test]
+    fn test_data_matrix_encode_eci() {
+        assert_eq!(encode_eci(26), vec![241, 27]);
+        assert_eq!(encode_eci(127), vec![241, 128, 1]);
+        assert_eq!(encode_eci(16382), vec![241, 191, 254]);
+        assert_eq!(encode_eci(16383), vec![241, 192, 1, 1]);
+        assert_eq!(encode_eci(999_999), vec![241, 207, 63, 129]);
+    }
+
+    #[test]
+    fn test_data_matrix_symbol_size() {
+        assert_eq!(symbol_size(DataMatrixType::Square(0), 3).unwrap().rows, 10);
+        assert_eq!(symbol_size(DataMatrixType::Square(0), 4).unwrap().rows, 12);
+        assert_eq!(symbol_size(DataMatrixType::Square(16), 4).unwrap().rows, 16);
+        assert_eq!(symbol_size(DataMatrixType::Square(0), 1558).unwrap().rows, 144);
+        assert_eq!(symbol_size(DataMatrixType::Rectangle(8, 0), 6).unwrap().columns, 32);
+        assert_eq!(symbol_size(DataMatrixType::Rectangle(16, 48), 1).unwrap().columns, 48);
+
+        assert!(symbol_size(DataMatrixType::Square(0), 1559).is_err());
+        assert!(symbol_size(DataMatrixType::Square(10), 4).is_err());
+        assert!(symbol_size(DataMatrixType::Rectangle(8, 0), 11).is_err());
+    }
+
+    #[test]
+    fn test_data_matrix_capacity() {
+        let option = |code_type: DataMatrixType| DataMatrixOption::new(code_type, 3).unwrap();
+
+        // 10x10: 3 data codewords
+        assert!(DataMatrix::new("123456", option(DataMatrixType::Square(10))).is_ok());
+        assert!(DataMatrix::new("1234567", option(DataMatrixType::Square(10))).is_err());
+        // 8x18: 5 data codewords, 8x32: 10 data codewords
+        let code = DataMatrix::new("ABCDE", option(DataMatrixType::Rectangle(8, 0))).unwrap();
+        assert_eq!(code.symbol_size().unwrap(), (8, 18));
+        let code = DataMatrix::new("ABCDEF", option(DataMatrixType::Rectangle(8, 0))).unwrap();
+        assert_eq!(code.symbol_size().unwrap(), (8, 32));
+        // Fits with C40 encodation, but not as image
+        let code = DataMatrix::new("ABCDEF", option(DataMatrixType::Rectangle(8, 18))).unwrap();
+        assert!(code.symbol_size().is_err());
+        assert!(DataMatrix::new("ABCDEFGHI", option(DataMatrixType::Rectangle(8, 18))).is_err());
+        // 144x144: 1558 data codewords
+        assert!(DataMatrix::new(&"1".repeat(3116), option(DataMatrixType::Square(0))).is_ok());
+        assert!(DataMatrix::new(&"1".repeat(3117), option(DataMatrixType::Square(0))).is_err());
+        // Binary data fits with Base256 encodation
+        assert!(DataMatrix::from_bytes(&[0xFF; 1555], option(DataMatrixType::Square(0))).is_ok());
+        assert!(DataMatrix::from_bytes(&[0xFF; 1556], option(DataMatrixType::Square(0))).is_err());
+
+        // ECI designator and FNC1
+        let eci_option = option(DataMatrixType::Square(10)).with_eci(26).unwrap();
+        assert!(DataMatrix::new("12", eci_option).is_ok());
+        let eci_option = option(DataMatrixType::Square(10)).with_eci(26).unwrap().with_gs1(true);
+        assert!(DataMatrix::new("(10)1", eci_option).is_err());
+    }
+
+    #[test]
+    fn test_data_matrix_gs1() {
+        let option = DataMatrixOption::default().with_gs1(true);
+        assert!(DataMatrix::new("0109501101530003", option).is_err());
+        let option = DataMatrixOption::default().with_gs1(true);
+        assert!(DataMatrix::from_bytes(&[0xFF], option).is_err());
+    }
+
+    #[test]
+    fn test_data_matrix_type_from_size() {
+        assert!(DataMatrixType::from_size(8, 18).is_ok());
+        assert!(DataMatrixType::from_size(144, 144).is_ok());
+        assert!(DataMatrixType::from_size(0, 0).is_err());
+        assert!(DataMatrixType::from_size(8, 0).is_err());
+        assert!(DataMatrixType::from_size(146, 146).is_err());
+    }
+
     #[test]
     fn test_data_matrix_option_new() {
         assert!(DataMatrixOption::new(DataMatrixType::default(), 3).is_ok());
         assert!(DataMatrixOption::new(DataMatrixType::default(), 1).is_err());
         assert!(DataMatrixOption::new(DataMatrixType::default(), 17).is_err());
+        assert!(DataMatrixOption::new(DataMatrixType::Square(11), 3).is_err());
     }
 }
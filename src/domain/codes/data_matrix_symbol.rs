@@ -0,0 +1,327 @@
+//! DataMatrix (ECC 200) symbol encoding, used to render DataMatrix as image
+
+#![cfg(all(feature = "graphics", feature = "codes_2d"))]
+
+use super::{data_matrix::*, reed_solomon::GaloisField};
+use crate::errors::Result;
+
+/// First pad codeword
+const PAD: u16 = 129;
+
+/// FNC1 character (GS1 DataMatrix in first position)
+const FNC1: u16 = 232;
+
+/// Add pad codewords and interleaved error correction codewords
+fn add_error_correction(mut codewords: Vec<u16>, size: &SymbolSize) -> Vec<u16> {
+    if codewords.len() < size.data_codewords {
+        codewords.push(PAD);
+    }
+    while codewords.len() < size.data_codewords {
+        // 253-state randomised pad
+        let random = (149 * (codewords.len() + 1)) % 253 + 1;
+        let pad = PAD as usize + random;
+        codewords.push(if pad > 254 { pad - 254 } else { pad } as u16);
+    }
+
+    let field = GaloisField::new(0x12D, 256);
+    let data_len = codewords.len();
+    codewords.resize(data_len + size.ec_codewords * size.blocks, 0);
+
+    for block in 0..size.blocks {
+        let data: Vec<u16> = codewords[..data_len]
+            .iter()
+            .skip(block)
+            .step_by(size.blocks)
+            .copied()
+            .collect();
+        for (i, ec) in field.reed_solomon(&data, size.ec_codewords).into_iter().enumerate() {
+            codewords[data_len + i * size.blocks + block] = ec;
+        }
+    }
+
+    codewords
+}
+
+/// Codeword placement in the mapping matrix (ECC 200 placement algorithm)
+struct Placement {
+    rows: usize,
+    columns: usize,
+    /// Codeword index (from 1) and bit (1 is the most significant) of each module, `Some((0, 1))` for fixed dark modules
+    modules: Vec<Option<(usize, u8)>>,
+}
+
+impl Placement {
+    fn new(rows: usize, columns: usize) -> Self {
+        let mut placement = Self {
+            rows,
+            columns,
+            modules: vec![None; rows * columns],
+        };
+        placement.place();
+        placement
+    }
+
+    fn module(&mut self, mut row: isize, mut column: isize, codeword: usize, bit: u8) {
+        let (rows, columns) = (self.rows as isize, self.columns as isize);
+        if row < 0 {
+            row += rows;
+            column += 4 - ((rows + 4) % 8);
+        }
+        if column < 0 {
+            column += columns;
+            row += 4 - ((columns + 4) % 8);
+        }
+        self.modules[(row * columns + column) as usize] = Some((codeword, bit));
+    }
+
+    fn utah(&mut self, row: isize, column: isize, codeword: usize) {
+        for (bit, (r, c)) in [
+            (-2, -2),
+            (-2, -1),
+            (-1, -2),
+            (-1, -1),
+            (-1, 0),
+            (0, -2),
+            (0, -1),
+            (0, 0),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            self.module(row + r, column + c, codeword, bit as u8 + 1);
+        }
+    }
+
+    fn corner(&mut self, positions: [(isize, isize); 8], codeword: usize) {
+        for (bit, (row, column)) in positions.into_iter().enumerate() {
+            self.module(row, column, codeword, bit as u8 + 1);
+        }
+    }
+
+    fn place(&mut self) {
+        let (rows, columns) = (self.rows as isize, self.columns as isize);
+        let (mut row, mut column, mut codeword) = (4isize, 0isize, 1usize);
+
+        loop {
+            if row == rows && column == 0 {
+                let positions = [
+                    (rows - 1, 0),
+                    (rows - 1, 1),
+                    (rows - 1, 2),
+                    (0, columns - 2),
+                    (0, columns - 1),
+                    (1, columns - 1),
+                    (2, columns - 1),
+                    (3, columns - 1),
+                ];
+                self.corner(positions, codeword);
+                codeword += 1;
+            }
+            if row == rows - 2 && column == 0 && columns % 4 != 0 {
+                let positions = [
+                    (rows - 3, 0),
+                    (rows - 2, 0),
+                    (rows - 1, 0),
+                    (0, columns - 4),
+                    (0, columns - 3),
+                    (0, columns - 2),
+                    (0, columns - 1),
+                    (1, columns - 1),
+                ];
+                self.corner(positions, codeword);
+                codeword += 1;
+            }
+            if row == rows - 2 && column == 0 && columns % 8 == 4 {
+                let positions = [
+                    (rows - 3, 0),
+                    (rows - 2, 0),
+                    (rows - 1, 0),
+                    (0, columns - 2),
+                    (0, columns - 1),
+                    (1, columns - 1),
+                    (2, columns - 1),
+                    (3, columns - 1),
+                ];
+                self.corner(positions, codeword);
+                codeword += 1;
+            }
+            if row == rows + 4 && column == 2 && columns % 8 == 0 {
+                let positions = [
+                    (rows - 1, 0),
+                    (rows - 1, columns - 1),
+                    (0, columns - 3),
+                    (0, columns - 2),
+                    (0, columns - 1),
+                    (1, columns - 3),
+                    (1, columns - 2),
+                    (1, columns - 1),
+                ];
+                self.corner(positions, codeword);
+                codeword += 1;
+            }
+
+            // Sweep upward diagonally
+            loop {
+                if row < rows && column >= 0 && self.modules[(row * columns + column) as usize].is_none() {
+                    self.utah(row, column, codeword);
+                    codeword += 1;
+                }
+                row -= 2;
+                column += 2;
+                if row < 0 || column >= columns {
+                    break;
+                }
+            }
+            row += 1;
+            column += 3;
+
+            // Sweep downward diagonally
+            loop {
+                if row >= 0 && column < columns && self.modules[(row * columns + column) as usize].is_none() {
+                    self.utah(row, column, codeword);
+                    codeword += 1;
+                }
+                row += 2;
+                column -= 2;
+                if row >= rows || column < 0 {
+                    break;
+                }
+            }
+            row += 3;
+            column += 1;
+
+            if row >= rows && column >= columns {
+                break;
+            }
+        }
+
+        // Fixed pattern in the lower right corner if it is not filled
+        let last = self.modules.len() - 1;
+        if self.modules[last].is_none() {
+            self.modules[last] = Some((0, 1));
+            self.modules[last - self.columns - 1] = Some((0, 1));
+            self.modules[last - 1] = Some((0, 0));
+            self.modules[last - self.columns] = Some((0, 0));
+        }
+    }
+
+    /// Is the module dark?
+    fn is_dark(&self, row: usize, column: usize, codewords: &[u16]) -> bool {
+        match self.modules[row * self.columns + column] {
+            Some((0, bit)) => bit == 1,
+            Some((codeword, bit)) => (codewords[codeword - 1] >> (8 - bit)) & 1 == 1,
+            None => false,
+        }
+    }
+}
+
+/// Encode data into a DataMatrix (ECC 200) symbol, `true` for dark modules (without quiet zone)
+///
+/// GS1 data starts with FNC1, `GS` separators are encoded as is.
+pub(crate) fn data_matrix_modules(
+    data: &[u8],
+    code_type: DataMatrixType,
+    eci: Option<u32>,
+    gs1: bool,
+) -> Result<Vec<Vec<bool>>> {
+    let mut codewords = eci.map(encode_eci).unwrap_or_default();
+    if gs1 {
+        codewords.push(FNC1);
+    }
+    codewords.extend(encode_ascii(data));
+    let size = symbol_size(code_type, codewords.len())?;
+    let codewords = add_error_correction(codewords, &size);
+
+    let regions_vertical = size.rows / (size.region_rows + 2);
+    let regions_horizontal = size.columns / (size.region_columns + 2);
+    let placement = Placement::new(
+        regions_vertical * size.region_rows,
+        regions_horizontal * size.region_columns,
+    );
+
+    let mut symbol = Vec::with_capacity(size.rows);
+    for y in 0..placement.rows {
+        // Alternating top edge of the data regions
+        if y % size.region_rows == 0 {
+            symbol.push((0..size.columns).map(|x| x % 2 == 0).collect());
+        }
+
+        let mut row = Vec::with_capacity(size.columns);
+        for x in 0..placement.columns {
+            // Solid left edge and alternating right edge of the data regions
+            if x % size.region_columns == 0 {
+                row.push(true);
+            }
+            row.push(placement.is_dark(y, x, &codewords));
+            if x % size.region_columns == size.region_columns - 1 {
+                row.push(y % 2 == 0);
+            }
+        }
+        symbol.push(row);
+
+        // Solid bottom edge of the data regions
+        if y % size.region_rows == size.region_rows - 1 {
+            symbol.push(vec![true; size.columns]);
+        }
+    }
+
+    Ok(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_matrix_error_correction() {
+        let size = symbol_size(DataMatrixType::Square(0), 3).unwrap();
+        assert_eq!(
+            add_error_correction(vec![142, 164, 186], &size),
+            vec![142, 164, 186, 114, 25, 5, 88, 102]
+        );
+
+        // Pad codewords
+        let size = symbol_size(DataMatrixType::Square(12), 1).unwrap();
+        assert_eq!(&add_error_correction(vec![66], &size)[..5], &[66, 129, 70, 220, 115]);
+    }
+
+    #[test]
+    fn test_data_matrix_placement() {
+        // Every module of the mapping matrix is used
+        for size in SYMBOL_SIZES {
+            let regions = (size.rows / (size.region_rows + 2)) * (size.columns / (size.region_columns + 2));
+            let placement = Placement::new(
+                size.rows / (size.region_rows + 2) * size.region_rows,
+                size.columns / (size.region_columns + 2) * size.region_columns,
+            );
+            assert!(placement.modules.iter().all(|m| m.is_some()));
+            let codewords = placement.modules.iter().filter_map(|m| *m).map(|(c, _)| c).max();
+            assert_eq!(codewords, Some(size.data_codewords + size.ec_codewords * size.blocks));
+            assert_eq!(
+                placement.modules.len(),
+                regions * size.region_rows * size.region_columns
+            );
+        }
+    }
+
+    #[test]
+    fn test_data_matrix_modules() {
+        let symbol = data_matrix_modules(b"123456", DataMatrixType::Square(0), None, false).unwrap();
+        assert_eq!((symbol.len(), symbol[0].len()), (10, 10));
+
+        // Finder pattern: solid left and bottom edges, alternating top and right edges
+        assert!(symbol.iter().all(|row| row[0]));
+        assert!(symbol[9].iter().all(|m| *m));
+        assert_eq!(symbol[0], (0..10).map(|x| x % 2 == 0).collect::<Vec<_>>());
+        assert_eq!(
+            symbol.iter().map(|row| row[9]).collect::<Vec<_>>(),
+            (0..10).map(|y| y % 2 == 1).collect::<Vec<_>>()
+        );
+
+        let symbol = data_matrix_modules(&[b'A'; 80], DataMatrixType::Square(0), None, false).unwrap();
+        assert_eq!(symbol.len(), 36);
+        // 2x2 data regions: solid and alternating rows between them
+        assert!(symbol[17].iter().all(|m| *m));
+        assert_eq!(symbol[18], (0..36).map(|x| x % 2 == 0).collect::<Vec<_>>());
+    }
+}
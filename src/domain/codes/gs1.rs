@@ -0,0 +1,762 @@
+//! GS1 element strings
+//!
+//! Element strings are written with their application identifier (AI) between parentheses,
+//! for example `(01)09501101530003(17)250101(10)ABC123`.
+
+#![cfg(any(feature = "barcodes", feature = "codes_2d"))]
+
+use crate::errors::{PrinterError, Result};
+use std::fmt;
+
+/// Total length (AI included) of the element strings with a predefined length, by AI first two digits
+///
+/// A FNC1 separator is not needed after these element strings.
+const PREDEFINED_LENGTHS: [(&str, usize); 22] = [
+    ("00", 20),
+    ("01", 16),
+    ("02", 16),
+    ("03", 16),
+    ("04", 18),
+    ("11", 8),
+    ("12", 8),
+    ("13", 8),
+    ("14", 8),
+    ("15", 8),
+    ("16", 8),
+    ("17", 8),
+    ("18", 8),
+    ("19", 8),
+    ("20", 4),
+    ("31", 10),
+    ("32", 10),
+    ("33", 10),
+    ("34", 10),
+    ("35", 10),
+    ("36", 10),
+    ("41", 16),
+];
+
+/// Maximum data length of an element string
+const GS1_DATA_MAX_LENGTH: usize = 90;
+
+/// GS1 application identifier data format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Gs1Format {
+    /// Digits
+    Numeric,
+    /// Digits, the last one being a check digit
+    NumericCheckDigit,
+    /// Date (YYMMDD, day can be 00)
+    Date,
+    /// Characters of the GS1 character set
+    Alphanumeric,
+}
+
+/// Known application identifiers (GS1 General Specifications):
+/// (AI prefix, AI length, data format, data min length, data max length)
+///
+/// When the AI length is greater than the prefix length, the last digits are free
+/// (decimal point position or sequence number like `310n`).
+/// AIs missing from this table are accepted with the rules common to all AIs (see `validate_unknown`).
+const APPLICATION_IDENTIFIERS: [(&str, usize, Gs1Format, usize, usize); 160] = [
+    ("00", 2, Gs1Format::NumericCheckDigit, 18, 18),
+    ("01", 2, Gs1Format::NumericCheckDigit, 14, 14),
+    ("02", 2, Gs1Format::NumericCheckDigit, 14, 14),
+    ("10", 2, Gs1Format::Alphanumeric, 1, 20),
+    ("11", 2, Gs1Format::Date, 6, 6),
+    ("12", 2, Gs1Format::Date, 6, 6),
+    ("13", 2, Gs1Format::Date, 6, 6),
+    ("15", 2, Gs1Format::Date, 6, 6),
+    ("16", 2, Gs1Format::Date, 6, 6),
+    ("17", 2, Gs1Format::Date, 6, 6),
+    ("20", 2, Gs1Format::Numeric, 2, 2),
+    ("21", 2, Gs1Format::Alphanumeric, 1, 20),
+    ("22", 2, Gs1Format::Alphanumeric, 1, 20),
+    ("235", 3, Gs1Format::Alphanumeric, 1, 28),
+    ("240", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("241", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("242", 3, Gs1Format::Numeric, 1, 6),
+    ("243", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("250", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("251", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("253", 3, Gs1Format::Alphanumeric, 13, 30),
+    ("254", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("255", 3, Gs1Format::Numeric, 13, 25),
+    ("30", 2, Gs1Format::Numeric, 1, 8),
+    ("31", 4, Gs1Format::Numeric, 6, 6),
+    ("32", 4, Gs1Format::Numeric, 6, 6),
+    ("33", 4, Gs1Format::Numeric, 6, 6),
+    ("34", 4, Gs1Format::Numeric, 6, 6),
+    ("35", 4, Gs1Format::Numeric, 6, 6),
+    ("36", 4, Gs1Format::Numeric, 6, 6),
+    ("37", 2, Gs1Format::Numeric, 1, 8),
+    ("390", 4, Gs1Format::Numeric, 1, 15),
+    ("391", 4, Gs1Format::Numeric, 4, 18),
+    ("392", 4, Gs1Format::Numeric, 1, 15),
+    ("393", 4, Gs1Format::Numeric, 4, 18),
+    ("394", 4, Gs1Format::Numeric, 4, 4),
+    ("395", 4, Gs1Format::Numeric, 6, 6),
+    ("400", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("401", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("402", 3, Gs1Format::NumericCheckDigit, 17, 17),
+    ("403", 3, Gs1Format::Alphanumeric, 1, 30),
+    ("410", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("411", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("412", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("413", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("414", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("415", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("416", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("417", 3, Gs1Format::NumericCheckDigit, 13, 13),
+    ("420", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("421", 3, Gs1Format::Alphanumeric, 4, 12),
+    ("422", 3, Gs1Format::Numeric, 3, 3),
+    ("423", 3, Gs1Format::Numeric, 3, 15),
+    ("424", 3, Gs1Format::Numeric, 3, 3),
+    ("425", 3, Gs1Format::Numeric, 3, 15),
+    ("426", 3, Gs1Format::Numeric, 3, 3),
+    ("427", 3, Gs1Format::Alphanumeric, 1, 3),
+    ("4300", 4, Gs1Format::Alphanumeric, 1, 35),
+    ("4301", 4, Gs1Format::Alphanumeric, 1, 35),
+    ("4302", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4303", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4304", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4305", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4306", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4307", 4, Gs1Format::Alphanumeric, 2, 2),
+    ("4308", 4, Gs1Format::Alphanumeric, 1, 30),
+    ("4309", 4, Gs1Format::Numeric, 20, 20),
+    ("4310", 4, Gs1Format::Alphanumeric, 1, 35),
+    ("4311", 4, Gs1Format::Alphanumeric, 1, 35),
+    ("4312", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4313", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4314", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4315", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4316", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("4317", 4, Gs1Format::Alphanumeric, 2, 2),
+    ("4318", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("4319", 4, Gs1Format::Alphanumeric, 1, 30),
+    ("4320", 4, Gs1Format::Alphanumeric, 1, 35),
+    ("4321", 4, Gs1Format::Numeric, 1, 1),
+    ("4322", 4, Gs1Format::Numeric, 1, 1),
+    ("4323", 4, Gs1Format::Numeric, 1, 1),
+    ("4324", 4, Gs1Format::Numeric, 10, 10),
+    ("4325", 4, Gs1Format::Numeric, 10, 10),
+    ("4326", 4, Gs1Format::Date, 6, 6),
+    ("4330", 4, Gs1Format::Alphanumeric, 6, 7),
+    ("4331", 4, Gs1Format::Alphanumeric, 6, 7),
+    ("4332", 4, Gs1Format::Alphanumeric, 6, 7),
+    ("4333", 4, Gs1Format::Alphanumeric, 6, 7),
+    ("7001", 4, Gs1Format::Numeric, 13, 13),
+    ("7002", 4, Gs1Format::Alphanumeric, 1, 30),
+    ("7003", 4, Gs1Format::Numeric, 10, 10),
+    ("7004", 4, Gs1Format::Numeric, 1, 4),
+    ("7005", 4, Gs1Format::Alphanumeric, 1, 12),
+    ("7006", 4, Gs1Format::Date, 6, 6),
+    ("7007", 4, Gs1Format::Numeric, 6, 12),
+    ("7008", 4, Gs1Format::Alphanumeric, 1, 3),
+    ("7009", 4, Gs1Format::Alphanumeric, 1, 10),
+    ("7010", 4, Gs1Format::Alphanumeric, 1, 2),
+    ("7011", 4, Gs1Format::Numeric, 6, 10),
+    ("7020", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("7021", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("7022", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("7023", 4, Gs1Format::Alphanumeric, 1, 30),
+    ("703", 4, Gs1Format::Alphanumeric, 4, 30),
+    ("7040", 4, Gs1Format::Alphanumeric, 4, 4),
+    ("710", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("711", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("712", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("713", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("714", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("715", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("716", 3, Gs1Format::Alphanumeric, 1, 20),
+    ("723", 4, Gs1Format::Alphanumeric, 3, 30),
+    ("7240", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("7241", 4, Gs1Format::Numeric, 2, 2),
+    ("7242", 4, Gs1Format::Alphanumeric, 1, 25),
+    ("7250", 4, Gs1Format::Numeric, 8, 8),
+    ("7251", 4, Gs1Format::Numeric, 12, 12),
+    ("7252", 4, Gs1Format::Numeric, 1, 1),
+    ("7253", 4, Gs1Format::Alphanumeric, 1, 40),
+    ("7254", 4, Gs1Format::Alphanumeric, 1, 40),
+    ("7255", 4, Gs1Format::Alphanumeric, 1, 10),
+    ("7256", 4, Gs1Format::Alphanumeric, 1, 90),
+    ("7257", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("7258", 4, Gs1Format::Alphanumeric, 3, 3),
+    ("7259", 4, Gs1Format::Alphanumeric, 1, 40),
+    ("8001", 4, Gs1Format::Numeric, 14, 14),
+    ("8002", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("8003", 4, Gs1Format::Alphanumeric, 14, 30),
+    ("8004", 4, Gs1Format::Alphanumeric, 1, 30),
+    ("8005", 4, Gs1Format::Numeric, 6, 6),
+    ("8006", 4, Gs1Format::Numeric, 18, 18),
+    ("8007", 4, Gs1Format::Alphanumeric, 1, 34),
+    ("8008", 4, Gs1Format::Numeric, 8, 12),
+    ("8009", 4, Gs1Format::Alphanumeric, 1, 50),
+    ("8010", 4, Gs1Format::Alphanumeric, 1, 30),
+    ("8011", 4, Gs1Format::Numeric, 1, 12),
+    ("8012", 4, Gs1Format::Alphanumeric, 1, 20),
+    ("8013", 4, Gs1Format::Alphanumeric, 1, 25),
+    ("8014", 4, Gs1Format::Alphanumeric, 1, 25),
+    ("8017", 4, Gs1Format::NumericCheckDigit, 18, 18),
+    ("8018", 4, Gs1Format::NumericCheckDigit, 18, 18),
+    ("8019", 4, Gs1Format::Numeric, 1, 10),
+    ("8020", 4, Gs1Format::Alphanumeric, 1, 25),
+    ("8026", 4, Gs1Format::Numeric, 18, 18),
+    ("8030", 4, Gs1Format::Alphanumeric, 1, 90),
+    ("8110", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("8111", 4, Gs1Format::Numeric, 4, 4),
+    ("8112", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("8200", 4, Gs1Format::Alphanumeric, 1, 70),
+    ("90", 2, Gs1Format::Alphanumeric, 1, 30),
+    ("91", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("92", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("93", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("94", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("95", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("96", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("97", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("98", 2, Gs1Format::Alphanumeric, 1, 90),
+    ("99", 2, Gs1Format::Alphanumeric, 1, 90),
+];
+
+/// Is the character in the GS1 character set (ISO/IEC 646 subset, "character set 82")?
+fn is_gs1_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!\"%&'()*+,-./:;<=>?_".contains(c)
+}
+
+/// Compute the GS1 check digit (modulo 10) of a digits string (without check digit)
+///
+/// Used by GTIN, GLN, SSCC as well as EAN and UPC barcodes.
+pub(crate) fn gs1_check_digit(digits: &str) -> u8 {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| u32::from(d - b'0') * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+
+    ((10 - sum % 10) % 10) as u8
+}
+
+/// Is the digits string (check digit included) valid?
+pub(crate) fn gs1_is_check_digit_valid(digits: &str) -> bool {
+    match digits.len() {
+        0 => false,
+        len => {
+            digits.bytes().all(|d| d.is_ascii_digit())
+                && gs1_check_digit(&digits[..len - 1]) == digits.as_bytes()[len - 1] - b'0'
+        }
+    }
+}
+
+/// GS1 element string (application identifier and data)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Gs1Element {
+    pub ai: String,
+    pub data: String,
+}
+
+impl Gs1Element {
+    /// Check the application identifier and its data
+    fn validate(&self) -> std::result::Result<(), String> {
+        let ai = self.ai.as_str();
+        let data = self.data.as_str();
+
+        let Some((_, _, format, min, max)) = APPLICATION_IDENTIFIERS
+            .iter()
+            .find(|(prefix, len, ..)| ai.len() == *len && ai.starts_with(prefix))
+        else {
+            return self.validate_unknown();
+        };
+
+        if !(*min..=*max).contains(&data.len()) {
+            return Err(match min == max {
+                true => format!("AI {ai} data must be {min} characters long"),
+                false => format!("AI {ai} data must be {min} to {max} characters long"),
+            });
+        }
+
+        let is_numeric = data.chars().all(|c| c.is_ascii_digit());
+        match format {
+            Gs1Format::Alphanumeric => Ok(()),
+            _ if !is_numeric => Err(format!("AI {ai} data must be digits")),
+            Gs1Format::Numeric => Ok(()),
+            Gs1Format::NumericCheckDigit => match gs1_is_check_digit_valid(data) {
+                true => Ok(()),
+                false => Err(format!("AI {ai} check digit is invalid")),
+            },
+            Gs1Format::Date => {
+                let month = &data[2..4];
+                let day = &data[4..6];
+                match ("01"..="12").contains(&month) && ("00"..="31").contains(&day) {
+                    true => Ok(()),
+                    false => Err(format!("AI {ai} date must be YYMMDD")),
+                }
+            }
+        }
+    }
+
+    /// Check an application identifier missing from the table, with the rules common to all AIs
+    ///
+    /// The AI must not overlap a known AI (AIs are prefix-free), its data has the predefined length
+    /// of its first two digits (if any) and at most 90 characters.
+    fn validate_unknown(&self) -> std::result::Result<(), String> {
+        let ai = self.ai.as_str();
+
+        if !(2..=4).contains(&ai.len())
+            || APPLICATION_IDENTIFIERS
+                .iter()
+                .any(|(prefix, len, ..)| ai.starts_with(prefix) || (prefix.starts_with(ai) && ai.len() < *len))
+        {
+            return Err(format!("invalid application identifier {ai}"));
+        }
+
+        let (min, max) = match self.predefined_length() {
+            Some(len) if len > ai.len() => (len - ai.len(), len - ai.len()),
+            Some(_) => return Err(format!("invalid application identifier {ai}")),
+            None => (1, GS1_DATA_MAX_LENGTH),
+        };
+        match (min..=max).contains(&self.data.len()) {
+            true => Ok(()),
+            false if min == max => Err(format!("AI {ai} data must be {min} characters long")),
+            false => Err(format!("AI {ai} data must be {min} to {max} characters long")),
+        }
+    }
+
+    /// Get the predefined total length (AI included), if any
+    fn predefined_length(&self) -> Option<usize> {
+        PREDEFINED_LENGTHS
+            .iter()
+            .find(|(prefix, _)| self.ai.starts_with(prefix))
+            .map(|(_, len)| *len)
+    }
+}
+
+/// Parse element strings written with AIs between parentheses
+pub(crate) fn gs1_parse(data: &str) -> Result<Vec<Gs1Element>> {
+    let invalid = |reason: &str| PrinterError::Input(format!("invalid GS1 element string ({reason}): {data}"));
+
+    if !data.starts_with('(') {
+        return Err(invalid("must start with an application identifier between parentheses"));
+    }
+
+    let mut elements = Vec::new();
+    for part in data.split('(').skip(1) {
+        let (ai, value) = part.split_once(')').ok_or_else(|| invalid("missing ')'"))?;
+
+        if !(2..=4).contains(&ai.len()) || !ai.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid(&format!("application identifier {ai}")));
+        }
+        if value.is_empty() || !value.chars().all(|c| is_gs1_char(c) && c != ')') {
+            return Err(invalid(&format!("data of AI {ai}")));
+        }
+
+        let element = Gs1Element {
+            ai: ai.to_owned(),
+            data: value.to_owned(),
+        };
+        element.validate().map_err(|reason| invalid(&reason))?;
+        elements.push(element);
+    }
+
+    Ok(elements)
+}
+
+/// Get the GTIN data (first 13 digits) from a GTIN with or without check digit, optionally prefixed by `(01)`
+pub(crate) fn gs1_gtin_data(data: &str) -> Option<&str> {
+    let gtin = data.strip_prefix("(01)").unwrap_or(data);
+    let is_valid = gtin.chars().all(|c| c.is_ascii_digit())
+        && match gtin.len() {
+            13 => true,
+            14 => gs1_is_check_digit_valid(gtin),
+            _ => false,
+        };
+
+    match is_valid {
+        true => Some(&gtin[..13]),
+        false => None,
+    }
+}
+
+/// Concatenate element strings, with a FNC1 `separator` after variable length element strings
+pub(crate) fn gs1_encode(elements: &[Gs1Element], separator: &str) -> String {
+    let mut result = String::new();
+
+    for (index, element) in elements.iter().enumerate() {
+        result.push_str(&element.ai);
+        result.push_str(&element.data);
+        if element.predefined_length().is_none() && index + 1 < elements.len() {
+            result.push_str(separator);
+        }
+    }
+
+    result
+}
+
+/// GS1 Digital Link primary keys and their qualifiers (in path order)
+const DIGITAL_LINK_KEYS: [(&str, &[&str]); 15] = [
+    ("01", &["22", "10", "21"]),
+    ("00", &[]),
+    ("253", &[]),
+    ("255", &[]),
+    ("401", &[]),
+    ("402", &[]),
+    ("414", &["254"]),
+    ("417", &[]),
+    ("8003", &[]),
+    ("8004", &[]),
+    ("8006", &["22", "10", "21"]),
+    ("8010", &["8011"]),
+    ("8013", &[]),
+    ("8017", &["8019"]),
+    ("8018", &["8019"]),
+];
+
+/// Percent-encode a value for a URI (RFC 3986 unreserved characters are kept)
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// GS1 element strings builder
+///
+/// Application identifiers (AI) and their data are checked against the GS1 AI dictionary
+/// (length, numeric or alphanumeric data, date and check digit).
+/// AIs which are not in the dictionary (for example newly assigned ones) are accepted with generic checks:
+/// no overlap with a known AI, GS1 characters and at most 90 characters.
+///
+/// The element strings can then be written in the form expected by each symbology:
+/// - [`to_string`](ToString::to_string): AIs between parentheses, used by GS1-128, GS1 DataBar Expanded
+///   and GS1 DataBar Expanded Stacked
+/// - [`to_raw`](Self::to_raw): concatenated element strings with `GS` (`0x1D`) separators,
+///   used by GS1 DataMatrix and GS1 QR code
+/// - [`to_digital_link`](Self::to_digital_link): GS1 Digital Link URI for QR codes
+///
+/// ```
+/// use escpos::utils::Gs1ElementString;
+///
+/// let element_string = Gs1ElementString::new()
+///     .gtin("09501101530003").unwrap()
+///     .expiry_date("250101").unwrap()
+///     .batch("ABC123").unwrap()
+///     .serial("42").unwrap();
+///
+/// assert_eq!(element_string.to_string(), "(01)09501101530003(17)250101(10)ABC123(21)42");
+/// assert_eq!(element_string.to_raw(), "01095011015300031725010110ABC123\x1D2142");
+/// assert_eq!(
+///     element_string.to_digital_link("https://example.com").unwrap(),
+///     "https://example.com/01/09501101530003/10/ABC123/21/42?17=250101"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gs1ElementString {
+    elements: Vec<Gs1Element>,
+}
+
+impl Gs1ElementString {
+    /// Create an empty element string
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse element strings written with AIs between parentheses (`(01)09501101530003(10)ABC123`)
+    pub fn parse(data: &str) -> Result<Self> {
+        gs1_parse(data)?
+            .into_iter()
+            .try_fold(Self::new(), |element_string, element| {
+                element_string.add(&element.ai, &element.data)
+            })
+    }
+
+    /// Add an element string
+    pub fn add(mut self, ai: &str, data: &str) -> Result<Self> {
+        if !ai.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PrinterError::Input(format!("invalid application identifier {ai}")));
+        }
+        if !data.chars().all(|c| is_gs1_char(c) && c != '(' && c != ')') {
+            return Err(PrinterError::Input(format!("invalid data of AI {ai}: {data}")));
+        }
+        if self.elements.iter().any(|element| element.ai == ai) {
+            return Err(PrinterError::Input(format!("duplicate application identifier {ai}")));
+        }
+
+        let element = Gs1Element {
+            ai: ai.to_owned(),
+            data: data.to_owned(),
+        };
+        element.validate().map_err(PrinterError::Input)?;
+        self.elements.push(element);
+
+        Ok(self)
+    }
+
+    /// Add a GTIN (AI 01)
+    ///
+    /// GTIN-8, GTIN-12 and GTIN-13 are padded with zeros to 14 digits.
+    pub fn gtin(self, gtin: &str) -> Result<Self> {
+        if ![8, 12, 13, 14].contains(&gtin.len()) {
+            return Err(PrinterError::Input(format!("invalid GTIN length: {gtin}")));
+        }
+
+        self.add("01", &format!("{gtin:0>14}"))
+    }
+
+    /// Add a batch or lot number (AI 10)
+    pub fn batch(self, batch: &str) -> Result<Self> {
+        self.add("10", batch)
+    }
+
+    /// Add an expiration date (AI 17, YYMMDD)
+    pub fn expiry_date(self, date: &str) -> Result<Self> {
+        self.add("17", date)
+    }
+
+    /// Add a serial number (AI 21)
+    pub fn serial(self, serial: &str) -> Result<Self> {
+        self.add("21", serial)
+    }
+
+    /// Get the element strings (AI and data)
+    pub fn elements(&self) -> Vec<(&str, &str)> {
+        self.elements
+            .iter()
+            .map(|element| (element.ai.as_str(), element.data.as_str()))
+            .collect()
+    }
+
+    /// Is the element string empty?
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Get the concatenated element strings, with `GS` (`0x1D`) separators after variable length element strings
+    pub fn to_raw(&self) -> String {
+        gs1_encode(&self.elements, "\x1D")
+    }
+
+    /// Get the GS1 Digital Link URI (`https://id.gs1.org` is the GS1 resolver)
+    ///
+    /// The first primary key (GTIN, SSCC, GLN, etc.) and its qualifiers are written in the path,
+    /// other element strings as query parameters.
+    pub fn to_digital_link(&self, domain: &str) -> Result<String> {
+        let (key, qualifiers) = self
+            .elements
+            .iter()
+            .find_map(|element| DIGITAL_LINK_KEYS.iter().find(|(key, _)| *key == element.ai))
+            .ok_or_else(|| PrinterError::Input("GS1 Digital Link needs a primary key (GTIN, SSCC, etc.)".to_owned()))?;
+
+        let mut uri = domain.trim_end_matches('/').to_owned();
+        let mut in_path = vec![*key];
+        in_path.extend(qualifiers.iter());
+
+        for ai in &in_path {
+            if let Some(element) = self.elements.iter().find(|element| element.ai == *ai) {
+                uri.push_str(&format!("/{}/{}", element.ai, percent_encode(&element.data)));
+            }
+        }
+
+        let query: Vec<String> = self
+            .elements
+            .iter()
+            .filter(|element| !in_path.contains(&element.ai.as_str()))
+            .map(|element| format!("{}={}", element.ai, percent_encode(&element.data)))
+            .collect();
+        if !query.is_empty() {
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+
+        Ok(uri)
+    }
+}
+
+impl fmt::Display for Gs1ElementString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for element in &self.elements {
+            write!(f, "({}){}", element.ai, element.data)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gs1_check_digit() {
+        assert_eq!(gs1_check_digit("0950110153000"), 3);
+        assert_eq!(gs1_check_digit("400638133393"), 1);
+        assert_eq!(gs1_check_digit("9638507"), 4);
+        assert_eq!(gs1_check_digit("00000000000000000"), 0);
+
+        assert!(gs1_is_check_digit_valid("09501101530003"));
+        assert!(gs1_is_check_digit_valid("4006381333931"));
+        assert!(!gs1_is_check_digit_valid("4006381333932"));
+        assert!(!gs1_is_check_digit_valid("40063813339a1"));
+        assert!(!gs1_is_check_digit_valid(""));
+    }
+
+    #[test]
+    fn test_gs1_parse() {
+        let elements = gs1_parse("(01)09501101530003(10)AB-12").unwrap();
+        assert_eq!(
+            elements,
+            vec![
+                Gs1Element {
+                    ai: "01".to_owned(),
+                    data: "09501101530003".to_owned()
+                },
+                Gs1Element {
+                    ai: "10".to_owned(),
+                    data: "AB-12".to_owned()
+                },
+            ]
+        );
+
+        assert!(gs1_parse("").is_err());
+        assert!(gs1_parse("0109501101530003").is_err());
+        assert!(gs1_parse("(01").is_err());
+        assert!(gs1_parse("(01)").is_err());
+        assert!(gs1_parse("(1)12").is_err());
+        assert!(gs1_parse("(0A)12").is_err());
+        assert!(gs1_parse("(10)AB{12").is_err());
+        assert!(gs1_parse("(01)0950110153000").is_err());
+        assert!(gs1_parse("(17)2501011").is_err());
+        assert!(gs1_parse("(17)25O101").is_err());
+        assert!(gs1_parse("(01)09501101530004").is_err());
+        assert!(gs1_parse("(414)5412345000014").is_err());
+        assert!(gs1_parse("(414)5412345000013").is_ok());
+    }
+
+    #[test]
+    fn test_gs1_parse_application_identifiers() {
+        assert!(gs1_parse("(00)106141411234567897").is_ok());
+        assert!(gs1_parse("(3103)000189(3922)0299").is_ok());
+        assert!(gs1_parse("(8200)http://example.com").is_ok());
+        assert!(gs1_parse("(11)250100").is_ok());
+        assert!(gs1_parse("(7003)2501011230").is_ok());
+        assert!(gs1_parse("(91)ABC-123(99)XYZ").is_ok());
+
+        assert!(gs1_parse("(4300)ACME(4307)FR(4321)1(4326)250101").is_ok());
+        assert!(gs1_parse("(7240)PROTOCOL-1(7250)19800101(8030)a-Z_09").is_ok());
+        assert!(gs1_parse("(7230)EMABC(7239)XY1").is_ok());
+        assert!(gs1_parse("(4307)FRA").is_err());
+        assert!(gs1_parse("(4321)2A").is_err());
+
+        // AI missing from the table: generic checks
+        assert!(gs1_parse("(8300)ABC").is_ok());
+        assert!(gs1_parse("(14)250101").is_ok());
+        assert!(gs1_parse("(14)2501").is_err());
+        assert!(gs1_parse(&format!("(8300){}", "A".repeat(91))).is_err());
+        assert!(gs1_parse("(310)000189").is_err());
+        assert!(gs1_parse("(23)ABC").is_err());
+        assert!(gs1_parse("(0100)ABC").is_err());
+
+        // Length
+        assert!(gs1_parse("(10)ABCDEFGHIJKLMNOPQRSTU").is_err());
+        assert!(gs1_parse("(422)25").is_err());
+        assert!(gs1_parse("(3103)00018").is_err());
+
+        // Format
+        assert!(gs1_parse("(30)1O").is_err());
+        assert!(gs1_parse("(17)251301").is_err());
+        assert!(gs1_parse("(17)250132").is_err());
+        assert!(gs1_parse("(8006)12345678901234567A").is_err());
+        assert!(gs1_parse("(00)106141411234567898").is_err());
+    }
+
+    #[test]
+    fn test_gs1_gtin_data() {
+        assert_eq!(gs1_gtin_data("0950110153000"), Some("0950110153000"));
+        assert_eq!(gs1_gtin_data("09501101530003"), Some("0950110153000"));
+        assert_eq!(gs1_gtin_data("(01)09501101530003"), Some("0950110153000"));
+
+        assert_eq!(gs1_gtin_data("09501101530004"), None);
+        assert_eq!(gs1_gtin_data("095011015300"), None);
+        assert_eq!(gs1_gtin_data("(02)09501101530003"), None);
+    }
+
+    #[test]
+    fn test_gs1_encode() {
+        let encode = |data| gs1_encode(&gs1_parse(data).unwrap(), "{1");
+
+        assert_eq!(encode("(01)09501101530003"), "0109501101530003");
+        assert_eq!(encode("(01)09501101530003(17)250101"), "010950110153000317250101");
+        assert_eq!(encode("(10)ABC(17)250101"), "10ABC{117250101");
+        assert_eq!(encode("(17)250101(10)ABC"), "1725010110ABC");
+        assert_eq!(encode("(3103)000189(21)12(10)ABC"), "31030001892112{110ABC");
+    }
+
+    #[test]
+    fn test_gs1_element_string() {
+        let element_string = Gs1ElementString::new()
+            .gtin("09501101530003")
+            .unwrap()
+            .add("3103", "000189")
+            .unwrap()
+            .batch("AB/12")
+            .unwrap();
+
+        assert_eq!(
+            element_string.elements(),
+            vec![("01", "09501101530003"), ("3103", "000189"), ("10", "AB/12")]
+        );
+        assert_eq!(element_string.to_string(), "(01)09501101530003(3103)000189(10)AB/12");
+        assert_eq!(element_string.to_raw(), "0109501101530003310300018910AB/12");
+        assert_eq!(
+            Gs1ElementString::parse(&element_string.to_string()).unwrap(),
+            element_string
+        );
+
+        assert!(Gs1ElementString::new().is_empty());
+        assert!(Gs1ElementString::new().add("10", "AB(1)").is_err());
+        assert!(Gs1ElementString::new().add("1O", "AB").is_err());
+        assert!(Gs1ElementString::new().add("14", "AB").is_err());
+        assert!(Gs1ElementString::new().add("17", "2501").is_err());
+        assert!(Gs1ElementString::new().batch("A").unwrap().batch("B").is_err());
+        assert!(Gs1ElementString::parse("(10)A(10)B").is_err());
+    }
+
+    #[test]
+    fn test_gs1_element_string_gtin() {
+        let gtin = |data| Gs1ElementString::new().gtin(data).map(|e| e.to_string());
+
+        assert_eq!(gtin("09501101530003").unwrap(), "(01)09501101530003");
+        assert_eq!(gtin("4006381333931").unwrap(), "(01)04006381333931");
+        assert_eq!(gtin("036000291452").unwrap(), "(01)00036000291452");
+        assert_eq!(gtin("96385074").unwrap(), "(01)00000096385074");
+
+        assert!(gtin("09501101530004").is_err());
+        assert!(gtin("0950110153000a").is_err());
+        assert!(gtin("0950110153000").is_err());
+        assert!(gtin("123").is_err());
+    }
+
+    #[test]
+    fn test_gs1_element_string_digital_link() {
+        let element_string = Gs1ElementString::parse("(17)250101(21)A/B(01)09501101530003(10)L1").unwrap();
+        assert_eq!(
+            element_string.to_digital_link("https://id.gs1.org/").unwrap(),
+            "https://id.gs1.org/01/09501101530003/10/L1/21/A%2FB?17=250101"
+        );
+
+        let element_string = Gs1ElementString::parse("(00)106141411234567897").unwrap();
+        assert_eq!(
+            element_string.to_digital_link("https://example.com").unwrap(),
+            "https://example.com/00/106141411234567897"
+        );
+
+        assert!(Gs1ElementString::parse("(10)L1")
+            .unwrap()
+            .to_digital_link("https://id.gs1.org")
+            .is_err());
+    }
+}
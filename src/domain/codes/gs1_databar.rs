@@ -0,0 +1,163 @@
+//! Linear GS1 DataBar
+
+#![cfg(feature = "barcodes")]
+
+use super::{barcodes::BarcodeOption, gs1::*};
+use crate::errors::{PrinterError, Result};
+use std::fmt;
+
+/// Linear GS1 DataBar type
+#[derive(Debug, Default, Clone, Copy)]
+pub enum GS1DataBarType {
+    #[default]
+    Omnidirectional,
+    Truncated,
+    Limited,
+    Expanded,
+}
+
+impl fmt::Display for GS1DataBarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GS1DataBarType::Omnidirectional => write!(f, "GS1 DataBar Omnidirectional"),
+            GS1DataBarType::Truncated => write!(f, "GS1 DataBar Truncated"),
+            GS1DataBarType::Limited => write!(f, "GS1 DataBar Limited"),
+            GS1DataBarType::Expanded => write!(f, "GS1 DataBar Expanded"),
+        }
+    }
+}
+
+impl From<GS1DataBarType> for u8 {
+    fn from(value: GS1DataBarType) -> Self {
+        match value {
+            GS1DataBarType::Omnidirectional => 75,
+            GS1DataBarType::Truncated => 76,
+            GS1DataBarType::Limited => 77,
+            GS1DataBarType::Expanded => 78,
+        }
+    }
+}
+
+/// Linear GS1 DataBar option
+#[derive(Debug, Clone, Default)]
+pub struct GS1DataBarOption {
+    code_type: GS1DataBarType,
+    barcode_option: BarcodeOption,
+}
+
+impl GS1DataBarOption {
+    /// Create a new `GS1DataBarOption`
+    pub fn new(code_type: GS1DataBarType, barcode_option: BarcodeOption) -> Self {
+        Self {
+            code_type,
+            barcode_option,
+        }
+    }
+
+    /// Get code type
+    pub fn code_type(&self) -> GS1DataBarType {
+        self.code_type
+    }
+
+    /// Get barcode option (width, height, HRI font and position)
+    pub fn barcode_option(&self) -> BarcodeOption {
+        self.barcode_option.clone()
+    }
+}
+
+/// Linear GS1 DataBar
+///
+/// Data of Omnidirectional, Truncated and Limited types is a GTIN: 13 digits (the check digit is computed)
+/// or 14 digits (the check digit is verified), optionally prefixed by `(01)`.
+///
+/// Data of Expanded type is written as element strings with application identifiers between parentheses
+/// (`(01)09501101530003(3103)000189`).
+#[derive(Debug)]
+pub struct GS1DataBar {
+    pub data: String,
+    pub option: GS1DataBarOption,
+}
+
+impl GS1DataBar {
+    /// Create a new `GS1DataBar`
+    pub fn new(data: &str, option: GS1DataBarOption) -> Result<Self> {
+        Self::printer_data(data, option.code_type)?;
+
+        Ok(Self {
+            data: data.to_string(),
+            option,
+        })
+    }
+
+    /// Check data and get the data sent to the printer
+    pub(crate) fn printer_data(data: &str, code_type: GS1DataBarType) -> Result<String> {
+        match code_type {
+            GS1DataBarType::Omnidirectional | GS1DataBarType::Truncated | GS1DataBarType::Limited => {
+                let gtin = gs1_gtin_data(data)
+                    .ok_or_else(|| PrinterError::Input(format!("invalid {code_type} data: {data}")))?;
+
+                // Limited type only encodes GTIN with indicator digit 0 or 1
+                if matches!(code_type, GS1DataBarType::Limited) && !gtin.starts_with(['0', '1']) {
+                    return Err(PrinterError::Input(format!(
+                        "invalid {code_type} data (indicator digit must be 0 or 1): {data}"
+                    )));
+                }
+
+                // The printer computes the check digit
+                Ok(gtin.to_string())
+            }
+            GS1DataBarType::Expanded => {
+                let data = gs1_encode(&gs1_parse(data)?, "{1");
+                if data.len() > 255 {
+                    return Err(PrinterError::Input(format!("{code_type} data too long: {data}")));
+                }
+                Ok(data)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gs1_databar_printer_data() {
+        for code_type in [GS1DataBarType::Omnidirectional, GS1DataBarType::Truncated] {
+            assert_eq!(
+                GS1DataBar::printer_data("0950110153000", code_type).unwrap(),
+                "0950110153000"
+            );
+            assert_eq!(
+                GS1DataBar::printer_data("09501101530003", code_type).unwrap(),
+                "0950110153000"
+            );
+            assert_eq!(
+                GS1DataBar::printer_data("(01)09501101530003", code_type).unwrap(),
+                "0950110153000"
+            );
+            assert_eq!(
+                GS1DataBar::printer_data("2950110153000", code_type).unwrap(),
+                "2950110153000"
+            );
+
+            assert!(GS1DataBar::printer_data("09501101530004", code_type).is_err());
+            assert!(GS1DataBar::printer_data("095011015300", code_type).is_err());
+            assert!(GS1DataBar::printer_data("095011015300a", code_type).is_err());
+            assert!(GS1DataBar::printer_data("(02)09501101530003", code_type).is_err());
+        }
+
+        assert_eq!(
+            GS1DataBar::printer_data("1950110153000", GS1DataBarType::Limited).unwrap(),
+            "1950110153000"
+        );
+        assert!(GS1DataBar::printer_data("2950110153000", GS1DataBarType::Limited).is_err());
+
+        assert_eq!(
+            GS1DataBar::printer_data("(01)09501101530003(10)AB(3103)000189", GS1DataBarType::Expanded).unwrap(),
+            "010950110153000310AB{13103000189"
+        );
+        assert!(GS1DataBar::printer_data("09501101530003", GS1DataBarType::Expanded).is_err());
+        assert!(GS1DataBar::printer_data("(01)09501101530004", GS1DataBarType::Expanded).is_err());
+    }
+}
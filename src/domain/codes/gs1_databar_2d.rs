@@ -1,13 +1,11 @@
 //! 2D GS1 DataBar
 
+#![cfg(feature = "codes_2d")]
+
+use super::gs1::*;
 use crate::errors::{PrinterError, Result};
 use std::fmt;
 
-const EXPANDED_STACKED_VALID_CHARS: [char; 36] = [
-    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', ' ', '!', '"', '%', '$', '\'', '(', ')', '*',
-    '+', ',', '-', '.', '/', ':', ';', '<', '=', '>', '?', '_', '{',
-];
-
 #[derive(Debug, Default, Clone, Copy)]
 pub enum GS1DataBar2DType {
     #[default]
@@ -64,17 +62,101 @@ impl From<GS1DataBar2DWidth> for u8 {
     }
 }
 
+/// GS1 DataBar Expanded Stacked maximum width
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum GS1DataBar2DExpandedWidth {
+    /// No restriction
+    #[default]
+    Auto,
+    /// Maximum width in dots (106 - 3072)
+    Dots(u16),
+    /// Number of segments per row (even number, 2 - 22)
+    Segments(u8),
+}
+
+impl fmt::Display for GS1DataBar2DExpandedWidth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GS1DataBar2DExpandedWidth::Auto => write!(f, "GS1 DataBar Expanded Stacked auto width"),
+            GS1DataBar2DExpandedWidth::Dots(dots) => write!(f, "GS1 DataBar Expanded Stacked {dots} dots max width"),
+            GS1DataBar2DExpandedWidth::Segments(segments) => {
+                write!(f, "GS1 DataBar Expanded Stacked {segments} segments per row")
+            }
+        }
+    }
+}
+
+impl GS1DataBar2DExpandedWidth {
+    /// Get the maximum width in dots (0 for no restriction)
+    pub(crate) fn dots(&self, width: GS1DataBar2DWidth) -> Result<u16> {
+        let dots = match *self {
+            GS1DataBar2DExpandedWidth::Auto => return Ok(0),
+            GS1DataBar2DExpandedWidth::Dots(dots) => dots,
+            GS1DataBar2DExpandedWidth::Segments(segments) => {
+                if !(2..=22).contains(&segments) || !segments.is_multiple_of(2) {
+                    return Err(PrinterError::Input(format!(
+                        "invalid GS1 DataBar Expanded Stacked segments per row: {segments}"
+                    )));
+                }
+
+                // Segments are 17 modules wide, with a 15 modules finder pattern for each pair of segments
+                // and 2 modules guards on each side
+                let modules = u16::from(segments) * 17 + u16::from(segments / 2) * 15 + 4;
+                modules * u16::from(u8::from(width))
+            }
+        };
+
+        if !(106..=3072).contains(&dots) {
+            return Err(PrinterError::Input(format!(
+                "invalid GS1 DataBar Expanded Stacked max width: {dots} dots (106 - 3072)"
+            )));
+        }
+
+        Ok(dots)
+    }
+}
+
 /// GS1 DataBar option
 #[derive(Debug, Clone, Default)]
 pub struct GS1DataBar2DOption {
     width: GS1DataBar2DWidth,
     code_type: GS1DataBar2DType,
+    expanded_max_width: GS1DataBar2DExpandedWidth,
 }
 
 impl GS1DataBar2DOption {
     /// Create a new `GS1DataBar2DOption`
     pub fn new(width: GS1DataBar2DWidth, code_type: GS1DataBar2DType) -> Self {
-        Self { width, code_type }
+        Self {
+            width,
+            code_type,
+            expanded_max_width: GS1DataBar2DExpandedWidth::default(),
+        }
+    }
+
+    /// Set the maximum width (used by GS1 DataBar Expanded Stacked only)
+    ///
+    /// ```
+    /// use escpos::utils::*;
+    ///
+    /// let option = GS1DataBar2DOption::new(GS1DataBar2DWidth::M, GS1DataBar2DType::ExpandedStacked)
+    ///     .with_expanded_max_width(GS1DataBar2DExpandedWidth::Segments(6))
+    ///     .unwrap();
+    /// assert_eq!(option.expanded_max_width(), GS1DataBar2DExpandedWidth::Segments(6));
+    ///
+    /// assert!(GS1DataBar2DOption::default()
+    ///     .with_expanded_max_width(GS1DataBar2DExpandedWidth::Dots(50))
+    ///     .is_err());
+    /// ```
+    pub fn with_expanded_max_width(mut self, max_width: GS1DataBar2DExpandedWidth) -> Result<Self> {
+        max_width.dots(self.width)?;
+        self.expanded_max_width = max_width;
+        Ok(self)
+    }
+
+    /// Get expanded stacked maximum width
+    pub fn expanded_max_width(&self) -> GS1DataBar2DExpandedWidth {
+        self.expanded_max_width
     }
 
     /// Get width
@@ -98,7 +180,7 @@ pub struct GS1DataBar2D {
 impl GS1DataBar2D {
     /// Create a new `GS1DataBar2D`
     pub fn new(data: &str, option: GS1DataBar2DOption) -> Result<Self> {
-        Self::check_data(data, &option.code_type)?;
+        Self::printer_data(data, option.code_type)?;
 
         Ok(Self {
             data: data.to_string(),
@@ -106,29 +188,21 @@ impl GS1DataBar2D {
         })
     }
 
-    /// Check data
-    fn check_data(data: &str, code_type: &GS1DataBar2DType) -> Result<()> {
-        let data_len = data.len();
-        let is_data_all_digits = data.chars().all(|c| c.is_ascii_digit());
-
+    /// Check data and get the data sent to the printer
+    ///
+    /// Stacked types encode a GTIN (13 digits, or 14 digits with a valid check digit).
+    /// Expanded Stacked type encodes GS1 element strings with application identifiers between parentheses.
+    pub(crate) fn printer_data(data: &str, code_type: GS1DataBar2DType) -> Result<String> {
         match code_type {
-            GS1DataBar2DType::Stacked | GS1DataBar2DType::StackedOmnidirectional => {
-                if is_data_all_digits && data_len == 13 {
-                    Ok(())
-                } else {
-                    Err(PrinterError::Input(format!(
-                        "invalid GS1 DataBar Stacked Omnidirectional data: {data}"
-                    )))
-                }
-            }
+            GS1DataBar2DType::Stacked | GS1DataBar2DType::StackedOmnidirectional => gs1_gtin_data(data)
+                .map(|gtin| gtin.to_string())
+                .ok_or_else(|| PrinterError::Input(format!("invalid {code_type} data: {data}"))),
             GS1DataBar2DType::ExpandedStacked => {
-                if (0..256).contains(&data_len) && data.chars().all(|c| EXPANDED_STACKED_VALID_CHARS.contains(&c)) {
-                    Ok(())
-                } else {
-                    Err(PrinterError::Input(format!(
-                        "invalid GS1 DataBar Stacked Omnidirectional data: {data}"
-                    )))
+                let data = gs1_encode(&gs1_parse(data)?, "{1");
+                if data.len() > 255 {
+                    return Err(PrinterError::Input(format!("{code_type} data too long: {data}")));
                 }
+                Ok(data)
             }
         }
     }
@@ -139,23 +213,55 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_gs1_databar_stacked_check_data() {
-        assert!(GS1DataBar2D::check_data("1234560987654", &GS1DataBar2DType::Stacked).is_ok());
-        assert!(GS1DataBar2D::check_data("123456098765", &GS1DataBar2DType::Stacked).is_err());
-        assert!(GS1DataBar2D::check_data("123456098765d", &GS1DataBar2DType::Stacked).is_err());
-        assert!(GS1DataBar2D::check_data("azs,rfT;YTfGq", &GS1DataBar2DType::Stacked).is_err());
+    fn test_gs1_databar_stacked_printer_data() {
+        for code_type in [GS1DataBar2DType::Stacked, GS1DataBar2DType::StackedOmnidirectional] {
+            assert_eq!(
+                GS1DataBar2D::printer_data("1234560987654", code_type).unwrap(),
+                "1234560987654"
+            );
+            assert_eq!(
+                GS1DataBar2D::printer_data("09501101530003", code_type).unwrap(),
+                "0950110153000"
+            );
+            assert!(GS1DataBar2D::printer_data("09501101530004", code_type).is_err());
+            assert!(GS1DataBar2D::printer_data("123456098765", code_type).is_err());
+            assert!(GS1DataBar2D::printer_data("123456098765d", code_type).is_err());
+            assert!(GS1DataBar2D::printer_data("azs,rfT;YTfGq", code_type).is_err());
+        }
+    }
+
+    #[test]
+    fn test_gs1_databar_expanded_printer_data() {
+        let code_type = GS1DataBar2DType::ExpandedStacked;
 
-        assert!(GS1DataBar2D::check_data("1234560987654", &GS1DataBar2DType::StackedOmnidirectional).is_ok());
-        assert!(GS1DataBar2D::check_data("123456098765", &GS1DataBar2DType::StackedOmnidirectional).is_err());
-        assert!(GS1DataBar2D::check_data("123456098765d", &GS1DataBar2DType::StackedOmnidirectional).is_err());
-        assert!(GS1DataBar2D::check_data("azs,rfT;YTfGq", &GS1DataBar2DType::StackedOmnidirectional).is_err());
+        assert_eq!(
+            GS1DataBar2D::printer_data("(01)09501101530003(8020)AC!45(3103)000189", code_type).unwrap(),
+            "01095011015300038020AC!45{13103000189"
+        );
+        assert!(GS1DataBar2D::printer_data("1234560987654", code_type).is_err());
+        assert!(GS1DataBar2D::printer_data("(01)09501101530004", code_type).is_err());
+        assert!(GS1DataBar2D::printer_data("(8020)", code_type).is_err());
+        assert!(GS1DataBar2D::printer_data("", code_type).is_err());
     }
 
     #[test]
-    fn test_gs1_databar_expanded_check_data() {
-        assert!(GS1DataBar2D::check_data("1234560987654", &GS1DataBar2DType::ExpandedStacked).is_ok());
-        assert!(GS1DataBar2D::check_data("123456098765AC!,", &GS1DataBar2DType::ExpandedStacked).is_ok());
-        assert!(GS1DataBar2D::check_data("123456098765d", &GS1DataBar2DType::ExpandedStacked).is_err());
-        assert!(GS1DataBar2D::check_data("", &GS1DataBar2DType::ExpandedStacked).is_ok());
+    fn test_gs1_databar_expanded_width_dots() {
+        let width = GS1DataBar2DWidth::M;
+
+        assert_eq!(GS1DataBar2DExpandedWidth::Auto.dots(width).unwrap(), 0);
+        assert_eq!(GS1DataBar2DExpandedWidth::Dots(384).dots(width).unwrap(), 384);
+        assert_eq!(GS1DataBar2DExpandedWidth::Segments(6).dots(width).unwrap(), 151);
+        assert_eq!(
+            GS1DataBar2DExpandedWidth::Segments(4)
+                .dots(GS1DataBar2DWidth::L)
+                .unwrap(),
+            102 * 4
+        );
+
+        assert!(GS1DataBar2DExpandedWidth::Dots(105).dots(width).is_err());
+        assert!(GS1DataBar2DExpandedWidth::Dots(3073).dots(width).is_err());
+        assert!(GS1DataBar2DExpandedWidth::Segments(3).dots(width).is_err());
+        assert!(GS1DataBar2DExpandedWidth::Segments(24).dots(width).is_err());
+        assert!(GS1DataBar2DExpandedWidth::Segments(4).dots(width).is_err());
     }
 }
@@ -0,0 +1,299 @@
+//! Macro PDF417
+//!
+//! Macro PDF417 spreads data over a sequence of PDF417 symbols. Each symbol ends with a control block
+//! holding its segment index, the file ID shared by the sequence and optional fields (file name, segment count,
+//! timestamp).
+
+#![cfg(feature = "codes_2d")]
+
+use super::pdf417::*;
+use crate::errors::{PrinterError, Result};
+use std::ops::Range;
+
+/// Macro PDF417 control block marker
+const MACRO_MARKER: u16 = 928;
+
+/// Macro PDF417 optional field marker
+const OPTIONAL_FIELD: u16 = 923;
+
+/// Macro PDF417 last segment terminator
+const LAST_SEGMENT: u16 = 922;
+
+/// Maximum number of segments
+const MAX_SEGMENTS: usize = 99_999;
+
+/// Macro PDF417 option (control block fields)
+#[derive(Debug, Clone)]
+pub struct MacroPdf417Option {
+    file_id: Vec<u16>,
+    file_name: Option<String>,
+    timestamp: Option<u64>,
+    segment_count: bool,
+}
+
+impl MacroPdf417Option {
+    /// Create a new `MacroPdf417Option`
+    ///
+    /// The file ID is a string of digits, each group of 3 digits (000 - 899) being a codeword.
+    /// The segment count is written in the control blocks.
+    pub fn new(file_id: &str) -> Result<Self> {
+        let invalid = || PrinterError::Input(format!("invalid Macro PDF417 file ID: {file_id}"));
+        if file_id.is_empty() || !file_id.len().is_multiple_of(3) || !file_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let file_id = file_id
+            .as_bytes()
+            .chunks(3)
+            .map(|group| {
+                std::str::from_utf8(group)
+                    .ok()
+                    .and_then(|group| group.parse::<u16>().ok())
+            })
+            .map(|codeword| codeword.filter(|codeword| *codeword < 900).ok_or_else(invalid))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            file_id,
+            file_name: None,
+            timestamp: None,
+            segment_count: true,
+        })
+    }
+
+    /// Set the file name (text compaction characters: ASCII letters, digits, spaces and punctuation)
+    pub fn with_file_name(mut self, file_name: &str) -> Result<Self> {
+        text_compaction(file_name.as_bytes())?;
+        self.file_name = Some(file_name.to_string());
+        Ok(self)
+    }
+
+    /// Set the timestamp (seconds since 1970-01-01 00:00:00 UTC)
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Write the segment count in the control blocks (`true` by default)
+    pub fn with_segment_count(mut self, segment_count: bool) -> Self {
+        self.segment_count = segment_count;
+        self
+    }
+
+    /// Get file ID codewords
+    pub fn file_id(&self) -> &[u16] {
+        &self.file_id
+    }
+
+    /// Get file name
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Get timestamp
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// Is the segment count written in the control blocks?
+    pub fn segment_count(&self) -> bool {
+        self.segment_count
+    }
+
+    /// Get the control block codewords of a segment
+    fn control_block(&self, index: usize, count: usize) -> Vec<u16> {
+        let mut codewords = vec![MACRO_MARKER];
+        codewords.extend(numeric_compaction(format!("{index:05}").as_bytes()));
+        codewords.extend(&self.file_id);
+
+        if let Some(file_name) = &self.file_name {
+            codewords.extend([OPTIONAL_FIELD, 0]);
+            // Characters were checked by `with_file_name`
+            codewords.extend(text_compaction(file_name.as_bytes()).unwrap_or_default());
+        }
+        if self.segment_count {
+            codewords.extend([OPTIONAL_FIELD, 1]);
+            codewords.extend(numeric_compaction(count.to_string().as_bytes()));
+        }
+        if let Some(timestamp) = self.timestamp {
+            codewords.extend([OPTIONAL_FIELD, 2]);
+            codewords.extend(numeric_compaction(timestamp.to_string().as_bytes()));
+        }
+
+        if index + 1 == count {
+            codewords.push(LAST_SEGMENT);
+        }
+
+        codewords
+    }
+}
+
+/// Macro PDF417 sequence
+///
+/// Data is split into segments fitting the rows and columns of the PDF417 option, the data of each segment
+/// being counted with byte compaction (the largest encoding) and the control block.
+///
+/// `GS ( k` stores the data of the symbol and the printer encodes it: the control block cannot be added to
+/// symbols printed by the printer, so [`Printer::macro_pdf417`](crate::printer::Printer::macro_pdf417) prints
+/// the segments as images (`graphics` feature).
+///
+/// ```
+/// use escpos::utils::*;
+///
+/// let option = Pdf417Option::new(5, 10, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1).unwrap();
+/// let macro_option = MacroPdf417Option::new("017053").unwrap().with_file_name("invoice").unwrap();
+/// let code = MacroPdf417::new(&"A".repeat(100), option, macro_option).unwrap();
+/// assert_eq!(code.segment_count(), 3);
+/// assert_eq!(code.segment(2).unwrap(), "A".repeat(32).as_bytes());
+/// ```
+#[derive(Debug)]
+pub struct MacroPdf417 {
+    pub data: Vec<u8>,
+    pub option: Pdf417Option,
+    pub macro_option: MacroPdf417Option,
+    segments: Vec<Range<usize>>,
+}
+
+impl MacroPdf417 {
+    /// Create a new `MacroPdf417`
+    pub fn new(data: &str, option: Pdf417Option, macro_option: MacroPdf417Option) -> Result<Self> {
+        Self::from_bytes(data.as_bytes(), option, macro_option)
+    }
+
+    /// Create a new `MacroPdf417` from bytes
+    pub fn from_bytes(data: &[u8], option: Pdf417Option, macro_option: MacroPdf417Option) -> Result<Self> {
+        if data.is_empty() {
+            return Err(PrinterError::Input("Macro PDF417 data is empty".to_string()));
+        }
+
+        // The segment count is part of the control blocks: split again until it is stable
+        let mut count = 1;
+        let segments = loop {
+            let segments = Self::split(data, &option, &macro_option, count)?;
+            if segments.len() == count {
+                break segments;
+            }
+            count = segments.len();
+        };
+
+        Ok(Self {
+            data: data.to_vec(),
+            option,
+            macro_option,
+            segments,
+        })
+    }
+
+    /// Split data into segments for a segment count
+    fn split(
+        data: &[u8],
+        option: &Pdf417Option,
+        macro_option: &MacroPdf417Option,
+        count: usize,
+    ) -> Result<Vec<Range<usize>>> {
+        // Data codewords without the length descriptor and the ECI codewords
+        let eci_codewords = option
+            .eci()
+            .map(|eci| pdf417_eci_codewords(eci).len())
+            .unwrap_or_default();
+        let max_codewords = option.max_data_codewords().saturating_sub(1 + eci_codewords);
+        let mut segments = Vec::new();
+        let mut start = 0;
+
+        while start < data.len() {
+            let index = segments.len();
+            if index >= MAX_SEGMENTS {
+                return Err(PrinterError::Input(format!(
+                    "Macro PDF417 data too long: more than {MAX_SEGMENTS} segments"
+                )));
+            }
+
+            // The last segment has a terminator, count it for each segment
+            let control_len = macro_option.control_block(index, count.max(index + 1)).len() + 1;
+            let len = (1..=data.len() - start)
+                .take_while(|len| byte_compaction_codewords(*len) + control_len <= max_codewords)
+                .last()
+                .ok_or_else(|| {
+                    PrinterError::Input(format!(
+                        "Macro PDF417 control block ({control_len} codewords) does not fit in {max_codewords} codewords"
+                    ))
+                })?;
+
+            segments.push(start..start + len);
+            start += len;
+        }
+
+        Ok(segments)
+    }
+
+    /// Get the number of segments
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Get the data of a segment
+    pub fn segment(&self, index: usize) -> Option<&[u8]> {
+        self.segments.get(index).map(|range| &self.data[range.clone()])
+    }
+
+    /// Get the data of every segment
+    pub fn segments(&self) -> Vec<&[u8]> {
+        self.segments.iter().map(|range| &self.data[range.clone()]).collect()
+    }
+
+    /// Get the control block codewords of a segment
+    pub fn control_block(&self, index: usize) -> Option<Vec<u16>> {
+        (index < self.segments.len()).then(|| self.macro_option.control_block(index, self.segments.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_macro_pdf417_option() {
+        assert_eq!(MacroPdf417Option::new("017053").unwrap().file_id(), &[17, 53]);
+        assert!(MacroPdf417Option::new("").is_err());
+        assert!(MacroPdf417Option::new("1234").is_err());
+        assert!(MacroPdf417Option::new("900").is_err());
+        assert!(MacroPdf417Option::new("017053").unwrap().with_file_name("é").is_err());
+    }
+
+    #[test]
+    fn test_macro_pdf417_control_block() {
+        let option = MacroPdf417Option::new("017053").unwrap().with_timestamp(4);
+        assert_eq!(
+            option.control_block(0, 2),
+            vec![928, 111, 100, 17, 53, 923, 1, 12, 923, 2, 14]
+        );
+        assert_eq!(
+            option.with_segment_count(false).control_block(1, 2),
+            vec![928, 111, 101, 17, 53, 923, 2, 14, 922]
+        );
+    }
+
+    #[test]
+    fn test_macro_pdf417_segments() {
+        let option = Pdf417Option::new(5, 10, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1).unwrap();
+        let macro_option = MacroPdf417Option::new("017053").unwrap();
+        let data = (0..=255).collect::<Vec<u8>>();
+        let code = MacroPdf417::from_bytes(&data, option.clone(), macro_option.clone()).unwrap();
+
+        // 50 codewords - 4 error correction - 1 length descriptor
+        let max_codewords = option.max_data_codewords() - 1;
+        assert_eq!(max_codewords, 45);
+        for index in 0..code.segment_count() {
+            let segment = code.segment(index).unwrap();
+            let control_block = code.control_block(index).unwrap();
+            assert!(byte_compaction_codewords(segment.len()) + control_block.len() <= max_codewords);
+        }
+        assert_eq!(code.segments().concat(), data);
+        assert_eq!(code.control_block(code.segment_count() - 1).unwrap().last(), Some(&922));
+        assert!(code.control_block(code.segment_count()).is_none());
+
+        // The control block does not fit
+        let option = Pdf417Option::new(1, 3, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level0).unwrap();
+        assert!(MacroPdf417::new("ABC", option, macro_option.clone()).is_err());
+        assert!(MacroPdf417::new("", Pdf417Option::default(), macro_option).is_err());
+    }
+}
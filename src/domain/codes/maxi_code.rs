@@ -1,7 +1,30 @@
 //! MaxiCode
 
+use crate::errors::{PrinterError, Result};
 use std::fmt;
 
+/// Structured carrier message header (ANSI MH10.8.3 format 01, version 96)
+const CARRIER_MESSAGE_HEADER: &str = "[)>\x1E01\x1D96";
+
+/// Structured carrier message trailer (RS and EOT)
+const CARRIER_MESSAGE_TRAILER: &str = "\x1E\x04";
+
+/// Maximum number of symbol characters of the secondary message in modes 2 and 3
+const SECONDARY_MESSAGE_MAX_LEN: usize = 84;
+
+/// Number of symbol characters of a text, characters out of the code set A (lower case letters, `[`, `>`, `EOT`...)
+/// needing a shift
+fn symbol_characters(text: &str) -> usize {
+    text.chars()
+        .map(
+            |c| match c.is_ascii_uppercase() || c.is_ascii_digit() || " \r\x1C\x1D\x1E\"#$%&'()*+,-./:".contains(c) {
+                true => 1,
+                false => 2,
+            },
+        )
+        .sum()
+}
+
 /// PDF417 correction level
 #[derive(Debug, Default, Clone, Copy)]
 pub enum MaxiCodeMode {
@@ -37,18 +60,314 @@ impl From<MaxiCodeMode> for u8 {
     }
 }
 
+/// MaxiCode
+///
+/// MaxiCode is only printed with the printer commands (`GS ( k`), which do not encode ECI designators:
+/// the data is written in the character set of the printer.
 #[derive(Debug)]
 pub struct MaxiCode {
-    pub data: String,
+    pub data: Vec<u8>,
     pub mode: MaxiCodeMode,
 }
 
 impl MaxiCode {
     /// Create a new `MaxiCode`
     pub fn new(data: &str, mode: MaxiCodeMode) -> Self {
+        Self::from_bytes(data.as_bytes(), mode)
+    }
+
+    /// Create a new `MaxiCode` from bytes
+    pub fn from_bytes(data: &[u8], mode: MaxiCodeMode) -> Self {
         Self {
-            data: data.to_string(),
+            data: data.to_vec(),
             mode,
         }
     }
 }
+
+/// MaxiCode structured carrier message (modes 2 and 3)
+///
+/// The primary message holds the postal code, the country code (ISO 3166 numeric) and the class of service.
+/// Mode 2 is selected for numeric postal codes (up to 9 digits), mode 3 for alphanumeric postal codes
+/// (up to 6 upper case letters, digits or spaces).
+///
+/// The secondary message follows the UPS format: tracking number, carrier (SCAC), shipper number, pickup day
+/// of year, shipment ID, package number, weight, address validation and ship to address.
+///
+/// ```
+/// use escpos::utils::*;
+///
+/// let message = MaxiCodeCarrierMessage::new("152382802", 840, 1)
+///     .with_tracking_number("1Z00004951")
+///     .with_shipper_number("06X610")
+///     .with_pickup_day(159)
+///     .unwrap()
+///     .with_package(1, 1)
+///     .unwrap()
+///     .with_weight(10)
+///     .with_ship_to("", "PITTSBURGH", "PA");
+/// let code = message.to_maxi_code().unwrap();
+/// assert!(matches!(code.mode, MaxiCodeMode::Mode2));
+/// assert_eq!(
+///     code.data,
+///     b"[)>\x1E01\x1D96152382802\x1D840\x1D001\x1D1Z00004951\x1DUPSN\x1D06X610\x1D159\x1D\x1D1/1\x1D10\x1D\x1D\x1DPITTSBURGH\x1DPA\x1E\x04"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct MaxiCodeCarrierMessage {
+    postal_code: String,
+    country_code: u16,
+    service_class: u16,
+    tracking_number: String,
+    carrier: String,
+    shipper_number: String,
+    pickup_day: Option<u16>,
+    shipment_id: String,
+    package: Option<(u16, u16)>,
+    weight: Option<u32>,
+    address_validation: Option<bool>,
+    ship_to: (String, String, String),
+}
+
+impl MaxiCodeCarrierMessage {
+    /// Create a new `MaxiCodeCarrierMessage` (the carrier is `UPSN`)
+    pub fn new(postal_code: &str, country_code: u16, service_class: u16) -> Self {
+        Self {
+            postal_code: postal_code.to_string(),
+            country_code,
+            service_class,
+            tracking_number: String::new(),
+            carrier: "UPSN".to_string(),
+            shipper_number: String::new(),
+            pickup_day: None,
+            shipment_id: String::new(),
+            package: None,
+            weight: None,
+            address_validation: None,
+            ship_to: Default::default(),
+        }
+    }
+
+    /// Set the tracking number
+    pub fn with_tracking_number(mut self, tracking_number: &str) -> Self {
+        self.tracking_number = tracking_number.to_string();
+        self
+    }
+
+    /// Set the carrier (Standard Carrier Alpha Code)
+    pub fn with_carrier(mut self, carrier: &str) -> Self {
+        self.carrier = carrier.to_string();
+        self
+    }
+
+    /// Set the shipper number
+    pub fn with_shipper_number(mut self, shipper_number: &str) -> Self {
+        self.shipper_number = shipper_number.to_string();
+        self
+    }
+
+    /// Set the pickup day of year (1 - 366)
+    pub fn with_pickup_day(mut self, day: u16) -> Result<Self> {
+        if !(1..=366).contains(&day) {
+            return Err(PrinterError::Input(format!(
+                "invalid MaxiCode pickup day of year: {day}"
+            )));
+        }
+        self.pickup_day = Some(day);
+        Ok(self)
+    }
+
+    /// Set the shipment ID
+    pub fn with_shipment_id(mut self, shipment_id: &str) -> Self {
+        self.shipment_id = shipment_id.to_string();
+        self
+    }
+
+    /// Set the package number and the total number of packages
+    pub fn with_package(mut self, number: u16, total: u16) -> Result<Self> {
+        if number == 0 || number > total {
+            return Err(PrinterError::Input(format!(
+                "invalid MaxiCode package: {number}/{total}"
+            )));
+        }
+        self.package = Some((number, total));
+        Ok(self)
+    }
+
+    /// Set the weight (in pounds)
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Set the address validation
+    pub fn with_address_validation(mut self, address_validation: bool) -> Self {
+        self.address_validation = Some(address_validation);
+        self
+    }
+
+    /// Set the ship to address (street, city and state)
+    pub fn with_ship_to(mut self, street: &str, city: &str, state: &str) -> Self {
+        self.ship_to = (street.to_string(), city.to_string(), state.to_string());
+        self
+    }
+
+    /// Get the mode selected from the postal code
+    pub fn mode(&self) -> Result<MaxiCodeMode> {
+        let postal_code = self.postal_code.as_str();
+        let len = postal_code.len();
+
+        if (1..=9).contains(&len) && postal_code.chars().all(|c| c.is_ascii_digit()) {
+            Ok(MaxiCodeMode::Mode2)
+        } else if (1..=6).contains(&len)
+            && postal_code
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == ' ')
+        {
+            Ok(MaxiCodeMode::Mode3)
+        } else {
+            Err(PrinterError::Input(format!(
+                "invalid MaxiCode postal code (9 digits or 6 alphanumeric characters): {postal_code}"
+            )))
+        }
+    }
+
+    /// Get the secondary message (fields separated by `GS`)
+    ///
+    /// Its length is checked with the header and the trailer, encoded in the secondary message of the symbol
+    /// (the postal code, the country code and the class of service being encoded in the primary message).
+    fn secondary_message(&self) -> Result<String> {
+        let to_string = |value: Option<String>| value.unwrap_or_default();
+        let fields = [
+            self.tracking_number.clone(),
+            self.carrier.clone(),
+            self.shipper_number.clone(),
+            to_string(self.pickup_day.map(|day| format!("{day:03}"))),
+            self.shipment_id.clone(),
+            to_string(self.package.map(|(number, total)| format!("{number}/{total}"))),
+            to_string(self.weight.map(|weight| weight.to_string())),
+            to_string(
+                self.address_validation
+                    .map(|valid| if valid { "Y" } else { "N" }.to_string()),
+            ),
+            self.ship_to.0.clone(),
+            self.ship_to.1.clone(),
+            self.ship_to.2.clone(),
+        ];
+
+        if let Some(field) = fields
+            .iter()
+            .find(|field| !field.chars().all(|c| c.is_ascii() && !c.is_ascii_control()))
+        {
+            return Err(PrinterError::Input(format!(
+                "invalid MaxiCode secondary message field: {field:?}"
+            )));
+        }
+
+        let message = fields.join("\x1D");
+        let len = symbol_characters(CARRIER_MESSAGE_HEADER)
+            + symbol_characters(&message)
+            + symbol_characters(CARRIER_MESSAGE_TRAILER);
+        if len > SECONDARY_MESSAGE_MAX_LEN {
+            return Err(PrinterError::Input(format!(
+                "MaxiCode secondary message too long: {len} characters (max {SECONDARY_MESSAGE_MAX_LEN})"
+            )));
+        }
+
+        Ok(message)
+    }
+
+    /// Check the fields and get the `MaxiCode` (mode 2 or 3)
+    pub fn to_maxi_code(&self) -> Result<MaxiCode> {
+        let mode = self.mode()?;
+        if self.country_code > 999 {
+            return Err(PrinterError::Input(format!(
+                "invalid MaxiCode country code: {}",
+                self.country_code
+            )));
+        }
+        if self.service_class > 999 {
+            return Err(PrinterError::Input(format!(
+                "invalid MaxiCode class of service: {}",
+                self.service_class
+            )));
+        }
+
+        let data = format!(
+            "{CARRIER_MESSAGE_HEADER}{}\x1D{:03}\x1D{:03}\x1D{}{CARRIER_MESSAGE_TRAILER}",
+            self.postal_code,
+            self.country_code,
+            self.service_class,
+            self.secondary_message()?
+        );
+
+        Ok(MaxiCode::new(&data, mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maxi_code_carrier_message_mode() {
+        let mode = |postal_code: &str| MaxiCodeCarrierMessage::new(postal_code, 250, 1).mode().ok();
+
+        assert!(matches!(mode("15238"), Some(MaxiCodeMode::Mode2)));
+        assert!(matches!(mode("152382802"), Some(MaxiCodeMode::Mode2)));
+        assert!(matches!(mode("B1050"), Some(MaxiCodeMode::Mode3)));
+        assert!(matches!(mode("SW1A 1"), Some(MaxiCodeMode::Mode3)));
+        assert!(mode("1523828021").is_none());
+        assert!(mode("SW1A 1AA").is_none());
+        assert!(mode("b1050").is_none());
+        assert!(mode("").is_none());
+    }
+
+    #[test]
+    fn test_symbol_characters() {
+        assert_eq!(symbol_characters(CARRIER_MESSAGE_HEADER), 11);
+        assert_eq!(symbol_characters(CARRIER_MESSAGE_TRAILER), 3);
+        assert_eq!(symbol_characters("1Z 00/A"), 7);
+        assert_eq!(symbol_characters("ab"), 4);
+    }
+
+    #[test]
+    fn test_maxi_code_carrier_message() {
+        let code = MaxiCodeCarrierMessage::new("B1050", 56, 12)
+            .with_address_validation(true)
+            .to_maxi_code()
+            .unwrap();
+        assert!(matches!(code.mode, MaxiCodeMode::Mode3));
+        assert_eq!(
+            code.data,
+            b"[)>\x1E01\x1D96B1050\x1D056\x1D012\x1D\x1DUPSN\x1D\x1D\x1D\x1D\x1D\x1DY\x1D\x1D\x1D\x1E\x04"
+        );
+
+        assert!(MaxiCodeCarrierMessage::new("B1050", 1000, 1).to_maxi_code().is_err());
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1000).to_maxi_code().is_err());
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1)
+            .with_ship_to(&"A".repeat(80), "", "")
+            .to_maxi_code()
+            .is_err());
+        // Header (11 symbol characters), fields (14 + 56) and trailer (3)
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1)
+            .with_ship_to(&"A".repeat(56), "", "")
+            .to_maxi_code()
+            .is_ok());
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1)
+            .with_ship_to(&"A".repeat(57), "", "")
+            .to_maxi_code()
+            .is_err());
+        // Lower case letters need a shift
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1)
+            .with_ship_to(&"a".repeat(40), "", "")
+            .to_maxi_code()
+            .is_err());
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1)
+            .with_tracking_number("1Z\x1D")
+            .to_maxi_code()
+            .is_err());
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1).with_pickup_day(0).is_err());
+        assert!(MaxiCodeCarrierMessage::new("B1050", 56, 1).with_package(2, 1).is_err());
+    }
+}
@@ -1,29 +1,69 @@
 //! Barcodes and 2D codes
 mod aztec;
+mod aztec_symbol;
 mod barcodes;
+mod code128;
+mod composite;
+mod composite_symbol;
 mod data_matrix;
+mod data_matrix_symbol;
+mod gs1;
+mod gs1_databar;
 mod gs1_databar_2d;
+mod macro_pdf417;
 mod maxi_code;
 mod pdf417;
+mod pdf417_symbol;
 mod qrcode;
+mod reed_solomon;
 
 #[cfg(feature = "barcodes")]
 pub use barcodes::*;
 
+#[cfg(feature = "barcodes")]
+pub(crate) use code128::*;
+
+#[cfg(feature = "barcodes")]
+pub use gs1_databar::*;
+
+#[cfg(feature = "barcodes")]
+pub(crate) use gs1::*;
+
+#[cfg(any(feature = "barcodes", feature = "codes_2d"))]
+pub use gs1::Gs1ElementString;
+
 #[cfg(feature = "codes_2d")]
 pub use aztec::*;
 
+#[cfg(all(feature = "graphics", feature = "codes_2d"))]
+pub(crate) use aztec_symbol::*;
+
+#[cfg(feature = "codes_2d")]
+pub use composite::*;
+
+#[cfg(all(feature = "graphics", feature = "barcodes", feature = "codes_2d"))]
+pub(crate) use composite_symbol::*;
+
 #[cfg(feature = "codes_2d")]
 pub use data_matrix::*;
 
+#[cfg(all(feature = "graphics", feature = "codes_2d"))]
+pub(crate) use data_matrix_symbol::*;
+
 #[cfg(feature = "codes_2d")]
 pub use gs1_databar_2d::*;
 
+#[cfg(feature = "codes_2d")]
+pub use macro_pdf417::*;
+
 #[cfg(feature = "codes_2d")]
 pub use maxi_code::*;
 
 #[cfg(feature = "codes_2d")]
 pub use pdf417::*;
 
+#[cfg(all(feature = "graphics", feature = "codes_2d"))]
+pub(crate) use pdf417_symbol::*;
+
 #[cfg(feature = "codes_2d")]
 pub use qrcode::*;
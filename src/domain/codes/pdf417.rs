@@ -1,6 +1,9 @@
 //! PDF417
 
-use crate::errors::{PrinterError, Result};
+use crate::{
+    domain::common::check_eci_designator,
+    errors::{PrinterError, Result},
+};
 use std::fmt;
 
 /// PDF417 correction level
@@ -88,9 +91,149 @@ impl From<Pdf417Type> for u8 {
     }
 }
 
+/// Maximum number of codewords in a PDF417 symbol
+pub(crate) const PDF417_MAX_CODEWORDS: usize = 928;
+
+/// Maximum ECI designator encoded in a PDF417 symbol
+const PDF417_MAX_ECI: u32 = 811_799;
+
+impl Pdf417CorrectionLevel {
+    /// Get the number of error correction codewords for a number of data codewords
+    ///
+    /// With a ratio, the level is the smallest one giving at least `data_codewords * ratio / 10` codewords.
+    pub(crate) fn ec_codewords(&self, data_codewords: usize) -> usize {
+        let level = match *self {
+            Pdf417CorrectionLevel::Level0 => 0,
+            Pdf417CorrectionLevel::Level1 => 1,
+            Pdf417CorrectionLevel::Level2 => 2,
+            Pdf417CorrectionLevel::Level3 => 3,
+            Pdf417CorrectionLevel::Level4 => 4,
+            Pdf417CorrectionLevel::Level5 => 5,
+            Pdf417CorrectionLevel::Level6 => 6,
+            Pdf417CorrectionLevel::Level7 => 7,
+            Pdf417CorrectionLevel::Level8 => 8,
+            Pdf417CorrectionLevel::Ratio(ratio) => {
+                let needed = (data_codewords * usize::from(ratio)).div_ceil(10);
+                (0..8).find(|level| 2 << level >= needed).unwrap_or(8)
+            }
+        };
+        2 << level
+    }
+}
+
+/// Text compaction sub-modes characters (Alpha, Lower, Mixed, Punctuation)
+///
+/// Values 26 to 29 of the Alpha, Lower and Mixed sub-modes are the space and sub-mode switches.
+const TEXT_ALPHA: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const TEXT_LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const TEXT_MIXED: &str = "0123456789&\r\t,:#-.$/+%*=^";
+const TEXT_PUNCT: &str = ";<>@[\\]_`~!\r\t,:\n-.$/\"|*()?{}'";
+
+/// Encode digits with numeric compaction (groups of 44 digits, prefixed by 1, in base 900)
+pub(crate) fn numeric_compaction(digits: &[u8]) -> Vec<u16> {
+    let mut codewords = Vec::new();
+
+    for group in digits.chunks(44) {
+        // Decimal digits, most significant first
+        let mut number: Vec<u32> = std::iter::once(1)
+            .chain(group.iter().map(|d| u32::from(d - b'0')))
+            .collect();
+        let mut group_codewords = Vec::new();
+
+        while number.iter().any(|d| *d != 0) {
+            let mut remainder = 0;
+            for digit in number.iter_mut() {
+                let value = remainder * 10 + *digit;
+                *digit = value / 900;
+                remainder = value % 900;
+            }
+            group_codewords.push(remainder as u16);
+        }
+
+        codewords.extend(group_codewords.into_iter().rev());
+    }
+
+    codewords
+}
+
+/// Encode text with text compaction (starting in Alpha sub-mode, pairs of values in base 30)
+pub(crate) fn text_compaction(text: &[u8]) -> Result<Vec<u16>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum SubMode {
+        Alpha,
+        Lower,
+        Mixed,
+    }
+
+    let mut values = Vec::new();
+    let mut mode = SubMode::Alpha;
+
+    for c in text.iter().map(|c| char::from(*c)) {
+        let position = |chars: &str| chars.chars().position(|x| x == c).map(|i| i as u16);
+        let target = match c {
+            ' ' => None,
+            _ if position(TEXT_ALPHA).is_some() => Some(SubMode::Alpha),
+            _ if position(TEXT_LOWER).is_some() => Some(SubMode::Lower),
+            _ if position(TEXT_MIXED).is_some() => Some(SubMode::Mixed),
+            _ => match position(TEXT_PUNCT) {
+                Some(value) => {
+                    // Punctuation shift
+                    values.extend([29, value]);
+                    continue;
+                }
+                None => {
+                    return Err(PrinterError::Input(format!(
+                        "invalid PDF417 text compaction character: {c:?}"
+                    )))
+                }
+            },
+        };
+
+        let Some(target) = target else {
+            values.push(26);
+            continue;
+        };
+        match (mode, target) {
+            (from, to) if from == to => (),
+            (SubMode::Lower, SubMode::Alpha) => values.extend([28, 28]),
+            (_, SubMode::Lower) => values.push(27),
+            _ => values.push(28),
+        }
+        mode = target;
+
+        let value = match mode {
+            SubMode::Alpha => position(TEXT_ALPHA),
+            SubMode::Lower => position(TEXT_LOWER),
+            SubMode::Mixed => position(TEXT_MIXED),
+        };
+        values.extend(value);
+    }
+
+    // Padding with a punctuation shift
+    if values.len() % 2 == 1 {
+        values.push(29);
+    }
+
+    Ok(values.chunks(2).map(|pair| pair[0] * 30 + pair[1]).collect())
+}
+
+/// Get the ECI codewords of a designator (at most 811799)
+pub(crate) fn pdf417_eci_codewords(eci: u32) -> Vec<u16> {
+    match eci {
+        0..=899 => vec![927, eci as u16],
+        900..=810_899 => vec![926, (eci / 900 - 1) as u16, (eci % 900) as u16],
+        _ => vec![925, (eci - 810_900) as u16],
+    }
+}
+
+/// Number of codewords of bytes encoded with byte compaction (latch included)
+pub(crate) fn byte_compaction_codewords(len: usize) -> usize {
+    1 + len / 6 * 5 + len % 6
+}
+
 /// PDF417 option
 // TODO: Make all Option type (barcode, qrcode, GS1, etc.) pub(crate) instead of pub?
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Pdf417Option {
     columns: u8,    // Default: 0
     rows: u8,       // Default: 0
@@ -98,6 +241,7 @@ pub struct Pdf417Option {
     row_height: u8, // Default: ?
     code_type: Pdf417Type,
     correction_level: Pdf417CorrectionLevel,
+    eci: Option<u32>,
 }
 
 impl Pdf417Option {
@@ -129,9 +273,25 @@ impl Pdf417Option {
             row_height,
             code_type,
             correction_level,
+            eci: None,
         })
     }
 
+    /// Declare the character set of the data with an ECI designator (0-811799)
+    ///
+    /// ECI designators are only encoded in PDF417 printed as images (software 2D codes, `graphics` feature):
+    /// the printer commands (`GS ( k`) do not support them, printing the code without software 2D codes fails.
+    pub fn with_eci(mut self, eci: u32) -> Result<Self> {
+        check_eci_designator(eci)?;
+        if eci > PDF417_MAX_ECI {
+            return Err(PrinterError::Input(format!(
+                "invalid PDF417 ECI designator (0-{PDF417_MAX_ECI}): {eci}"
+            )));
+        }
+        self.eci = Some(eci);
+        Ok(self)
+    }
+
     /// Get number of columns
     pub fn columns(&self) -> u8 {
         self.columns
@@ -161,20 +321,49 @@ impl Pdf417Option {
     pub fn correction_level(&self) -> Pdf417CorrectionLevel {
         self.correction_level
     }
+
+    /// Get ECI designator
+    pub fn eci(&self) -> Option<u32> {
+        self.eci
+    }
+
+    /// Get the maximum number of data codewords (length descriptor included) of a symbol
+    ///
+    /// When the number of columns or rows is 0 (automatic), the largest symbol (at most 30 columns, 90 rows and 928
+    /// codewords) is used.
+    pub(crate) fn max_data_codewords(&self) -> usize {
+        let (columns, rows) = (usize::from(self.columns), usize::from(self.rows));
+        let total = match (columns, rows) {
+            (0, 0) => PDF417_MAX_CODEWORDS,
+            (0, rows) => rows * (PDF417_MAX_CODEWORDS / rows).min(30),
+            (columns, 0) => columns * (PDF417_MAX_CODEWORDS / columns).min(90),
+            (columns, rows) => (columns * rows).min(PDF417_MAX_CODEWORDS),
+        };
+
+        (0..=total)
+            .rev()
+            .find(|data| data + self.correction_level.ec_codewords(*data) <= total)
+            .unwrap_or_default()
+    }
 }
 
 /// PDF417
 #[derive(Debug)]
 pub struct Pdf417 {
-    pub data: String,
+    pub data: Vec<u8>,
     pub option: Pdf417Option,
 }
 
 impl Pdf417 {
     /// Create a new `Pdf417`
     pub fn new(data: &str, option: Pdf417Option) -> Self {
+        Self::from_bytes(data.as_bytes(), option)
+    }
+
+    /// Create a new `Pdf417` from bytes
+    pub fn from_bytes(data: &[u8], option: Pdf417Option) -> Self {
         Self {
-            data: data.to_string(),
+            data: data.to_vec(),
             option,
         }
     }
@@ -191,4 +380,57 @@ mod tests {
         assert!(Pdf417Option::new(0, 100, 8, 8, Pdf417Type::Standard, Pdf417CorrectionLevel::Level0).is_err());
         assert!(Pdf417Option::new(0, 0, 8, 8, Pdf417Type::Standard, Pdf417CorrectionLevel::Level0).is_ok());
     }
+
+    #[test]
+    fn test_pdf417_option_eci() {
+        let option = Pdf417Option::default().with_eci(26).unwrap();
+        assert_eq!(option.eci(), Some(26));
+        assert!(Pdf417Option::default().with_eci(811_799).is_ok());
+        assert!(Pdf417Option::default().with_eci(811_800).is_err());
+    }
+
+    #[test]
+    fn test_pdf417_max_data_codewords() {
+        let option = Pdf417Option::new(3, 10, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level2).unwrap();
+        assert_eq!(option.max_data_codewords(), 30 - 8);
+
+        let option = Pdf417Option::new(0, 0, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level5).unwrap();
+        assert_eq!(option.max_data_codewords(), 928 - 64);
+
+        // 10 % of 20 data codewords: level 0 (2 codewords)
+        assert_eq!(Pdf417CorrectionLevel::Ratio(1).ec_codewords(20), 2);
+        // 50 % of 100 data codewords: level 5 (64 codewords)
+        assert_eq!(Pdf417CorrectionLevel::Ratio(5).ec_codewords(100), 64);
+        assert_eq!(Pdf417CorrectionLevel::Ratio(40).ec_codewords(900), 512);
+    }
+
+    #[test]
+    fn test_pdf417_eci_codewords() {
+        assert_eq!(pdf417_eci_codewords(26), vec![927, 26]);
+        assert_eq!(pdf417_eci_codewords(900), vec![926, 0, 0]);
+        assert_eq!(pdf417_eci_codewords(810_899), vec![926, 899, 899]);
+        assert_eq!(pdf417_eci_codewords(811_799), vec![925, 899]);
+    }
+
+    #[test]
+    fn test_numeric_compaction() {
+        assert_eq!(numeric_compaction(b"00000"), vec![111, 100]);
+        assert_eq!(numeric_compaction(b"00001"), vec![111, 101]);
+        assert_eq!(numeric_compaction(b"4"), vec![14]);
+        // 44 digits groups
+        assert_eq!(numeric_compaction(&[b'1'; 45]).len(), 15 + 1);
+    }
+
+    #[test]
+    fn test_text_compaction() {
+        // A, B
+        assert_eq!(text_compaction(b"AB").unwrap(), vec![1]);
+        // A, space, latch lower, a
+        assert_eq!(text_compaction(b"A a").unwrap(), vec![26, 27 * 30]);
+        // latch mixed, 1, punctuation shift, @
+        assert_eq!(text_compaction(b"1@").unwrap(), vec![28 * 30 + 1, 29 * 30 + 3]);
+        // latch lower, a, latch mixed, latch alpha, B, padding
+        assert_eq!(text_compaction(b"aB").unwrap(), vec![27 * 30, 28 * 30 + 28, 30 + 29]);
+        assert!(text_compaction("é".as_bytes()).is_err());
+    }
 }
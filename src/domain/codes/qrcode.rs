@@ -2,10 +2,79 @@
 
 #![cfg(feature = "codes_2d")]
 
-use crate::errors::Result;
+use crate::{
+    domain::common::check_eci_designator,
+    errors::{PrinterError, Result},
+};
 use std::fmt;
 
-const QRCODE_MAX_DATA_SIZE: usize = 7089;
+/// Data codewords of Model 1 QR codes by version and error correction level (L, M, Q, H)
+const MODEL1_DATA_CODEWORDS: [[u16; 4]; 14] = [
+    [19, 16, 13, 9],
+    [36, 30, 22, 16],
+    [57, 44, 36, 24],
+    [80, 60, 50, 34],
+    [108, 82, 68, 46],
+    [136, 106, 86, 58],
+    [170, 132, 108, 72],
+    [208, 160, 128, 88],
+    [244, 186, 150, 104],
+    [287, 216, 180, 122],
+    [333, 266, 203, 145],
+    [383, 306, 234, 166],
+    [441, 354, 276, 194],
+    [495, 392, 302, 214],
+];
+
+/// Data codewords of Model 2 QR codes by version and error correction level (L, M, Q, H)
+const MODEL2_DATA_CODEWORDS: [[u16; 4]; 40] = [
+    [19, 16, 13, 9],
+    [34, 28, 22, 16],
+    [55, 44, 34, 26],
+    [80, 64, 48, 36],
+    [108, 86, 62, 46],
+    [136, 108, 76, 60],
+    [156, 124, 88, 66],
+    [194, 154, 110, 86],
+    [232, 182, 132, 100],
+    [274, 216, 154, 122],
+    [324, 254, 180, 140],
+    [370, 290, 206, 158],
+    [428, 334, 244, 180],
+    [461, 365, 261, 197],
+    [523, 415, 295, 223],
+    [589, 453, 325, 253],
+    [647, 507, 367, 283],
+    [721, 563, 397, 313],
+    [795, 627, 445, 341],
+    [861, 669, 485, 385],
+    [932, 714, 512, 406],
+    [1006, 782, 568, 442],
+    [1094, 860, 614, 464],
+    [1174, 914, 664, 514],
+    [1276, 1000, 718, 538],
+    [1370, 1062, 754, 596],
+    [1468, 1128, 808, 628],
+    [1531, 1193, 871, 661],
+    [1631, 1267, 911, 701],
+    [1735, 1373, 985, 745],
+    [1843, 1455, 1033, 793],
+    [1955, 1541, 1115, 845],
+    [2071, 1631, 1171, 901],
+    [2191, 1725, 1231, 961],
+    [2306, 1812, 1286, 986],
+    [2434, 1914, 1354, 1054],
+    [2566, 1992, 1426, 1096],
+    [2702, 2102, 1502, 1142],
+    [2812, 2216, 1582, 1222],
+    [2956, 2334, 1666, 1276],
+];
+
+/// Data bits of Micro QR codes by version and error correction level (L, M, Q), `0` if not available
+const MICRO_DATA_BITS: [[u16; 3]; 4] = [[20, 0, 0], [40, 32, 0], [84, 68, 0], [128, 112, 80]];
+
+/// Maximum module size
+const QRCODE_MAX_SIZE: u8 = 15;
 
 /// QR Code model
 #[derive(Debug, Clone, Copy)]
@@ -35,6 +104,178 @@ impl fmt::Display for QRCodeModel {
     }
 }
 
+impl QRCodeModel {
+    /// Get the maximum version (40 for Model 2, 14 for Model 1 and 4 for Micro QR code)
+    pub fn max_version(&self) -> u8 {
+        match self {
+            QRCodeModel::Model1 => 14,
+            QRCodeModel::Model2 => 40,
+            QRCodeModel::Micro => 4,
+        }
+    }
+
+    /// Get the number of modules per side of a version (without quiet zone)
+    pub fn modules(&self, version: u8) -> u32 {
+        match self {
+            QRCodeModel::Micro => 9 + 2 * u32::from(version),
+            _ => 17 + 4 * u32::from(version),
+        }
+    }
+
+    /// Get the quiet zone width (in modules)
+    pub fn quiet_zone(&self) -> u32 {
+        match self {
+            QRCodeModel::Micro => 2,
+            _ => 4,
+        }
+    }
+
+    /// Get the number of data bits of a version, `None` if the error correction level is not available
+    pub fn data_bits(&self, version: u8, correction_level: QRCodeCorrectionLevel) -> Option<usize> {
+        if !(1..=self.max_version()).contains(&version) {
+            return None;
+        }
+        let version = usize::from(version - 1);
+
+        match self {
+            QRCodeModel::Micro => match correction_level {
+                QRCodeCorrectionLevel::H => None,
+                level => Some(usize::from(MICRO_DATA_BITS[version][usize::from(u8::from(level) - 48)]))
+                    .filter(|bits| *bits > 0),
+            },
+            QRCodeModel::Model1 => {
+                Some(usize::from(MODEL1_DATA_CODEWORDS[version][usize::from(u8::from(correction_level) - 48)]) * 8)
+            }
+            QRCodeModel::Model2 => {
+                Some(usize::from(MODEL2_DATA_CODEWORDS[version][usize::from(u8::from(correction_level) - 48)]) * 8)
+            }
+        }
+    }
+}
+
+/// QR Code data mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QRCodeDataMode {
+    Numeric,
+    Alphanumeric,
+    Byte,
+}
+
+impl QRCodeDataMode {
+    /// Get the most compact mode encoding the whole data
+    pub fn from_data(data: &[u8]) -> Self {
+        if data.iter().all(u8::is_ascii_digit) {
+            Self::Numeric
+        } else if data
+            .iter()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || b" $%*+-./:".contains(c))
+        {
+            Self::Alphanumeric
+        } else {
+            Self::Byte
+        }
+    }
+
+    /// Number of bits of `len` characters (without mode indicator and character count)
+    fn bits(&self, len: usize) -> usize {
+        match self {
+            Self::Numeric => len / 3 * 10 + [0, 4, 7][len % 3],
+            Self::Alphanumeric => len / 2 * 11 + len % 2 * 6,
+            Self::Byte => len * 8,
+        }
+    }
+
+    /// Number of bits of the mode indicator and the character count, `None` if the mode is not available
+    fn header_bits(&self, model: QRCodeModel, version: u8) -> Option<usize> {
+        match model {
+            QRCodeModel::Micro => {
+                let count_bits = match self {
+                    Self::Numeric => 2 + version,
+                    Self::Alphanumeric if version >= 2 => 1 + version,
+                    Self::Byte if version >= 3 => 1 + version,
+                    _ => return None,
+                };
+                Some(usize::from(version - 1 + count_bits))
+            }
+            _ => {
+                let group = match version {
+                    1..=9 => 0,
+                    10..=26 => 1,
+                    _ => 2,
+                };
+                let count_bits = match self {
+                    Self::Numeric => [10, 12, 14][group],
+                    Self::Alphanumeric => [9, 11, 13][group],
+                    Self::Byte => [8, 16, 16][group],
+                };
+                Some(4 + count_bits)
+            }
+        }
+    }
+}
+
+impl fmt::Display for QRCodeDataMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QRCodeDataMode::Numeric => write!(f, "numeric"),
+            QRCodeDataMode::Alphanumeric => write!(f, "alphanumeric"),
+            QRCodeDataMode::Byte => write!(f, "byte"),
+        }
+    }
+}
+
+/// Get the smallest QR code version encoding the data (in a single mode)
+///
+/// ```
+/// use escpos::utils::*;
+///
+/// assert_eq!(qrcode_version(b"12345", QRCodeModel::Micro, QRCodeCorrectionLevel::L).unwrap(), 1);
+/// assert_eq!(qrcode_version(b"HELLO WORLD", QRCodeModel::Model2, QRCodeCorrectionLevel::Q).unwrap(), 1);
+/// assert_eq!(qrcode_version(&[b'a'; 2953], QRCodeModel::Model2, QRCodeCorrectionLevel::L).unwrap(), 40);
+/// assert!(qrcode_version(&[b'a'; 2954], QRCodeModel::Model2, QRCodeCorrectionLevel::L).is_err());
+/// assert!(qrcode_version(b"12345", QRCodeModel::Micro, QRCodeCorrectionLevel::H).is_err());
+/// ```
+pub fn qrcode_version(data: &[u8], model: QRCodeModel, correction_level: QRCodeCorrectionLevel) -> Result<u8> {
+    smallest_version(data, model, correction_level, None)
+}
+
+/// Number of bits of an ECI designator, with its mode indicator
+fn eci_bits(eci: u32) -> usize {
+    4 + match eci {
+        0..=127 => 8,
+        128..=16383 => 16,
+        _ => 24,
+    }
+}
+
+/// Get the smallest QR code version encoding the data, preceded by an ECI designator if any
+fn smallest_version(
+    data: &[u8],
+    model: QRCodeModel,
+    correction_level: QRCodeCorrectionLevel,
+    eci: Option<u32>,
+) -> Result<u8> {
+    let mode = QRCodeDataMode::from_data(data);
+    let eci_bits = eci.map(eci_bits).unwrap_or_default();
+
+    (1..=model.max_version())
+        .find(|version| {
+            match (
+                mode.header_bits(model, *version),
+                model.data_bits(*version, correction_level),
+            ) {
+                (Some(header), Some(data_bits)) => eci_bits + header + mode.bits(data.len()) <= data_bits,
+                _ => false,
+            }
+        })
+        .ok_or_else(|| {
+            PrinterError::Input(format!(
+                "QR code data is too long for {model} with {correction_level} ({} {mode} characters)",
+                data.len()
+            ))
+        })
+}
+
 /// QR Code error correction level
 #[derive(Debug, Clone, Copy)]
 pub enum QRCodeCorrectionLevel {
@@ -72,6 +313,8 @@ pub struct QRCodeOption {
     model: QRCodeModel,
     size: u8,
     correction_level: QRCodeCorrectionLevel,
+    auto_size: bool,
+    eci: Option<u32>,
 }
 
 impl Default for QRCodeOption {
@@ -80,6 +323,8 @@ impl Default for QRCodeOption {
             model: QRCodeModel::Model1,
             size: 4,
             correction_level: QRCodeCorrectionLevel::H,
+            auto_size: false,
+            eci: None,
         }
     }
 }
@@ -91,7 +336,30 @@ impl QRCodeOption {
             model,
             size,
             correction_level,
+            auto_size: false,
+            eci: None,
+        }
+    }
+
+    /// Declare the character set of the data with an ECI designator (0-999999, not available with Micro QR codes)
+    ///
+    /// ECI designators are only encoded in QR codes printed as images (software 2D codes, `graphics` feature):
+    /// the printer commands (`GS ( k`) do not support them, printing the code without software 2D codes fails.
+    pub fn with_eci(mut self, eci: u32) -> Result<Self> {
+        check_eci_designator(eci)?;
+        if matches!(self.model, QRCodeModel::Micro) {
+            return Err(PrinterError::Input(
+                "Micro QR codes do not support ECI designators".to_string(),
+            ));
         }
+        self.eci = Some(eci);
+        Ok(self)
+    }
+
+    /// Use the largest size fitting the printable width (`size` is ignored)
+    pub fn with_auto_size(mut self, auto_size: bool) -> Self {
+        self.auto_size = auto_size;
+        self
     }
 
     /// Get model
@@ -108,36 +376,66 @@ impl QRCodeOption {
     pub fn correction_level(&self) -> QRCodeCorrectionLevel {
         self.correction_level
     }
+
+    /// Get auto size
+    pub fn auto_size(&self) -> bool {
+        self.auto_size
+    }
+
+    /// Get ECI designator
+    pub fn eci(&self) -> Option<u32> {
+        self.eci
+    }
 }
 
 /// QR code
 #[derive(Debug)]
 pub struct QRCode {
-    pub data: String,
+    pub data: Vec<u8>,
     pub option: QRCodeOption,
 }
 
 impl QRCode {
     /// Create a new `QRCode`
     pub fn new(data: &str, option: Option<QRCodeOption>) -> Result<Self> {
-        Self::check_data(data)?;
+        Self::from_bytes(data.as_bytes(), option)
+    }
+
+    /// Create a new `QRCode` from bytes
+    pub fn from_bytes(data: &[u8], option: Option<QRCodeOption>) -> Result<Self> {
+        let option = option.unwrap_or_default();
+        Self::check_data(data, &option)?;
 
         Ok(Self {
-            data: data.to_string(),
-            option: option.unwrap_or_default(),
+            data: data.to_vec(),
+            option,
         })
     }
 
-    /// Check data
-    fn check_data(data: &str) -> Result<()> {
-        let bytes = data.as_bytes();
-        let data_len = bytes.len();
-        if data_len > QRCODE_MAX_DATA_SIZE {
-            return Err(crate::errors::PrinterError::Input(format!(
-                "QR code data is too long ({data_len}), its length should be smaller than 7090"
-            )));
-        }
-        Ok(())
+    /// Check data fits in the model with the error correction level
+    fn check_data(data: &[u8], option: &QRCodeOption) -> Result<()> {
+        smallest_version(data, option.model(), option.correction_level(), option.eci()).map(|_| ())
+    }
+
+    /// Get the smallest version encoding the data
+    pub fn version(&self) -> Result<u8> {
+        smallest_version(
+            &self.data,
+            self.option.model(),
+            self.option.correction_level(),
+            self.option.eci(),
+        )
+    }
+
+    /// Get the largest size (1 to 15 dots per module) of the symbol and its quiet zone fitting in `width` dots
+    pub fn fit_size(&self, width: u32) -> Result<u8> {
+        let model = self.option.model();
+        let modules = model.modules(self.version()?) + 2 * model.quiet_zone();
+
+        (1..=QRCODE_MAX_SIZE)
+            .rev()
+            .find(|size| modules * u32::from(*size) <= width)
+            .ok_or_else(|| PrinterError::Input(format!("QR code too wide: {modules} modules (max: {width} dots)")))
     }
 }
 
@@ -158,10 +456,127 @@ mod tests {
 
     #[test]
     fn test_qrcode_check_data() {
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::L);
         let data = "azerty123456789QTG,{";
-        assert!(QRCode::check_data(data).is_ok());
+        assert!(QRCode::check_data(data.as_bytes(), &option).is_ok());
 
         let data = "azerty123456789QTG,{".repeat(400);
-        assert!(QRCode::check_data(&data).is_err());
+        assert!(QRCode::check_data(data.as_bytes(), &option).is_err());
+
+        let option = QRCodeOption::new(QRCodeModel::Model1, 4, QRCodeCorrectionLevel::H);
+        assert!(QRCode::check_data(&[b'a'; 211], &option).is_ok());
+        assert!(QRCode::check_data(&[b'a'; 212], &option).is_err());
+    }
+
+    #[test]
+    fn test_qrcode_data_mode() {
+        assert_eq!(QRCodeDataMode::from_data(b"0123"), QRCodeDataMode::Numeric);
+        assert_eq!(
+            QRCodeDataMode::from_data(b"HTTPS://A.B/C"),
+            QRCodeDataMode::Alphanumeric
+        );
+        assert_eq!(QRCodeDataMode::from_data(b"https://a.b/c"), QRCodeDataMode::Byte);
+
+        assert_eq!(QRCodeDataMode::Numeric.bits(8), 27);
+        assert_eq!(QRCodeDataMode::Alphanumeric.bits(5), 28);
+        assert_eq!(QRCodeDataMode::Byte.bits(3), 24);
+    }
+
+    #[test]
+    fn test_qrcode_version() {
+        let version = |data: &[u8], model, level| qrcode_version(data, model, level).ok();
+
+        // Model 2 maximum capacities
+        let l = QRCodeCorrectionLevel::L;
+        assert_eq!(version(&[b'1'; 7089], QRCodeModel::Model2, l), Some(40));
+        assert_eq!(version(&[b'1'; 7090], QRCodeModel::Model2, l), None);
+        assert_eq!(version(&[b'A'; 4296], QRCodeModel::Model2, l), Some(40));
+        assert_eq!(version(&[b'A'; 4297], QRCodeModel::Model2, l), None);
+        assert_eq!(version(&[b'1'; 41], QRCodeModel::Model2, l), Some(1));
+        assert_eq!(version(&[b'1'; 42], QRCodeModel::Model2, l), Some(2));
+        let h = QRCodeCorrectionLevel::H;
+        assert_eq!(version(&[b'a'; 1273], QRCodeModel::Model2, h), Some(40));
+        assert_eq!(version(&[b'a'; 1274], QRCodeModel::Model2, h), None);
+
+        // Model 1
+        assert_eq!(version(&[b'1'; 41], QRCodeModel::Model1, l), Some(1));
+        assert_eq!(version(&[b'1'; 42], QRCodeModel::Model1, l), Some(2));
+        assert_eq!(version(&[b'a'; 492], QRCodeModel::Model1, l), Some(14));
+        assert_eq!(version(&[b'a'; 493], QRCodeModel::Model1, l), None);
+        assert_eq!(version(&[b'a'; 211], QRCodeModel::Model1, h), Some(14));
+        assert_eq!(version(&[b'a'; 212], QRCodeModel::Model1, h), None);
+
+        // Micro QR code
+        assert_eq!(version(&[b'1'; 5], QRCodeModel::Micro, l), Some(1));
+        assert_eq!(version(&[b'1'; 6], QRCodeModel::Micro, l), Some(2));
+        assert_eq!(version(b"AB", QRCodeModel::Micro, l), Some(2));
+        assert_eq!(version(b"ab", QRCodeModel::Micro, l), Some(3));
+        assert_eq!(version(&[b'1'; 35], QRCodeModel::Micro, l), Some(4));
+        assert_eq!(version(&[b'1'; 36], QRCodeModel::Micro, l), None);
+        assert_eq!(
+            version(&[b'1'; 21], QRCodeModel::Micro, QRCodeCorrectionLevel::Q),
+            Some(4)
+        );
+        assert_eq!(version(b"1", QRCodeModel::Micro, h), None);
+    }
+
+    #[test]
+    fn test_qrcode_fit_size() {
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::M);
+        let code = QRCode::new("https://example.com", Some(option)).unwrap();
+        // Version 2: 25 modules and 8 modules of quiet zone
+        assert_eq!(code.version().unwrap(), 2);
+        assert_eq!(code.fit_size(576).unwrap(), 15);
+        assert_eq!(code.fit_size(200).unwrap(), 6);
+        assert_eq!(code.fit_size(33).unwrap(), 1);
+        assert!(code.fit_size(32).is_err());
+    }
+
+    #[test]
+    fn test_qrcode_eci() {
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::L);
+        assert!(option.with_eci(1_000_000).is_err());
+        let option = QRCodeOption::new(QRCodeModel::Micro, 4, QRCodeCorrectionLevel::L);
+        assert!(option.with_eci(3).is_err());
+
+        // 17 bytes fit in version 1 (152 bits), not with an ECI designator (12 more bits)
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::L);
+        let code = QRCode::from_bytes(&[0xA9; 17], Some(option)).unwrap();
+        assert_eq!(code.version().unwrap(), 1);
+
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::L)
+            .with_eci(3)
+            .unwrap();
+        let code = QRCode::from_bytes(&[0xA9; 17], Some(option)).unwrap();
+        assert_eq!(code.version().unwrap(), 2);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn test_qrcode_capacity() {
+        use ::qrcode::{EcLevel, QrCode, Version};
+
+        // Model 2 byte mode capacities agree with the encoder
+        let levels = [
+            (QRCodeCorrectionLevel::L, EcLevel::L),
+            (QRCodeCorrectionLevel::M, EcLevel::M),
+            (QRCodeCorrectionLevel::Q, EcLevel::Q),
+            (QRCodeCorrectionLevel::H, EcLevel::H),
+        ];
+        for version in 1..=40u8 {
+            for (level, ec_level) in levels {
+                // Largest byte mode data of the version: 4 bits mode indicator, 8 or 16 bits character count
+                let bits = QRCodeModel::Model2.data_bits(version, level).unwrap();
+                let len = (bits - if version < 10 { 12 } else { 20 }) / 8;
+                let version_of = |len: usize| qrcode_version(&vec![b'a'; len], QRCodeModel::Model2, level).ok();
+                assert_eq!(version_of(len), Some(version));
+                assert!(version_of(len + 1).is_none_or(|v| v > version));
+
+                let encode =
+                    |len: usize| QrCode::with_version(vec![b'a'; len], Version::Normal(version.into()), ec_level);
+                assert!(encode(len).is_ok(), "version {version} {level}: {len}");
+                assert!(encode(len + 1).is_err(), "version {version} {level}: {}", len + 1);
+            }
+        }
     }
 }
@@ -0,0 +1,85 @@
+//! Reed-Solomon error correction over GF(2^m), used to render 2D codes
+
+#![cfg(all(feature = "graphics", feature = "codes_2d"))]
+
+/// Galois field GF(2^m)
+#[derive(Debug)]
+pub(crate) struct GaloisField {
+    exp: Vec<u16>,
+    log: Vec<u16>,
+}
+
+impl GaloisField {
+    /// Create a new Galois field of `size` elements from its primitive polynomial
+    pub(crate) fn new(primitive: u32, size: usize) -> Self {
+        let mut exp = vec![0; size];
+        let mut log = vec![0; size];
+        let mut x = 1u32;
+
+        for (i, value) in exp.iter_mut().enumerate() {
+            *value = x as u16;
+            if i < size - 1 {
+                log[x as usize] = i as u16;
+            }
+            x <<= 1;
+            if x >= size as u32 {
+                x ^= primitive;
+            }
+        }
+
+        Self { exp, log }
+    }
+
+    /// Multiply two elements
+    fn multiply(&self, a: u16, b: u16) -> u16 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        let order = self.exp.len() - 1;
+        self.exp[(usize::from(self.log[usize::from(a)]) + usize::from(self.log[usize::from(b)])) % order]
+    }
+
+    /// Compute the `ec_len` error correction words of `data` (generator roots: α¹ to αⁿ)
+    pub(crate) fn reed_solomon(&self, data: &[u16], ec_len: usize) -> Vec<u16> {
+        let order = self.exp.len() - 1;
+
+        // Generator polynomial, highest degree first
+        let mut generator = vec![1u16];
+        for i in 1..=ec_len {
+            let root = self.exp[i % order];
+            let mut next = vec![0u16; generator.len() + 1];
+            for (j, coefficient) in generator.iter().enumerate() {
+                next[j] ^= coefficient;
+                next[j + 1] ^= self.multiply(*coefficient, root);
+            }
+            generator = next;
+        }
+
+        // Remainder of the division of data(x)·xⁿ by the generator
+        let mut remainder = vec![0u16; ec_len];
+        for word in data {
+            let factor = word ^ remainder.first().copied().unwrap_or_default();
+            remainder.rotate_left(1);
+            if let Some(last) = remainder.last_mut() {
+                *last = 0;
+            }
+            for (r, g) in remainder.iter_mut().zip(&generator[1..]) {
+                *r ^= self.multiply(*g, factor);
+            }
+        }
+
+        remainder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reed_solomon() {
+        // DataMatrix 10x10 "123456"
+        let field = GaloisField::new(0x12D, 256);
+        assert_eq!(field.reed_solomon(&[142, 164, 186], 5), vec![114, 25, 5, 88, 102]);
+    }
+}
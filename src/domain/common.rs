@@ -3,34 +3,47 @@
 use crate::errors::{PrinterError, Result};
 
 /// Get parameters pL and pH
-pub(crate) fn get_parameters_number_2(data: &str, padding: u8) -> Result<(u8, u8)> {
-    let bytes = data.as_bytes();
-    let data_len = bytes.len() + (padding as usize);
+pub(crate) fn get_parameters_number_2(data: &[u8], padding: u8) -> Result<(u8, u8)> {
+    let data_len = data.len() + (padding as usize);
     let ph = data_len / 256;
     let pl = data_len
         .checked_add_signed(-256 * isize::try_from(ph)?)
         .ok_or(PrinterError::Input(format!(
-            "invalid parameter numbers (pL, pH) for data: {data}"
+            "invalid parameter numbers (pL, pH) for data of {} bytes",
+            data.len()
         )))?;
 
     Ok((u8::try_from(pl)?, u8::try_from(ph)?))
 }
 
+/// Maximum ECI designator
+const ECI_MAX_DESIGNATOR: u32 = 999_999;
+
+/// Check an ECI (Extended Channel Interpretation) designator
+pub(crate) fn check_eci_designator(eci: u32) -> Result<()> {
+    if eci > ECI_MAX_DESIGNATOR {
+        return Err(PrinterError::Input(format!(
+            "invalid ECI designator (0-{ECI_MAX_DESIGNATOR}): {eci}"
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_get_parameters_number_2() {
-        assert_eq!(get_parameters_number_2("test123456", 3).unwrap(), (13, 0));
+        assert_eq!(get_parameters_number_2(b"test123456", 3).unwrap(), (13, 0));
         assert_eq!(
-            get_parameters_number_2("test123456".repeat(200).as_str(), 4).unwrap(),
+            get_parameters_number_2("test123456".repeat(200).as_bytes(), 4).unwrap(),
             (212, 7)
         );
         assert_eq!(
-            get_parameters_number_2("1".repeat(65_531).as_str(), 4).unwrap(),
+            get_parameters_number_2("1".repeat(65_531).as_bytes(), 4).unwrap(),
             (255, 255)
         );
-        assert!(get_parameters_number_2("1".repeat(65_600).as_str(), 4).is_err());
+        assert!(get_parameters_number_2("1".repeat(65_600).as_bytes(), 4).is_err());
     }
 }
@@ -96,7 +96,7 @@ pub const GS_2D_QRCODE_PRINT_SYMBOL_DATA: &[u8] = &[GS, b'(', b'k', 3, 0, 49, 81
 #[cfg(feature = "codes_2d")]
 pub const GS_2D_GS1_DATABAR_WIDTH: &[u8] = &[GS, b'(', b'k', 3, 0, 51, 67];
 #[cfg(feature = "codes_2d")]
-pub const GS_2D_GS1_DATABAR_WIDTH_EXTENDED: &[u8] = &[GS, b'(', b'k', 3, 0, 51, 71];
+pub const GS_2D_GS1_DATABAR_WIDTH_EXTENDED: &[u8] = &[GS, b'(', b'k', 4, 0, 51, 71];
 #[cfg(feature = "codes_2d")]
 pub const GS_2D_GS1_DATABAR_PRINT: &[u8] = &[GS, b'(', b'k', 3, 0, 51, 81, 48];
 
@@ -140,6 +140,16 @@ pub const GS_2D_AZTEC_CODE_CORRECTION_LEVEL: &[u8] = &[GS, b'(', b'k', 4, 0, 53,
 #[cfg(feature = "codes_2d")]
 pub const GS_2D_AZTEC_CODE_PRINT: &[u8] = &[GS, b'(', b'k', 3, 0, 53, 81, 48];
 
+// Composite symbology
+#[cfg(feature = "codes_2d")]
+pub const GS_2D_COMPOSITE_WIDTH: &[u8] = &[GS, b'(', b'k', 3, 0, 52, 67];
+#[cfg(feature = "codes_2d")]
+pub const GS_2D_COMPOSITE_WIDTH_EXTENDED: &[u8] = &[GS, b'(', b'k', 4, 0, 52, 71];
+#[cfg(feature = "codes_2d")]
+pub const GS_2D_COMPOSITE_FONT: &[u8] = &[GS, b'(', b'k', 3, 0, 52, 72];
+#[cfg(feature = "codes_2d")]
+pub const GS_2D_COMPOSITE_PRINT: &[u8] = &[GS, b'(', b'k', 3, 0, 52, 81, 48];
+
 // Image
 #[cfg(feature = "graphics")]
 pub const GS_IMAGE_BITMAP_PREFIX: &[u8] = &[GS, b'v', b'0'];
@@ -0,0 +1,286 @@
+//! German receipt QR code (DSFinV-K appendix I, signed by a technical security system, TSE)
+
+use super::{check_base64, check_fiscal_field, format_fiscal_amount, FiscalDateTime, FiscalQRCode};
+use crate::{
+    domain::qrcode_payload::QRCodePayload,
+    errors::{PrinterError, Result},
+};
+
+/// Field separator
+const DSFINVK_SEPARATOR: char = ';';
+
+/// QR code version
+const DSFINVK_VERSION: &str = "V0";
+
+/// DSFinV-K process type
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DsfinvkProcessType {
+    /// Receipt (`Kassenbeleg-V1`)
+    #[default]
+    Kassenbeleg,
+    /// Order (`Bestellung-V1`)
+    Bestellung,
+    /// Other process (`SonstigerVorgang`)
+    SonstigerVorgang,
+}
+
+impl DsfinvkProcessType {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Kassenbeleg => "Kassenbeleg-V1",
+            Self::Bestellung => "Bestellung-V1",
+            Self::SonstigerVorgang => "SonstigerVorgang",
+        }
+    }
+}
+
+/// DSFinV-K payment type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DsfinvkPaymentType {
+    /// Cash (`Bar`)
+    Cash,
+    /// Non-cash (`Unbar`)
+    NonCash,
+}
+
+/// DSFinV-K log time format of the TSE
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DsfinvkTimeFormat {
+    #[default]
+    UnixTime,
+    UtcTime,
+    GeneralizedTime,
+}
+
+impl DsfinvkTimeFormat {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::UnixTime => "unixTime",
+            Self::UtcTime => "utcTime",
+            Self::GeneralizedTime => "generalizedTime",
+        }
+    }
+}
+
+/// Build the process data of a `Kassenbeleg-V1` process
+///
+/// `receipt_type` is the receipt type (`Beleg`, `AVTransfer`...), `vat_amounts` the gross amounts in cents
+/// for the VAT rates in DSFinV-K order (normal, reduced, average rates and 0 %) and `payments` the amounts in cents
+/// by payment type, with an optional ISO 4217 currency code (EUR if not given).
+///
+/// ```rust
+/// use escpos::utils::*;
+///
+/// let data = dsfinvk_receipt_process_data(
+///     "Beleg",
+///     [7533, 799, 0, 0, 0],
+///     &[(1000, DsfinvkPaymentType::Cash, None), (500, DsfinvkPaymentType::NonCash, Some("USD"))],
+/// )
+/// .unwrap();
+/// assert_eq!(data, "Beleg^75.33_7.99_0.00_0.00_0.00^10.00:Bar_5.00:Unbar:USD");
+/// ```
+pub fn dsfinvk_receipt_process_data(
+    receipt_type: &str,
+    vat_amounts: [i64; 5],
+    payments: &[(i64, DsfinvkPaymentType, Option<&str>)],
+) -> Result<String> {
+    check_fiscal_field("receipt type", receipt_type, '^', 30)?;
+
+    let vat_amounts: Vec<_> = vat_amounts
+        .iter()
+        .map(|amount| format_fiscal_amount(*amount, '.'))
+        .collect();
+    let mut payment_amounts = vec![];
+    for (amount, payment_type, currency) in payments {
+        let payment_type = match payment_type {
+            DsfinvkPaymentType::Cash => "Bar",
+            DsfinvkPaymentType::NonCash => "Unbar",
+        };
+        let mut payment = format!("{}:{payment_type}", format_fiscal_amount(*amount, '.'));
+        match currency {
+            Some(currency) if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) => {
+                return Err(PrinterError::Input(format!("invalid currency code: {currency}")));
+            }
+            Some(currency) if *currency != "EUR" => payment.push_str(&format!(":{currency}")),
+            _ => (),
+        }
+        payment_amounts.push(payment);
+    }
+
+    Ok(format!(
+        "{receipt_type}^{}^{}",
+        vat_amounts.join("_"),
+        payment_amounts.join("_")
+    ))
+}
+
+/// German receipt QR code (DSFinV-K)
+#[derive(Debug, Clone)]
+pub struct DsfinvkReceipt {
+    client_id: String,
+    process_type: DsfinvkProcessType,
+    process_data: String,
+    transaction_number: u64,
+    signature_counter: u64,
+    start_time: FiscalDateTime,
+    log_time: FiscalDateTime,
+    signature_algorithm: String,
+    log_time_format: DsfinvkTimeFormat,
+    signature: String,
+    public_key: String,
+}
+
+impl DsfinvkReceipt {
+    /// Create a new `DsfinvkReceipt`
+    ///
+    /// `client_id` is the serial number of the cash register (`kassen-seriennummer`), `signature` and `public_key`
+    /// are Base64 encoded, `signature_algorithm` is the TSE algorithm (`ecdsa-plain-SHA384`...).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        client_id: &str,
+        process_type: DsfinvkProcessType,
+        process_data: &str,
+        transaction_number: u64,
+        signature_counter: u64,
+        start_time: FiscalDateTime,
+        log_time: FiscalDateTime,
+        signature_algorithm: &str,
+        signature: &str,
+        public_key: &str,
+    ) -> Result<Self> {
+        check_fiscal_field("client ID", client_id, DSFINVK_SEPARATOR, 30)?;
+        check_fiscal_field("process data", process_data, DSFINVK_SEPARATOR, 1000)?;
+        check_fiscal_field("signature algorithm", signature_algorithm, DSFINVK_SEPARATOR, 50)?;
+        check_base64("signature", signature)?;
+        check_base64("public key", public_key)?;
+        if log_time < start_time {
+            return Err(PrinterError::Input(
+                "DSFinV-K log time must not be before the start time".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            client_id: client_id.to_string(),
+            process_type,
+            process_data: process_data.to_string(),
+            transaction_number,
+            signature_counter,
+            start_time,
+            log_time,
+            signature_algorithm: signature_algorithm.to_string(),
+            log_time_format: DsfinvkTimeFormat::default(),
+            signature: signature.to_string(),
+            public_key: public_key.to_string(),
+        })
+    }
+
+    /// Set the log time format of the TSE
+    pub fn with_log_time_format(mut self, log_time_format: DsfinvkTimeFormat) -> Self {
+        self.log_time_format = log_time_format;
+        self
+    }
+
+    /// Get client ID
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Get transaction number
+    pub fn transaction_number(&self) -> u64 {
+        self.transaction_number
+    }
+
+    /// Get signature counter
+    pub fn signature_counter(&self) -> u64 {
+        self.signature_counter
+    }
+}
+
+impl QRCodePayload for DsfinvkReceipt {
+    fn payload(&self) -> Result<String> {
+        Ok([
+            DSFINVK_VERSION,
+            &self.client_id,
+            self.process_type.name(),
+            &self.process_data,
+            &self.transaction_number.to_string(),
+            &self.signature_counter.to_string(),
+            &self.start_time.iso8601_utc(),
+            &self.log_time.iso8601_utc(),
+            &self.signature_algorithm,
+            self.log_time_format.name(),
+            &self.signature,
+            &self.public_key,
+        ]
+        .join(";"))
+    }
+}
+
+impl FiscalQRCode for DsfinvkReceipt {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURE: &str =
+        "MEUCIAy4HJcxK5Lqsjk9j4MJ+6U+qMQGQqVh1q0RH0Zj4tZaAiEAmXlZ0XmFYlHuKtFyMLbzu6XmE6GGLa3nu1dgEpzRUJA=";
+    const PUBLIC_KEY: &str = "BHhWOeisRpPBTGQ1W4VUH95TXx2GARf8e2NYZXJoInjtGqnxJ8sZ3CQpYgjI+LYEmW5A37sLWHsyU7nSJUBemyU=";
+
+    #[test]
+    fn test_dsfinvk_receipt() {
+        let time = FiscalDateTime::new(2019, 7, 10, 18, 41, 4).unwrap();
+        let process_data =
+            dsfinvk_receipt_process_data("Beleg", [0, 255, 0, 0, 0], &[(255, DsfinvkPaymentType::Cash, None)]).unwrap();
+        let receipt = DsfinvkReceipt::new(
+            "955002-00",
+            DsfinvkProcessType::Kassenbeleg,
+            &process_data,
+            18,
+            112,
+            time,
+            time,
+            "ecdsa-plain-SHA256",
+            SIGNATURE,
+            PUBLIC_KEY,
+        )
+        .unwrap();
+        assert_eq!(
+            receipt.payload().unwrap(),
+            format!(
+                "V0;955002-00;Kassenbeleg-V1;Beleg^0.00_2.55_0.00_0.00_0.00^2.55:Bar;18;112;\
+                 2019-07-10T18:41:04.000Z;2019-07-10T18:41:04.000Z;ecdsa-plain-SHA256;unixTime;{SIGNATURE};{PUBLIC_KEY}"
+            )
+        );
+
+        let qrcode = receipt.qrcode().unwrap();
+        assert!(qrcode.option.auto_size());
+        assert_eq!(qrcode.data, receipt.payload().unwrap().into_bytes());
+    }
+
+    #[test]
+    fn test_dsfinvk_receipt_invalid() {
+        let time = FiscalDateTime::new(2019, 7, 10, 18, 41, 4).unwrap();
+        let earlier = FiscalDateTime::new(2019, 7, 10, 18, 41, 3).unwrap();
+        let receipt = |client_id: &str, start_time, signature: &str| {
+            DsfinvkReceipt::new(
+                client_id,
+                DsfinvkProcessType::Kassenbeleg,
+                "Beleg^0.00_0.00_0.00_0.00_0.00^",
+                1,
+                1,
+                start_time,
+                earlier,
+                "ecdsa-plain-SHA256",
+                signature,
+                PUBLIC_KEY,
+            )
+        };
+        assert!(receipt("955002-00", earlier, SIGNATURE).is_ok());
+        assert!(receipt("955002;00", earlier, SIGNATURE).is_err());
+        assert!(receipt("", earlier, SIGNATURE).is_err());
+        assert!(receipt("955002-00", time, SIGNATURE).is_err());
+        assert!(receipt("955002-00", earlier, "not base64").is_err());
+
+        assert!(dsfinvk_receipt_process_data("Beleg", [0; 5], &[(0, DsfinvkPaymentType::Cash, Some("E"))]).is_err());
+    }
+}
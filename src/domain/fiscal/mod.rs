@@ -0,0 +1,210 @@
+//! Fiscal receipt QR code payloads
+//!
+//! DSFinV-K (German TSE) and RKSV (Austrian cash register) receipt payloads, printed with
+//! [`Printer::fiscal_qrcode`] in a Model 2 QR code as large as the printable width allows.
+//!
+//! [`Printer::fiscal_qrcode`]: crate::printer::Printer::fiscal_qrcode
+
+#![cfg(feature = "codes_2d")]
+
+mod dsfinvk;
+mod rksv;
+
+pub use dsfinvk::*;
+pub use rksv::*;
+
+use super::{
+    codes::{QRCode, QRCodeModel, QRCodeOption},
+    qrcode_payload::{check_field, QRCodePayload},
+};
+use crate::errors::{PrinterError, Result};
+
+/// Fiscal receipt QR code
+pub trait FiscalQRCode: QRCodePayload {
+    /// Get the QR code (Model 2, largest size fitting the printable width)
+    fn qrcode(&self) -> Result<QRCode> {
+        let option = QRCodeOption::new(QRCodeModel::Model2, 0, self.correction_level()).with_auto_size(true);
+        QRCode::new(&self.payload()?, Some(option))
+    }
+}
+
+/// Fiscal date and time (UTC for DSFinV-K, local time for RKSV)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FiscalDateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    millisecond: u16,
+}
+
+impl FiscalDateTime {
+    /// Create a new `FiscalDateTime` (year: 1970 - 9999)
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<Self> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+            2 => 28,
+            _ => 0,
+        };
+        if !(1970..=9999).contains(&year)
+            || !(1..=days_in_month).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(PrinterError::Input(format!(
+                "invalid date and time: {year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}"
+            )));
+        }
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            millisecond: 0,
+        })
+    }
+
+    /// Create a new `FiscalDateTime` from a Unix timestamp in milliseconds (UTC)
+    pub fn from_unix_millis(millis: u64) -> Result<Self> {
+        let seconds = millis / 1000;
+        let (days, time) = (seconds / 86_400, seconds % 86_400);
+
+        // Civil date from the number of days since 1970-01-01 (400 years eras of 146097 days)
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z % 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+        let year = u16::try_from(year).map_err(|_| PrinterError::Input(format!("invalid timestamp: {millis}")))?;
+        let date_time = Self::new(
+            year,
+            month as u8,
+            day as u8,
+            (time / 3600) as u8,
+            (time % 3600 / 60) as u8,
+            (time % 60) as u8,
+        )?;
+        Ok(date_time.with_millisecond((millis % 1000) as u16))
+    }
+
+    /// Set the milliseconds (0 - 999)
+    pub fn with_millisecond(mut self, millisecond: u16) -> Self {
+        self.millisecond = millisecond.min(999);
+        self
+    }
+
+    /// ISO 8601 UTC format with milliseconds (`2019-07-10T18:41:04.000Z`)
+    pub(crate) fn iso8601_utc(&self) -> String {
+        format!("{}.{:03}Z", self.iso8601_local(), self.millisecond)
+    }
+
+    /// ISO 8601 local format without time zone (`2019-07-10T18:41:04`)
+    pub(crate) fn iso8601_local(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Check a field: not empty, no `separator` and a valid payload field
+pub(crate) fn check_fiscal_field(name: &str, value: &str, separator: char, max_len: usize) -> Result<()> {
+    if value.is_empty() {
+        return Err(PrinterError::Input(format!("invalid {name}: empty")));
+    }
+    if value.contains(separator) {
+        return Err(PrinterError::Input(format!(
+            "invalid {name}: '{separator}' is not allowed"
+        )));
+    }
+    check_field(name, value, max_len)
+}
+
+/// Check a Base64 encoded field (standard alphabet with padding)
+pub(crate) fn check_base64(name: &str, value: &str) -> Result<()> {
+    let data = value.trim_end_matches('=');
+    let is_valid = !value.is_empty()
+        && value.len().is_multiple_of(4)
+        && value.len() - data.len() <= 2
+        && data.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/');
+
+    match is_valid {
+        true => Ok(()),
+        false => Err(PrinterError::Input(format!("invalid Base64 {name}: {value}"))),
+    }
+}
+
+/// Format an amount in cents with two decimals and `decimal_separator`
+pub(crate) fn format_fiscal_amount(cents: i64, decimal_separator: char) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{sign}{}{decimal_separator}{:02}", cents / 100, cents % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fiscal_date_time() {
+        let date_time = FiscalDateTime::new(2019, 7, 10, 18, 41, 4).unwrap();
+        assert_eq!(date_time.iso8601_utc(), "2019-07-10T18:41:04.000Z");
+        assert_eq!(date_time.iso8601_local(), "2019-07-10T18:41:04");
+        assert_eq!(
+            FiscalDateTime::from_unix_millis(1_562_784_064_123).unwrap(),
+            date_time.with_millisecond(123)
+        );
+        assert_eq!(
+            FiscalDateTime::from_unix_millis(951_782_400_000).unwrap(),
+            FiscalDateTime::new(2000, 2, 29, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            FiscalDateTime::from_unix_millis(0).unwrap(),
+            FiscalDateTime::new(1970, 1, 1, 0, 0, 0).unwrap()
+        );
+
+        assert!(FiscalDateTime::new(2019, 2, 29, 0, 0, 0).is_err());
+        assert!(FiscalDateTime::new(2019, 13, 1, 0, 0, 0).is_err());
+        assert!(FiscalDateTime::new(2019, 1, 1, 24, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_check_fiscal_field() {
+        assert!(check_fiscal_field("client ID", "Kasse 1", ';', 30).is_ok());
+        assert!(check_fiscal_field("client ID", "", ';', 30).is_err());
+        assert!(check_fiscal_field("client ID", "Kasse;1", ';', 30).is_err());
+        assert!(check_fiscal_field("client ID", "Kasse\t1", ';', 30).is_err());
+        assert!(check_fiscal_field("client ID", &"K".repeat(31), ';', 30).is_err());
+    }
+
+    #[test]
+    fn test_check_base64() {
+        assert!(check_base64("signature", "TWFu").is_ok());
+        assert!(check_base64("signature", "TWE=").is_ok());
+        assert!(check_base64("signature", "TQ==").is_ok());
+        assert!(check_base64("signature", "TQ=").is_err());
+        assert!(check_base64("signature", "T===").is_err());
+        assert!(check_base64("signature", "TW_u").is_err());
+        assert!(check_base64("signature", "").is_err());
+    }
+
+    #[test]
+    fn test_format_fiscal_amount() {
+        assert_eq!(format_fiscal_amount(7533, '.'), "75.33");
+        assert_eq!(format_fiscal_amount(0, ','), "0,00");
+        assert_eq!(format_fiscal_amount(-505, ','), "-5,05");
+    }
+}
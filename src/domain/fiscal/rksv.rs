@@ -0,0 +1,196 @@
+//! Austrian receipt QR code (RKSV machine-readable code, signed by a signature creation device)
+
+use super::{check_base64, check_fiscal_field, format_fiscal_amount, FiscalDateTime, FiscalQRCode};
+use crate::{
+    domain::qrcode_payload::QRCodePayload,
+    errors::{PrinterError, Result},
+};
+
+/// Field separator
+const RKSV_SEPARATOR: char = '_';
+
+/// Signature value of receipts signed while the signature creation device failed
+/// (`Sicherheitseinrichtung ausgefallen` in Base64)
+pub const RKSV_SIGNATURE_DEVICE_FAILED: &str = "U2ljaGVyaGVpdHNlaW5yaWNodHVuZyBhdXNnZWZhbGxlbg==";
+
+/// RKSV amounts by VAT rate, in cents
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RksvAmounts {
+    /// Normal rate (20 %)
+    pub normal: i64,
+    /// First reduced rate (10 %)
+    pub reduced_1: i64,
+    /// Second reduced rate (13 %)
+    pub reduced_2: i64,
+    /// Zero rate (0 %)
+    pub zero: i64,
+    /// Special rate (19 %)
+    pub special: i64,
+}
+
+/// Austrian receipt QR code (RKSV)
+#[derive(Debug, Clone)]
+pub struct RksvReceipt {
+    certification_provider: String,
+    cash_register_id: String,
+    receipt_number: String,
+    date_time: FiscalDateTime,
+    amounts: RksvAmounts,
+    turnover_counter: String,
+    certificate_serial: String,
+    previous_signature: String,
+    signature: String,
+}
+
+impl RksvReceipt {
+    /// Create a new `RksvReceipt`
+    ///
+    /// `certification_provider` is the trust service provider code of the algorithm suite (`AT0` to `AT99`),
+    /// `date_time` is the local date and time of the receipt. The encrypted turnover counter, the chaining value
+    /// of the previous receipt and the signature are Base64 encoded ([`RKSV_SIGNATURE_DEVICE_FAILED`] if the
+    /// signature creation device failed).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        certification_provider: &str,
+        cash_register_id: &str,
+        receipt_number: &str,
+        date_time: FiscalDateTime,
+        amounts: RksvAmounts,
+        turnover_counter: &str,
+        certificate_serial: &str,
+        previous_signature: &str,
+        signature: &str,
+    ) -> Result<Self> {
+        let is_valid_provider = certification_provider
+            .strip_prefix("AT")
+            .is_some_and(|id| (1..=2).contains(&id.len()) && id.chars().all(|c| c.is_ascii_digit()));
+        if !is_valid_provider {
+            return Err(PrinterError::Input(format!(
+                "invalid RKSV certification provider: {certification_provider}"
+            )));
+        }
+        check_fiscal_field("cash register ID", cash_register_id, RKSV_SEPARATOR, 100)?;
+        check_fiscal_field("receipt number", receipt_number, RKSV_SEPARATOR, 100)?;
+        check_fiscal_field("certificate serial number", certificate_serial, RKSV_SEPARATOR, 100)?;
+        check_base64("turnover counter", turnover_counter)?;
+        check_base64("previous signature", previous_signature)?;
+        check_base64("signature", signature)?;
+
+        Ok(Self {
+            certification_provider: certification_provider.to_string(),
+            cash_register_id: cash_register_id.to_string(),
+            receipt_number: receipt_number.to_string(),
+            date_time,
+            amounts,
+            turnover_counter: turnover_counter.to_string(),
+            certificate_serial: certificate_serial.to_string(),
+            previous_signature: previous_signature.to_string(),
+            signature: signature.to_string(),
+        })
+    }
+
+    /// Get cash register ID
+    pub fn cash_register_id(&self) -> &str {
+        &self.cash_register_id
+    }
+
+    /// Get receipt number
+    pub fn receipt_number(&self) -> &str {
+        &self.receipt_number
+    }
+
+    /// Get amounts
+    pub fn amounts(&self) -> RksvAmounts {
+        self.amounts
+    }
+
+    /// Was the receipt signed while the signature creation device failed?
+    pub fn is_signature_device_failed(&self) -> bool {
+        self.signature == RKSV_SIGNATURE_DEVICE_FAILED
+    }
+}
+
+impl QRCodePayload for RksvReceipt {
+    fn payload(&self) -> Result<String> {
+        let amounts = &self.amounts;
+        let suite = format!("R1-{}", self.certification_provider);
+
+        Ok([
+            // The payload starts with a separator
+            "",
+            &suite,
+            &self.cash_register_id,
+            &self.receipt_number,
+            &self.date_time.iso8601_local(),
+            &format_fiscal_amount(amounts.normal, ','),
+            &format_fiscal_amount(amounts.reduced_1, ','),
+            &format_fiscal_amount(amounts.reduced_2, ','),
+            &format_fiscal_amount(amounts.zero, ','),
+            &format_fiscal_amount(amounts.special, ','),
+            &self.turnover_counter,
+            &self.certificate_serial,
+            &self.previous_signature,
+            &self.signature,
+        ]
+        .join("_"))
+    }
+}
+
+impl FiscalQRCode for RksvReceipt {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rksv_receipt() {
+        let amounts = RksvAmounts {
+            normal: 2973,
+            reduced_1: 3600,
+            special: -500,
+            ..Default::default()
+        };
+        let receipt = RksvReceipt::new(
+            "AT0",
+            "DEMO-CASH-BOX524",
+            "366596",
+            FiscalDateTime::new(2015, 12, 17, 11, 23, 44).unwrap(),
+            amounts,
+            "Dx/9Kmc=",
+            "3D2F6B0E",
+            "Q+2Dc0mH/e0=",
+            "pVrR1lvS2Ux1Vh/qiiQ2KzwGN0Vdpr5Ozy5MKeCm6svpJFpY6uZP3tMYXhxAmAbQJhfOOPLPTPfemr2TSYLlyQ==",
+        )
+        .unwrap();
+        assert_eq!(
+            receipt.payload().unwrap(),
+            "_R1-AT0_DEMO-CASH-BOX524_366596_2015-12-17T11:23:44_29,73_36,00_0,00_0,00_-5,00_Dx/9Kmc=_3D2F6B0E_\
+             Q+2Dc0mH/e0=_pVrR1lvS2Ux1Vh/qiiQ2KzwGN0Vdpr5Ozy5MKeCm6svpJFpY6uZP3tMYXhxAmAbQJhfOOPLPTPfemr2TSYLlyQ=="
+        );
+        assert!(!receipt.is_signature_device_failed());
+    }
+
+    #[test]
+    fn test_rksv_receipt_invalid() {
+        let receipt = |provider: &str, cash_register_id: &str, signature: &str| {
+            RksvReceipt::new(
+                provider,
+                cash_register_id,
+                "1",
+                FiscalDateTime::new(2015, 12, 17, 11, 23, 44).unwrap(),
+                RksvAmounts::default(),
+                "Dx/9Kmc=",
+                "3D2F6B0E",
+                "Q+2Dc0mH/e0=",
+                signature,
+            )
+        };
+        assert!(receipt("AT1", "KASSE-1", RKSV_SIGNATURE_DEVICE_FAILED)
+            .unwrap()
+            .is_signature_device_failed());
+        assert!(receipt("DE1", "KASSE-1", RKSV_SIGNATURE_DEVICE_FAILED).is_err());
+        assert!(receipt("AT100", "KASSE-1", RKSV_SIGNATURE_DEVICE_FAILED).is_err());
+        assert!(receipt("AT1", "KASSE_1", RKSV_SIGNATURE_DEVICE_FAILED).is_err());
+        assert!(receipt("AT1", "KASSE-1", "invalid signature").is_err());
+    }
+}
@@ -1,21 +1,40 @@
+mod barcode_image;
 mod bit_image;
 mod character;
+mod code_2d_image;
 mod codes;
 pub(crate) mod common;
 mod constants;
+mod fiscal;
 mod graphics;
 mod page_codes;
+mod payments;
 mod protocol;
+mod qrcode_payload;
 mod status;
+mod text_image;
+mod thai;
 mod types;
 
+#[cfg(all(feature = "graphics", feature = "barcodes"))]
+pub use barcode_image::*;
 #[cfg(feature = "graphics")]
 pub use bit_image::*;
 pub use character::*;
 pub use codes::*;
 pub use constants::*;
+#[cfg(feature = "codes_2d")]
+pub use fiscal::*;
 #[cfg(feature = "graphics")]
 pub use graphics::*;
+pub use page_codes::CustomPageCode;
+#[cfg(feature = "codes_2d")]
+pub use payments::*;
 pub use protocol::*;
+#[cfg(feature = "codes_2d")]
+pub use qrcode_payload::*;
 pub use status::*;
+#[cfg(feature = "graphics")]
+pub use text_image::*;
+pub use thai::*;
 pub use types::*;
@@ -1,13 +1,18 @@
 //! List of page codes
 
 use crate::domain::PageCode;
-use crate::errors::PrinterError;
+use crate::errors::{PrinterError, Result};
 use std::collections::HashMap;
+use std::fs;
 use std::iter::{IntoIterator, Iterator};
-use std::sync::LazyLock;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// Number of characters in a page code table (0x80 - 0xFF)
+const CUSTOM_TABLE_SIZE: usize = 128;
 
 /// Page codes table list
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub(crate) enum PageCodeTable {
     PC437,
     Katakana,
@@ -40,6 +45,8 @@ pub(crate) enum PageCodeTable {
     WPC1254,
     WPC1257,
     KZ1048,
+    Thai18,
+    Custom(Arc<HashMap<char, u8>>),
 }
 
 impl PageCodeTable {
@@ -77,6 +84,8 @@ impl PageCodeTable {
             Self::WPC1254 => &WPC1254_TABLE,
             Self::WPC1257 => &WPC1257_TABLE,
             Self::KZ1048 => &KZ1048_TABLE,
+            Self::Thai18 => &THAI18_TABLE,
+            Self::Custom(table) => table,
         }
     }
 }
@@ -84,7 +93,7 @@ impl PageCodeTable {
 impl TryFrom<PageCode> for PageCodeTable {
     type Error = PrinterError;
 
-    fn try_from(value: PageCode) -> Result<Self, Self::Error> {
+    fn try_from(value: PageCode) -> Result<Self> {
         match value {
             PageCode::PC437 => Ok(Self::PC437),
             PageCode::Katakana => Ok(Self::Katakana),
@@ -117,11 +126,229 @@ impl TryFrom<PageCode> for PageCodeTable {
             PageCode::WPC1254 => Ok(Self::WPC1254),
             PageCode::WPC1257 => Ok(Self::WPC1257),
             PageCode::KZ1048 => Ok(Self::KZ1048),
+            PageCode::Thai18 => Ok(Self::Thai18),
+            PageCode::Custom(number) => CUSTOM_TABLES
+                .read()
+                .map_err(|e| PrinterError::Io(e.to_string()))?
+                .get(&number)
+                .map(|table| Self::Custom(Arc::clone(table)))
+                .ok_or(PrinterError::Input(format!(
+                    "no custom table registered for this page code: {value}"
+                ))),
             _ => Err(PrinterError::Input(format!("no table for this page code: {value}"))),
         }
     }
 }
 
+type CustomTables = HashMap<u8, Arc<HashMap<char, u8>>>;
+
+/// Custom page code tables registered at runtime, indexed by their `ESC t` number
+static CUSTOM_TABLES: LazyLock<RwLock<CustomTables>> = LazyLock::new(Default::default);
+
+/// Custom page code
+///
+/// Describes a code page which is not built into the crate, for example a vendor-specific table
+/// or a standard table selected with a non-Epson `ESC t` number.
+/// Once [registered](CustomPageCode::register), it can be used as any built-in page code
+/// with [`PageCode::Custom`].
+///
+/// The table can be loaded from a text file:
+///
+/// ```text
+/// # Comments start with '#'
+/// number 30
+/// 0x80 U+0410
+/// 0x81 Б
+/// ```
+///
+/// or, with the `json` feature, from a JSON file containing the 128 characters from 0x80 to 0xFF
+/// (`null` for unmapped positions):
+///
+/// ```json
+/// { "number": 30, "characters": ["А", "Б", null] }
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use escpos::printer::Printer;
+/// use escpos::printer_options::PrinterOptions;
+/// use escpos::utils::*;
+/// use escpos::{driver::*, errors::Result};
+///
+/// fn main() -> Result<()> {
+///     let page_code = CustomPageCode::from_text("number 90\n0x80 U+0416\n0x81 Щ")?.register()?;
+///
+///     let driver = ConsoleDriver::open(false);
+///     Printer::new(driver, Protocol::default(), Some(PrinterOptions::new(Some(page_code), None, 42)))
+///         .init()?
+///         .writeln("Ж Щ")?
+///         .print_cut()?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CustomPageCode {
+    number: u8,
+    table: HashMap<char, u8>,
+}
+
+impl CustomPageCode {
+    /// Create a new `CustomPageCode`
+    ///
+    /// `characters` contains the 128 characters from 0x80 to 0xFF.
+    /// `'\0'` can be used as placeholder for empty spots.
+    pub fn new(number: u8, characters: &[char]) -> Result<Self> {
+        if characters.len() != CUSTOM_TABLE_SIZE {
+            return Err(PrinterError::Input(format!(
+                "custom page code table must contain {CUSTOM_TABLE_SIZE} characters: {}",
+                characters.len()
+            )));
+        }
+
+        let table = characters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c != '\0')
+            .map(|(i, c)| (*c, (i + 0x80) as u8))
+            .collect();
+
+        Ok(Self { number, table })
+    }
+
+    /// Load a custom page code from its text representation
+    ///
+    /// Each line contains either the `ESC t` number (`number <n>`), or a byte (`0x80` - `0xFF` or `128` - `255`)
+    /// followed by a character (the character itself or its code point `U+XXXX`).
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut number = None;
+        let mut table = HashMap::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_line = || PrinterError::Input(format!("invalid custom page code line {}: {line}", index + 1));
+            let (key, value) = line.split_once(char::is_whitespace).ok_or_else(invalid_line)?;
+            let value = value.trim();
+
+            if key == "number" {
+                number = Some(value.parse::<u8>().map_err(|_| invalid_line())?);
+                continue;
+            }
+
+            let byte = Self::parse_byte(key).ok_or_else(invalid_line)?;
+            let c = Self::parse_char(value).ok_or_else(invalid_line)?;
+            table.insert(c, byte);
+        }
+
+        let number = number.ok_or(PrinterError::Input("missing custom page code number".to_owned()))?;
+
+        Ok(Self { number, table })
+    }
+
+    /// Load a custom page code from its JSON representation
+    ///
+    /// The JSON object contains the `ESC t` number and the 128 characters from 0x80 to 0xFF.
+    /// Each character is either a string (the character itself or its code point `U+XXXX`) or `null`.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| PrinterError::Input(format!("invalid custom page code: {e}")))?;
+
+        let number = value
+            .get("number")
+            .and_then(|n| n.as_u64())
+            .and_then(|n| u8::try_from(n).ok())
+            .ok_or(PrinterError::Input(
+                "missing or invalid custom page code number".to_owned(),
+            ))?;
+
+        let characters = value
+            .get("characters")
+            .and_then(|c| c.as_array())
+            .ok_or(PrinterError::Input("missing custom page code characters".to_owned()))?
+            .iter()
+            .map(|c| match c {
+                serde_json::Value::Null => Some('\0'),
+                serde_json::Value::String(s) => Self::parse_char(s),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(PrinterError::Input("invalid custom page code characters".to_owned()))?;
+
+        Self::new(number, &characters)
+    }
+
+    /// Load a custom page code from a file
+    ///
+    /// Files with a `.json` extension are read with `from_json` (`json` feature needed),
+    /// others with [`from_text`](CustomPageCode::from_text).
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "json")]
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&content),
+            #[cfg(not(feature = "json"))]
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Err(PrinterError::Input(
+                "JSON custom page codes need the `json` feature".to_owned(),
+            )),
+            _ => Self::from_text(&content),
+        }
+    }
+
+    /// Get the `ESC t` number
+    pub fn number(&self) -> u8 {
+        self.number
+    }
+
+    /// Get the [page code](PageCode) which selects this table once registered
+    pub fn page_code(&self) -> PageCode {
+        PageCode::Custom(self.number)
+    }
+
+    /// Register the table so that it can be selected with [`PageCode::Custom`]
+    ///
+    /// A table already registered with the same number is replaced.
+    pub fn register(self) -> Result<PageCode> {
+        let page_code = self.page_code();
+        CUSTOM_TABLES
+            .write()
+            .map_err(|e| PrinterError::Io(e.to_string()))?
+            .insert(self.number, Arc::new(self.table));
+
+        Ok(page_code)
+    }
+
+    /// Parse a byte of the upper half of the table (`0x80` or `128`)
+    fn parse_byte(value: &str) -> Option<u8> {
+        let byte = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+            Some(hex) => u8::from_str_radix(hex, 16).ok()?,
+            None => value.parse::<u8>().ok()?,
+        };
+
+        (byte >= 0x80).then_some(byte)
+    }
+
+    /// Parse a character (`é` or `U+00E9`)
+    fn parse_char(value: &str) -> Option<char> {
+        if let Some(code_point) = value.strip_prefix("U+").or_else(|| value.strip_prefix("u+")) {
+            return u32::from_str_radix(code_point, 16).ok().and_then(char::from_u32);
+        }
+
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    }
+}
+
 /// PC437 Page code table
 static PC437_TABLE: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
     [
@@ -639,3 +866,101 @@ static KZ1048_TABLE: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
     .map(|(i, c)| (c, (i + 0x80) as u8))
     .collect()
 });
+
+/// Thai character code 18 Page code table (TIS-620 with Windows-874 extensions)
+/// Uses '\0' as placeholder for empty spots
+static THAI18_TABLE: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
+    [
+        '€', '\0', '\0', '\0', '\0', '…', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '‘', '’',
+        '“', '”', '•', '–', '—', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\u{00A0}', 'ก', 'ข', 'ฃ', 'ค', 'ฅ',
+        'ฆ', 'ง', 'จ', 'ฉ', 'ช', 'ซ', 'ฌ', 'ญ', 'ฎ', 'ฏ', 'ฐ', 'ฑ', 'ฒ', 'ณ', 'ด', 'ต', 'ถ', 'ท', 'ธ', 'น', 'บ', 'ป',
+        'ผ', 'ฝ', 'พ', 'ฟ', 'ภ', 'ม', 'ย', 'ร', 'ฤ', 'ล', 'ฦ', 'ว', 'ศ', 'ษ', 'ส', 'ห', 'ฬ', 'อ', 'ฮ', 'ฯ', 'ะ',
+        '\u{0E31}', 'า', 'ำ', '\u{0E34}', '\u{0E35}', '\u{0E36}', '\u{0E37}', '\u{0E38}', '\u{0E39}', '\u{0E3A}',
+        '\0', '\0', '\0', '\0', '฿', 'เ', 'แ', 'โ', 'ใ', 'ไ', 'ๅ', 'ๆ', '\u{0E47}', '\u{0E48}', '\u{0E49}',
+        '\u{0E4A}', '\u{0E4B}', '\u{0E4C}', '\u{0E4D}', '\u{0E4E}', '๏', '๐', '๑', '๒', '๓', '๔', '๕', '๖', '๗', '๘',
+        '๙', '๚', '๛', '\0', '\0', '\0', '\0',
+    ]
+    .into_iter()
+    .enumerate()
+    .filter(|(_, c)| *c != '\0')
+    .map(|(i, c)| (c, (i + 0x80) as u8))
+    .collect()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_page_code_new() {
+        let mut characters = ['\0'; CUSTOM_TABLE_SIZE];
+        characters[0] = 'Ж';
+        characters[127] = 'Щ';
+
+        let custom = CustomPageCode::new(60, &characters).unwrap();
+        assert_eq!(custom.number(), 60);
+        assert_eq!(custom.page_code(), PageCode::Custom(60));
+        assert_eq!(custom.table.len(), 2);
+        assert_eq!(custom.table.get(&'Ж'), Some(&0x80));
+        assert_eq!(custom.table.get(&'Щ'), Some(&0xFF));
+
+        assert!(CustomPageCode::new(60, &['a'; 127]).is_err());
+    }
+
+    #[test]
+    fn test_custom_page_code_from_text() {
+        let custom = CustomPageCode::from_text("# Test\n\nnumber 61\n0x80 U+0416\n129 Щ\n0xff  é  ").unwrap();
+        assert_eq!(custom.number(), 61);
+        assert_eq!(custom.table.get(&'Ж'), Some(&0x80));
+        assert_eq!(custom.table.get(&'Щ'), Some(&0x81));
+        assert_eq!(custom.table.get(&'é'), Some(&0xFF));
+
+        assert!(CustomPageCode::from_text("0x80 U+0416").is_err());
+        assert!(CustomPageCode::from_text("number 256").is_err());
+        assert!(CustomPageCode::from_text("number 61\n0x7F a").is_err());
+        assert!(CustomPageCode::from_text("number 61\n0x80 ab").is_err());
+        assert!(CustomPageCode::from_text("number 61\n0x80").is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_custom_page_code_from_json() {
+        let mut characters = vec!["null".to_string(); CUSTOM_TABLE_SIZE];
+        characters[0] = "\"Ж\"".to_string();
+        characters[1] = "\"U+0429\"".to_string();
+        let json = format!(r#"{{ "number": 62, "characters": [{}] }}"#, characters.join(","));
+
+        let custom = CustomPageCode::from_json(&json).unwrap();
+        assert_eq!(custom.number(), 62);
+        assert_eq!(custom.table.len(), 2);
+        assert_eq!(custom.table.get(&'Ж'), Some(&0x80));
+        assert_eq!(custom.table.get(&'Щ'), Some(&0x81));
+
+        assert!(CustomPageCode::from_json(r#"{ "number": 62, "characters": ["a"] }"#).is_err());
+        assert!(CustomPageCode::from_json(r#"{ "characters": [] }"#).is_err());
+        assert!(CustomPageCode::from_json("number 62").is_err());
+    }
+
+    #[test]
+    fn test_custom_page_code_from_file() {
+        let custom = CustomPageCode::from_file(Path::new("./resources/page_codes/custom.txt")).unwrap();
+        assert_eq!(custom.number(), 63);
+        assert_eq!(custom.table.get(&'€'), Some(&0x80));
+
+        assert!(CustomPageCode::from_file(Path::new("./resources/page_codes/unknown.txt")).is_err());
+    }
+
+    #[test]
+    fn test_custom_page_code_register() {
+        assert!(PageCodeTable::try_from(PageCode::Custom(64)).is_err());
+
+        let page_code = CustomPageCode::from_text("number 64\n0x80 Ж")
+            .unwrap()
+            .register()
+            .unwrap();
+        assert_eq!(page_code, PageCode::Custom(64));
+
+        let table = PageCodeTable::try_from(page_code).unwrap();
+        assert_eq!(table.get_table().get(&'Ж'), Some(&0x80));
+    }
+}
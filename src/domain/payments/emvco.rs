@@ -0,0 +1,328 @@
+//! EMVCo merchant-presented QR code (EMV QRCPS Merchant-Presented Mode)
+
+use super::PaymentQRCode;
+use crate::{
+    domain::qrcode_payload::{check_field, QRCodePayload},
+    errors::{PrinterError, Result},
+};
+
+/// Maximum payload length in characters
+const EMVCO_MAX_LEN: usize = 512;
+
+/// Compute the CRC-16/CCITT-FALSE checksum (polynomial `0x1021`, initial value `0xFFFF`)
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xFFFF, |crc, byte| {
+        (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| match crc & 0x8000 {
+            0 => crc << 1,
+            _ => (crc << 1) ^ 0x1021,
+        })
+    })
+}
+
+/// Encode a TLV data object (2 digits ID, 2 digits length, value of 1 - 99 characters)
+fn tlv(id: u8, value: &str) -> Result<String> {
+    let len = value.chars().count();
+    if !(1..=99).contains(&len) {
+        return Err(PrinterError::Input(format!(
+            "invalid EMVCo data object {id:02} length: {len} (1 - 99)"
+        )));
+    }
+    Ok(format!("{id:02}{len:02}{value}"))
+}
+
+/// Check a field only contains digits and has a length in `lengths`
+fn check_digits(name: &str, value: &str, lengths: std::ops::RangeInclusive<usize>) -> Result<()> {
+    match lengths.contains(&value.len()) && value.chars().all(|c| c.is_ascii_digit()) {
+        true => Ok(()),
+        false => Err(PrinterError::Input(format!("invalid {name}: {value}"))),
+    }
+}
+
+/// EMVCo point of initiation method
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum EmvcoInitiation {
+    /// Same QR code for more than one transaction
+    #[default]
+    Static,
+    /// New QR code for each transaction
+    Dynamic,
+}
+
+/// EMVCo merchant account information (template with a globally unique identifier)
+#[derive(Debug, Clone)]
+pub struct EmvcoMerchantAccount {
+    id: u8,
+    guid: String,
+    fields: Vec<(u8, String)>,
+}
+
+impl EmvcoMerchantAccount {
+    /// Create a new `EmvcoMerchantAccount` (ID: 02 - 51, 26 - 51 for templates with a GUID)
+    pub fn new(id: u8, guid: &str) -> Result<Self> {
+        if !(26..=51).contains(&id) {
+            return Err(PrinterError::Input(format!(
+                "invalid EMVCo merchant account template ID: {id} (26 - 51)"
+            )));
+        }
+        check_field("merchant account GUID", guid, 32)?;
+
+        Ok(Self {
+            id,
+            guid: guid.to_string(),
+            fields: vec![],
+        })
+    }
+
+    /// Create a new `EmvcoMerchantAccount` with a payment network specific value (ID: 02 - 25)
+    pub fn network(id: u8, value: &str) -> Result<Self> {
+        if !(2..=25).contains(&id) {
+            return Err(PrinterError::Input(format!(
+                "invalid EMVCo payment network ID: {id} (02 - 25)"
+            )));
+        }
+        check_field("merchant account", value, 99)?;
+
+        Ok(Self {
+            id,
+            guid: value.to_string(),
+            fields: vec![],
+        })
+    }
+
+    /// Add a template field (ID: 01 - 99)
+    pub fn with_field(mut self, id: u8, value: &str) -> Result<Self> {
+        if !(1..=99).contains(&id) || !(26..=51).contains(&self.id) {
+            return Err(PrinterError::Input(format!(
+                "invalid EMVCo merchant account field ID: {id}"
+            )));
+        }
+        check_field("merchant account field", value, 99)?;
+        self.fields.push((id, value.to_string()));
+        Ok(self)
+    }
+
+    /// Get ID
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    fn encode(&self) -> Result<String> {
+        if self.id <= 25 {
+            return tlv(self.id, &self.guid);
+        }
+
+        let mut value = tlv(0, &self.guid)?;
+        for (id, field) in &self.fields {
+            value.push_str(&tlv(*id, field)?);
+        }
+        tlv(self.id, &value)
+    }
+}
+
+/// EMVCo merchant-presented QR code
+#[derive(Debug, Clone)]
+pub struct EmvcoMerchantQR {
+    initiation: EmvcoInitiation,
+    accounts: Vec<EmvcoMerchantAccount>,
+    category_code: String,
+    currency: String,
+    amount: Option<String>,
+    country_code: String,
+    name: String,
+    city: String,
+    postal_code: Option<String>,
+    additional_data: Vec<(u8, String)>,
+}
+
+impl EmvcoMerchantQR {
+    /// Create a new `EmvcoMerchantQR`
+    ///
+    /// Merchant category code: 4 digits (ISO 18245), currency: 3 digits (ISO 4217),
+    /// country code: ISO 3166-1 alpha-2, name: max 25 characters, city: max 15 characters
+    pub fn new(
+        account: EmvcoMerchantAccount,
+        category_code: &str,
+        currency: &str,
+        country_code: &str,
+        name: &str,
+        city: &str,
+    ) -> Result<Self> {
+        check_digits("merchant category code", category_code, 4..=4)?;
+        check_digits("currency", currency, 3..=3)?;
+        if country_code.len() != 2 || !country_code.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(PrinterError::Input(format!("invalid country code: {country_code}")));
+        }
+        check_field("merchant name", name, 25)?;
+        check_field("merchant city", city, 15)?;
+        if name.is_empty() || city.is_empty() {
+            return Err(PrinterError::Input("merchant name and city are required".to_string()));
+        }
+
+        Ok(Self {
+            initiation: EmvcoInitiation::default(),
+            accounts: vec![account],
+            category_code: category_code.to_string(),
+            currency: currency.to_string(),
+            amount: None,
+            country_code: country_code.to_string(),
+            name: name.to_string(),
+            city: city.to_string(),
+            postal_code: None,
+            additional_data: vec![],
+        })
+    }
+
+    /// Set the point of initiation method
+    pub fn with_initiation(mut self, initiation: EmvcoInitiation) -> Self {
+        self.initiation = initiation;
+        self
+    }
+
+    /// Add a merchant account
+    pub fn with_account(mut self, account: EmvcoMerchantAccount) -> Result<Self> {
+        if self.accounts.iter().any(|a| a.id == account.id) {
+            return Err(PrinterError::Input(format!(
+                "duplicate EMVCo merchant account ID: {}",
+                account.id
+            )));
+        }
+        self.accounts.push(account);
+        Ok(self)
+    }
+
+    /// Set the transaction amount (max 13 characters, digits and an optional `.`, e.g. `12.50`)
+    pub fn with_amount(mut self, amount: &str) -> Result<Self> {
+        let is_valid = (1..=13).contains(&amount.len())
+            && amount.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && amount.matches('.').count() <= 1
+            && amount.chars().any(|c| c.is_ascii_digit());
+        if !is_valid {
+            return Err(PrinterError::Input(format!("invalid amount: {amount}")));
+        }
+        self.amount = Some(amount.to_string());
+        Ok(self)
+    }
+
+    /// Set the postal code (max 10 characters)
+    pub fn with_postal_code(mut self, postal_code: &str) -> Result<Self> {
+        check_field("postal code", postal_code, 10)?;
+        self.postal_code = Some(postal_code.to_string());
+        Ok(self)
+    }
+
+    /// Add an additional data field (ID: 01 - 99, e.g. 01 for the bill number, 05 for the reference label)
+    pub fn with_additional_data(mut self, id: u8, value: &str) -> Result<Self> {
+        if !(1..=99).contains(&id) {
+            return Err(PrinterError::Input(format!("invalid EMVCo additional data ID: {id}")));
+        }
+        check_field("additional data", value, 25)?;
+        self.additional_data.push((id, value.to_string()));
+        Ok(self)
+    }
+}
+
+impl QRCodePayload for EmvcoMerchantQR {
+    fn payload(&self) -> Result<String> {
+        let initiation = match self.initiation {
+            EmvcoInitiation::Static => "11",
+            EmvcoInitiation::Dynamic => "12",
+        };
+
+        let mut payload = tlv(0, "01")? + &tlv(1, initiation)?;
+        let mut accounts: Vec<_> = self.accounts.iter().collect();
+        accounts.sort_by_key(|account| account.id);
+        for account in accounts {
+            payload.push_str(&account.encode()?);
+        }
+        payload.push_str(&tlv(52, &self.category_code)?);
+        payload.push_str(&tlv(53, &self.currency)?);
+        if let Some(amount) = &self.amount {
+            payload.push_str(&tlv(54, amount)?);
+        }
+        payload.push_str(&tlv(58, &self.country_code)?);
+        payload.push_str(&tlv(59, &self.name)?);
+        payload.push_str(&tlv(60, &self.city)?);
+        if let Some(postal_code) = &self.postal_code {
+            payload.push_str(&tlv(61, postal_code)?);
+        }
+        if !self.additional_data.is_empty() {
+            let mut data = String::new();
+            for (id, value) in &self.additional_data {
+                data.push_str(&tlv(*id, value)?);
+            }
+            payload.push_str(&tlv(62, &data)?);
+        }
+
+        // The CRC covers the payload including the CRC ID and length
+        payload.push_str("6304");
+        payload.push_str(&format!("{:04X}", crc16(payload.as_bytes())));
+
+        let len = payload.chars().count();
+        if len > EMVCO_MAX_LEN {
+            return Err(PrinterError::Input(format!(
+                "EMVCo payload too long: {len} characters (max {EMVCO_MAX_LEN})"
+            )));
+        }
+        Ok(payload)
+    }
+}
+
+impl PaymentQRCode for EmvcoMerchantQR {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc16() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+        assert_eq!(crc16(b""), 0xFFFF);
+    }
+
+    #[test]
+    fn test_emvco_merchant_qr() {
+        let account = EmvcoMerchantAccount::new(26, "com.example.pay")
+            .unwrap()
+            .with_field(1, "123456")
+            .unwrap();
+        let qr = EmvcoMerchantQR::new(account, "5812", "978", "FR", "Bistro", "Paris")
+            .unwrap()
+            .with_initiation(EmvcoInitiation::Dynamic)
+            .with_amount("12.50")
+            .unwrap()
+            .with_additional_data(1, "INV-42")
+            .unwrap();
+        let payload = qr.payload().unwrap();
+        assert_eq!(
+            &payload[..payload.len() - 4],
+            "00020101021226290015com.example.pay0106123456520458125303978540512.505802FR5906Bistro6005Paris\
+             62100106INV-426304"
+        );
+        let (data, crc) = payload.split_at(payload.len() - 4);
+        assert_eq!(crc, format!("{:04X}", crc16(data.as_bytes())));
+
+        let account = EmvcoMerchantAccount::network(2, "4000123456789012").unwrap();
+        let qr = EmvcoMerchantQR::new(account, "5411", "840", "US", "Shop", "Austin").unwrap();
+        assert!(qr.payload().unwrap().starts_with("00020101021102164000123456789012"));
+    }
+
+    #[test]
+    fn test_emvco_merchant_qr_invalid() {
+        assert!(EmvcoMerchantAccount::new(2, "com.example").is_err());
+        assert!(EmvcoMerchantAccount::network(26, "123").is_err());
+        assert!(EmvcoMerchantAccount::network(2, "123")
+            .unwrap()
+            .with_field(1, "a")
+            .is_err());
+
+        let account = || EmvcoMerchantAccount::new(26, "com.example").unwrap();
+        assert!(EmvcoMerchantQR::new(account(), "58", "978", "FR", "Bistro", "Paris").is_err());
+        assert!(EmvcoMerchantQR::new(account(), "5812", "EUR", "FR", "Bistro", "Paris").is_err());
+        assert!(EmvcoMerchantQR::new(account(), "5812", "978", "FR", &"a".repeat(26), "Paris").is_err());
+
+        let qr = EmvcoMerchantQR::new(account(), "5812", "978", "FR", "Bistro", "Paris").unwrap();
+        assert!(qr.clone().with_amount("1.2.3").is_err());
+        assert!(qr.clone().with_amount("12345678901234").is_err());
+        assert!(qr.with_account(account()).is_err());
+    }
+}
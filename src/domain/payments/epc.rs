@@ -0,0 +1,209 @@
+//! EPC QR code (EPC069-12): SEPA credit transfer
+
+use super::{check_creditor_reference, format_amount, normalize_iban, PaymentQRCode};
+use crate::{
+    domain::qrcode_payload::{check_field, QRCodePayload},
+    errors::{PrinterError, Result},
+};
+
+/// Maximum payload length in bytes
+const EPC_MAX_LEN: usize = 331;
+
+/// Remittance information
+#[derive(Debug, Clone, PartialEq)]
+pub enum EpcRemittance {
+    /// ISO 11649 creditor reference (`RF...`)
+    Structured(String),
+    /// Free text (max 140 characters)
+    Unstructured(String),
+}
+
+/// EPC QR code (SEPA credit transfer, version 002, UTF-8)
+#[derive(Debug, Clone)]
+pub struct EpcPayment {
+    bic: Option<String>,
+    name: String,
+    iban: String,
+    amount: Option<u64>,
+    purpose: Option<String>,
+    remittance: Option<EpcRemittance>,
+    information: Option<String>,
+}
+
+impl EpcPayment {
+    /// Create a new `EpcPayment` (beneficiary name: max 70 characters)
+    pub fn new(name: &str, iban: &str) -> Result<Self> {
+        check_field("beneficiary name", name, 70)?;
+        if name.is_empty() {
+            return Err(PrinterError::Input("beneficiary name is required".to_string()));
+        }
+
+        Ok(Self {
+            bic: None,
+            name: name.to_string(),
+            iban: normalize_iban(iban)?,
+            amount: None,
+            purpose: None,
+            remittance: None,
+            information: None,
+        })
+    }
+
+    /// Set the BIC of the beneficiary bank (8 or 11 characters)
+    pub fn with_bic(mut self, bic: &str) -> Result<Self> {
+        let bic = bic.to_ascii_uppercase();
+        let is_valid = matches!(bic.len(), 8 | 11)
+            && bic.is_ascii()
+            && bic[..6].chars().all(|c| c.is_ascii_uppercase())
+            && bic[6..].chars().all(|c| c.is_ascii_alphanumeric());
+        if !is_valid {
+            return Err(PrinterError::Input(format!("invalid BIC: {bic}")));
+        }
+        self.bic = Some(bic);
+        Ok(self)
+    }
+
+    /// Set the amount in euro cents (0.01 - 999999999.99)
+    pub fn with_amount(mut self, cents: u64) -> Result<Self> {
+        format_amount(cents)?;
+        self.amount = Some(cents);
+        Ok(self)
+    }
+
+    /// Set the purpose code (4 upper case letters)
+    pub fn with_purpose(mut self, purpose: &str) -> Result<Self> {
+        if purpose.len() != 4 || !purpose.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(PrinterError::Input(format!("invalid purpose code: {purpose}")));
+        }
+        self.purpose = Some(purpose.to_string());
+        Ok(self)
+    }
+
+    /// Set the remittance information
+    pub fn with_remittance(mut self, remittance: EpcRemittance) -> Result<Self> {
+        match &remittance {
+            EpcRemittance::Structured(reference) => check_creditor_reference(reference)?,
+            EpcRemittance::Unstructured(text) => check_field("remittance information", text, 140)?,
+        }
+        self.remittance = Some(remittance);
+        Ok(self)
+    }
+
+    /// Set the beneficiary to originator information (max 70 characters)
+    pub fn with_information(mut self, information: &str) -> Result<Self> {
+        check_field("beneficiary to originator information", information, 70)?;
+        self.information = Some(information.to_string());
+        Ok(self)
+    }
+
+    /// Get beneficiary name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get IBAN
+    pub fn iban(&self) -> &str {
+        &self.iban
+    }
+
+    /// Get amount in euro cents
+    pub fn amount(&self) -> Option<u64> {
+        self.amount
+    }
+}
+
+impl QRCodePayload for EpcPayment {
+    fn payload(&self) -> Result<String> {
+        let (structured, unstructured) = match &self.remittance {
+            Some(EpcRemittance::Structured(reference)) => (reference.as_str(), ""),
+            Some(EpcRemittance::Unstructured(text)) => ("", text.as_str()),
+            None => ("", ""),
+        };
+        let amount = match self.amount {
+            Some(cents) => format!("EUR{}", format_amount(cents)?),
+            None => String::new(),
+        };
+
+        let mut lines = vec![
+            "BCD",
+            "002",
+            "1",
+            "SCT",
+            self.bic.as_deref().unwrap_or_default(),
+            &self.name,
+            &self.iban,
+            &amount,
+            self.purpose.as_deref().unwrap_or_default(),
+            structured,
+            unstructured,
+            self.information.as_deref().unwrap_or_default(),
+        ];
+        // Trailing empty fields are omitted
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        let payload = lines.join("\n");
+        if payload.len() > EPC_MAX_LEN {
+            return Err(PrinterError::Input(format!(
+                "EPC payload too long: {} bytes (max {EPC_MAX_LEN})",
+                payload.len()
+            )));
+        }
+        Ok(payload)
+    }
+}
+
+impl PaymentQRCode for EpcPayment {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epc_payment() {
+        let payment = EpcPayment::new("Red Cross of Belgium", "BE72 0000 0000 1616")
+            .unwrap()
+            .with_bic("BPOTBEB1")
+            .unwrap()
+            .with_amount(100)
+            .unwrap()
+            .with_remittance(EpcRemittance::Unstructured("Urgency fund".to_string()))
+            .unwrap();
+        assert_eq!(
+            payment.payload().unwrap(),
+            "BCD\n002\n1\nSCT\nBPOTBEB1\nRed Cross of Belgium\nBE72000000001616\nEUR1.00\n\n\nUrgency fund"
+        );
+
+        let payment = EpcPayment::new("Café", "DE89370400440532013000")
+            .unwrap()
+            .with_remittance(EpcRemittance::Structured("RF18539007547034".to_string()))
+            .unwrap();
+        assert_eq!(
+            payment.payload().unwrap(),
+            "BCD\n002\n1\nSCT\n\nCafé\nDE89370400440532013000\n\n\nRF18539007547034"
+        );
+        assert_eq!(payment.qrcode(4).unwrap().data, payment.payload().unwrap().into_bytes());
+    }
+
+    #[test]
+    fn test_epc_payment_invalid() {
+        assert!(EpcPayment::new("", "DE89370400440532013000").is_err());
+        assert!(EpcPayment::new(&"a".repeat(71), "DE89370400440532013000").is_err());
+        assert!(EpcPayment::new("Name", "DE00370400440532013000").is_err());
+
+        let payment = EpcPayment::new("Name", "DE89370400440532013000").unwrap();
+        assert!(payment.clone().with_bic("BPOT").is_err());
+        assert!(payment.clone().with_bic("ABCDEéF").is_err());
+        assert!(payment.clone().with_bic("BPOTBEé").is_err());
+        assert!(payment.clone().with_amount(0).is_err());
+        assert!(payment.clone().with_purpose("gdds").is_err());
+        assert!(payment
+            .clone()
+            .with_remittance(EpcRemittance::Structured("RF00539007547034".to_string()))
+            .is_err());
+        assert!(payment
+            .with_remittance(EpcRemittance::Unstructured("a".repeat(141)))
+            .is_err());
+    }
+}
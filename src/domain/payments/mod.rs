@@ -0,0 +1,131 @@
+//! Payment QR code payloads
+//!
+//! EPC (SEPA credit transfer), Swiss QR-bill and EMVCo merchant-presented payloads, printed with
+//! [`Printer::payment_qrcode`] in a Model 2 QR code of the given module size.
+//!
+//! [`Printer::payment_qrcode`]: crate::printer::Printer::payment_qrcode
+
+#![cfg(feature = "codes_2d")]
+
+mod emvco;
+mod epc;
+mod swiss_qr_bill;
+
+pub use emvco::*;
+pub use epc::*;
+pub use swiss_qr_bill::*;
+
+use super::{
+    codes::{QRCode, QRCodeModel, QRCodeOption},
+    qrcode_payload::QRCodePayload,
+};
+use crate::errors::{PrinterError, Result};
+
+/// Payment QR code
+pub trait PaymentQRCode: QRCodePayload {
+    /// Is the Swiss cross printed in the center of the QR code (only when printed as image)?
+    fn swiss_cross(&self) -> bool {
+        false
+    }
+
+    /// Get the QR code (Model 2), each module being `size` dots wide
+    fn qrcode(&self, size: u8) -> Result<QRCode> {
+        let option = QRCodeOption::new(QRCodeModel::Model2, size, self.correction_level());
+        QRCode::new(&self.payload()?, Some(option))
+    }
+}
+
+/// Compute the ISO 7064 MOD 97-10 remainder of an alphanumeric string (letters are 10 to 35)
+fn mod97(value: &str) -> Option<u32> {
+    value.chars().try_fold(0, |remainder, c| {
+        let digit = c.to_digit(36)?;
+        Some(match digit {
+            0..=9 => (remainder * 10 + digit) % 97,
+            _ => (remainder * 100 + digit) % 97,
+        })
+    })
+}
+
+/// Normalize an IBAN (spaces removed, upper case) and check its length, country code and check digits
+pub(crate) fn normalize_iban(iban: &str) -> Result<String> {
+    let iban: String = iban
+        .chars()
+        .filter(|c| *c != ' ')
+        .collect::<String>()
+        .to_ascii_uppercase();
+    let is_valid = (15..=34).contains(&iban.len())
+        && iban.chars().all(|c| c.is_ascii_alphanumeric())
+        && iban[..2].chars().all(|c| c.is_ascii_uppercase())
+        && iban[2..4].chars().all(|c| c.is_ascii_digit())
+        && mod97(&format!("{}{}", &iban[4..], &iban[..4])) == Some(1);
+
+    match is_valid {
+        true => Ok(iban),
+        false => Err(PrinterError::Input(format!("invalid IBAN: {iban}"))),
+    }
+}
+
+/// Check an ISO 11649 creditor reference (`RF` and check digits, up to 21 alphanumeric characters)
+pub(crate) fn check_creditor_reference(reference: &str) -> Result<()> {
+    let is_valid = (5..=25).contains(&reference.len())
+        && reference.is_ascii()
+        && reference.starts_with("RF")
+        && reference[2..4].chars().all(|c| c.is_ascii_digit())
+        && reference[4..].chars().all(|c| c.is_ascii_alphanumeric())
+        && mod97(&format!("{}{}", &reference[4..], &reference[..4]).to_ascii_uppercase()) == Some(1);
+
+    match is_valid {
+        true => Ok(()),
+        false => Err(PrinterError::Input(format!("invalid creditor reference: {reference}"))),
+    }
+}
+
+/// Format an amount in cents with two decimals, checking it is in `0.01 - 999999999.99`
+pub(crate) fn format_amount(cents: u64) -> Result<String> {
+    match cents {
+        1..=99_999_999_999 => Ok(format!("{}.{:02}", cents / 100, cents % 100)),
+        _ => Err(PrinterError::Input(format!(
+            "invalid amount: {cents} cents (0.01 - 999999999.99)"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_iban() {
+        assert_eq!(
+            normalize_iban("DE89 3704 0044 0532 0130 00").unwrap(),
+            "DE89370400440532013000"
+        );
+        assert_eq!(
+            normalize_iban("ch4431999123000889012").unwrap(),
+            "CH4431999123000889012"
+        );
+        assert!(normalize_iban("DE88 3704 0044 0532 0130 00").is_err());
+        assert!(normalize_iban("DE89").is_err());
+        assert!(normalize_iban("DE89 3704 0044 0532 0130 0!").is_err());
+    }
+
+    #[test]
+    fn test_check_creditor_reference() {
+        assert!(check_creditor_reference("RF18539007547034").is_ok());
+        assert!(check_creditor_reference("RF18000000000539007547034").is_ok());
+        assert!(check_creditor_reference("RF19539007547034").is_err());
+        assert!(check_creditor_reference("RF18").is_err());
+        assert!(check_creditor_reference("XX18539007547034").is_err());
+        assert!(check_creditor_reference("RF1éx").is_err());
+        assert!(check_creditor_reference("RF18é39007547034").is_err());
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(1).unwrap(), "0.01");
+        assert_eq!(format_amount(194975).unwrap(), "1949.75");
+        assert_eq!(format_amount(99_999_999_999).unwrap(), "999999999.99");
+        assert!(format_amount(0).is_err());
+        assert!(format_amount(100_000_000_000).is_err());
+    }
+}
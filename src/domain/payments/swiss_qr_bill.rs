@@ -0,0 +1,386 @@
+//! Swiss QR-bill (Swiss Implementation Guidelines for the QR-bill, version 2.3)
+
+use super::{check_creditor_reference, format_amount, normalize_iban, PaymentQRCode};
+use crate::{
+    domain::qrcode_payload::{check_field, QRCodePayload},
+    errors::{PrinterError, Result},
+};
+
+/// Maximum payload length in characters
+const SWISS_QR_BILL_MAX_LEN: usize = 997;
+
+/// QR reference check digits (modulo 10, recursive)
+const QR_REFERENCE_TABLE: [u32; 10] = [0, 9, 4, 6, 8, 2, 7, 1, 3, 5];
+
+/// Compute the check digit of a QR reference
+fn qr_reference_check_digit(digits: &str) -> Option<u32> {
+    let carry = digits.chars().try_fold(0, |carry, c| {
+        Some(QR_REFERENCE_TABLE[((carry + c.to_digit(10)?) % 10) as usize])
+    })?;
+    Some((10 - carry) % 10)
+}
+
+/// Is the IBAN a QR-IBAN (institution identification in `30000 - 31999`)?
+fn is_qr_iban(iban: &str) -> bool {
+    iban.get(4..9)
+        .and_then(|iid| iid.parse::<u32>().ok())
+        .is_some_and(|iid| (30000..=31999).contains(&iid))
+}
+
+/// Swiss QR-bill currency
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SwissQRBillCurrency {
+    #[default]
+    Chf,
+    Eur,
+}
+
+impl SwissQRBillCurrency {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Chf => "CHF",
+            Self::Eur => "EUR",
+        }
+    }
+}
+
+/// Swiss QR-bill structured address
+#[derive(Debug, Clone)]
+pub struct SwissQRBillAddress {
+    name: String,
+    street: String,
+    building_number: String,
+    postal_code: String,
+    town: String,
+    country: String,
+}
+
+impl SwissQRBillAddress {
+    /// Create a new `SwissQRBillAddress`
+    ///
+    /// Name: max 70 characters, postal code: max 16 characters, town: max 35 characters,
+    /// country: ISO 3166-1 alpha-2 code
+    pub fn new(name: &str, postal_code: &str, town: &str, country: &str) -> Result<Self> {
+        check_field("name", name, 70)?;
+        check_field("postal code", postal_code, 16)?;
+        check_field("town", town, 35)?;
+        if name.is_empty() || postal_code.is_empty() || town.is_empty() {
+            return Err(PrinterError::Input(
+                "name, postal code and town of an address are required".to_string(),
+            ));
+        }
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(PrinterError::Input(format!("invalid country code: {country}")));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            street: String::new(),
+            building_number: String::new(),
+            postal_code: postal_code.to_string(),
+            town: town.to_string(),
+            country: country.to_string(),
+        })
+    }
+
+    /// Set the street (max 70 characters) and the building number (max 16 characters)
+    pub fn with_street(mut self, street: &str, building_number: &str) -> Result<Self> {
+        check_field("street", street, 70)?;
+        check_field("building number", building_number, 16)?;
+        self.street = street.to_string();
+        self.building_number = building_number.to_string();
+        Ok(self)
+    }
+
+    /// Get name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the address lines
+    fn lines(&self) -> [&str; 7] {
+        [
+            "S",
+            &self.name,
+            &self.street,
+            &self.building_number,
+            &self.postal_code,
+            &self.town,
+            &self.country,
+        ]
+    }
+}
+
+/// Swiss QR-bill payment reference
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SwissQRBillReference {
+    /// QR reference (27 digits, QR-IBAN only)
+    Qrr(String),
+    /// ISO 11649 creditor reference (`RF...`)
+    Scor(String),
+    /// Without reference
+    #[default]
+    Non,
+}
+
+impl SwissQRBillReference {
+    /// Create a QR reference from a number (max 26 digits), adding leading zeros and the check digit
+    pub fn qr_reference(number: &str) -> Result<Self> {
+        if number.is_empty() || number.len() > 26 {
+            return Err(PrinterError::Input(format!("invalid QR reference number: {number}")));
+        }
+        let digits = format!("{number:0>26}");
+        let check_digit = qr_reference_check_digit(&digits)
+            .ok_or_else(|| PrinterError::Input(format!("invalid QR reference number: {number}")))?;
+        Ok(Self::Qrr(format!("{digits}{check_digit}")))
+    }
+
+    /// Check the reference
+    fn check(&self) -> Result<()> {
+        match self {
+            Self::Qrr(reference) => {
+                let is_valid = reference.len() == 27
+                    && reference.is_ascii()
+                    && qr_reference_check_digit(&reference[..26])
+                        .is_some_and(|check_digit| reference[26..].parse() == Ok(check_digit));
+                match is_valid {
+                    true => Ok(()),
+                    false => Err(PrinterError::Input(format!("invalid QR reference: {reference}"))),
+                }
+            }
+            Self::Scor(reference) => check_creditor_reference(reference),
+            Self::Non => Ok(()),
+        }
+    }
+
+    fn lines(&self) -> [&str; 2] {
+        match self {
+            Self::Qrr(reference) => ["QRR", reference],
+            Self::Scor(reference) => ["SCOR", reference],
+            Self::Non => ["NON", ""],
+        }
+    }
+}
+
+/// Swiss QR-bill
+///
+/// The QR code is printed with the Swiss cross in its center when printed as image (`graphics` feature).
+#[derive(Debug, Clone)]
+pub struct SwissQRBill {
+    iban: String,
+    creditor: SwissQRBillAddress,
+    amount: Option<u64>,
+    currency: SwissQRBillCurrency,
+    debtor: Option<SwissQRBillAddress>,
+    reference: SwissQRBillReference,
+    message: String,
+    bill_information: String,
+}
+
+impl SwissQRBill {
+    /// Create a new `SwissQRBill` (IBAN or QR-IBAN of Switzerland or Liechtenstein)
+    pub fn new(iban: &str, creditor: SwissQRBillAddress, currency: SwissQRBillCurrency) -> Result<Self> {
+        let iban = normalize_iban(iban)?;
+        if !iban.starts_with("CH") && !iban.starts_with("LI") {
+            return Err(PrinterError::Input(format!(
+                "Swiss QR-bill IBAN must be from Switzerland or Liechtenstein: {iban}"
+            )));
+        }
+
+        Ok(Self {
+            iban,
+            creditor,
+            amount: None,
+            currency,
+            debtor: None,
+            reference: SwissQRBillReference::default(),
+            message: String::new(),
+            bill_information: String::new(),
+        })
+    }
+
+    /// Set the amount in cents (0.01 - 999999999.99)
+    pub fn with_amount(mut self, cents: u64) -> Result<Self> {
+        format_amount(cents)?;
+        self.amount = Some(cents);
+        Ok(self)
+    }
+
+    /// Set the debtor
+    pub fn with_debtor(mut self, debtor: SwissQRBillAddress) -> Self {
+        self.debtor = Some(debtor);
+        self
+    }
+
+    /// Set the payment reference (a QR reference requires a QR-IBAN, which requires a QR reference)
+    pub fn with_reference(mut self, reference: SwissQRBillReference) -> Result<Self> {
+        reference.check()?;
+        self.reference = reference;
+        Ok(self)
+    }
+
+    /// Set the unstructured message (message and bill information: max 140 characters)
+    pub fn with_message(mut self, message: &str) -> Result<Self> {
+        check_field("message", message, 140 - self.bill_information.chars().count())?;
+        self.message = message.to_string();
+        Ok(self)
+    }
+
+    /// Set the bill information (message and bill information: max 140 characters)
+    pub fn with_bill_information(mut self, bill_information: &str) -> Result<Self> {
+        check_field("bill information", bill_information, 140 - self.message.chars().count())?;
+        self.bill_information = bill_information.to_string();
+        Ok(self)
+    }
+
+    /// Get IBAN
+    pub fn iban(&self) -> &str {
+        &self.iban
+    }
+
+    /// Get amount in cents
+    pub fn amount(&self) -> Option<u64> {
+        self.amount
+    }
+
+    /// Get currency
+    pub fn currency(&self) -> SwissQRBillCurrency {
+        self.currency
+    }
+
+    /// Get payment reference
+    pub fn reference(&self) -> &SwissQRBillReference {
+        &self.reference
+    }
+}
+
+impl QRCodePayload for SwissQRBill {
+    fn payload(&self) -> Result<String> {
+        let is_qrr = matches!(self.reference, SwissQRBillReference::Qrr(_));
+        if is_qr_iban(&self.iban) != is_qrr {
+            return Err(PrinterError::Input(
+                "Swiss QR-bill QR-IBAN must be used with a QR reference (and only with a QR-IBAN)".to_string(),
+            ));
+        }
+
+        let amount = match self.amount {
+            Some(cents) => format_amount(cents)?,
+            None => String::new(),
+        };
+        let debtor = self.debtor.as_ref().map(SwissQRBillAddress::lines).unwrap_or_default();
+
+        let mut lines = vec!["SPC", "0200", "1", &self.iban];
+        lines.extend(self.creditor.lines());
+        lines.extend([""; 7]); // Ultimate creditor (reserved)
+        lines.extend([amount.as_str(), self.currency.code()]);
+        lines.extend(debtor);
+        lines.extend(self.reference.lines());
+        lines.extend([self.message.as_str(), "EPD"]);
+        if !self.bill_information.is_empty() {
+            lines.push(&self.bill_information);
+        }
+
+        let payload = lines.join("\n");
+        let len = payload.chars().count();
+        if len > SWISS_QR_BILL_MAX_LEN {
+            return Err(PrinterError::Input(format!(
+                "Swiss QR-bill payload too long: {len} characters (max {SWISS_QR_BILL_MAX_LEN})"
+            )));
+        }
+        Ok(payload)
+    }
+}
+
+impl PaymentQRCode for SwissQRBill {
+    fn swiss_cross(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn creditor() -> SwissQRBillAddress {
+        SwissQRBillAddress::new("Robert Schneider AG", "2501", "Biel", "CH")
+            .unwrap()
+            .with_street("Rue du Lac", "1268")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_qr_reference() {
+        assert_eq!(
+            SwissQRBillReference::qr_reference("21000000000313947143000901").unwrap(),
+            SwissQRBillReference::Qrr("210000000003139471430009017".to_string())
+        );
+        assert_eq!(
+            SwissQRBillReference::qr_reference("1").unwrap(),
+            SwissQRBillReference::Qrr("000000000000000000000000011".to_string())
+        );
+        assert!(SwissQRBillReference::qr_reference("12a").is_err());
+        assert!(SwissQRBillReference::Qrr("210000000003139471430009018".to_string())
+            .check()
+            .is_err());
+        assert!(SwissQRBillReference::Qrr("2100000000031394714300090é".to_string())
+            .check()
+            .is_err());
+        assert!(SwissQRBillReference::qr_reference("2100000000031394714300é").is_err());
+    }
+
+    #[test]
+    fn test_swiss_qr_bill() {
+        let debtor = SwissQRBillAddress::new("Pia-Maria Rutschmann-Schnyder", "9400", "Rorschach", "CH")
+            .unwrap()
+            .with_street("Grosse Marktgasse", "28")
+            .unwrap();
+        let bill = SwissQRBill::new("CH44 3199 9123 0008 8901 2", creditor(), SwissQRBillCurrency::Chf)
+            .unwrap()
+            .with_amount(194975)
+            .unwrap()
+            .with_debtor(debtor)
+            .with_reference(SwissQRBillReference::Qrr("210000000003139471430009017".to_string()))
+            .unwrap()
+            .with_message("Order of 15 June 2020")
+            .unwrap()
+            .with_bill_information(
+                "//S1/10/10201409/11/200701/20/140.000-53/30/102673831/31/200615/32/7.7/33/7.7:139.40/40/0:30",
+            )
+            .unwrap();
+        assert_eq!(
+            bill.payload().unwrap(),
+            "SPC\n0200\n1\nCH4431999123000889012\nS\nRobert Schneider AG\nRue du Lac\n1268\n2501\nBiel\nCH\n\n\n\n\n\n\n\n\
+             1949.75\nCHF\nS\nPia-Maria Rutschmann-Schnyder\nGrosse Marktgasse\n28\n9400\nRorschach\nCH\n\
+             QRR\n210000000003139471430009017\nOrder of 15 June 2020\nEPD\n\
+             //S1/10/10201409/11/200701/20/140.000-53/30/102673831/31/200615/32/7.7/33/7.7:139.40/40/0:30"
+        );
+        assert!(bill.swiss_cross());
+
+        let bill = SwissQRBill::new("CH5800791123000889012", creditor(), SwissQRBillCurrency::Eur).unwrap();
+        assert_eq!(
+            bill.payload().unwrap(),
+            "SPC\n0200\n1\nCH5800791123000889012\nS\nRobert Schneider AG\nRue du Lac\n1268\n2501\nBiel\nCH\n\n\n\n\n\n\n\n\
+             \nEUR\n\n\n\n\n\n\n\nNON\n\n\nEPD"
+        );
+    }
+
+    #[test]
+    fn test_swiss_qr_bill_invalid() {
+        assert!(SwissQRBill::new("DE89370400440532013000", creditor(), SwissQRBillCurrency::Chf).is_err());
+        assert!(SwissQRBillAddress::new("Name", "2501", "Biel", "Switzerland").is_err());
+
+        // QR-IBAN without QR reference
+        let bill = SwissQRBill::new("CH4431999123000889012", creditor(), SwissQRBillCurrency::Chf).unwrap();
+        assert!(bill.payload().is_err());
+
+        // QR reference without QR-IBAN
+        let bill = SwissQRBill::new("CH5800791123000889012", creditor(), SwissQRBillCurrency::Chf)
+            .unwrap()
+            .with_reference(SwissQRBillReference::qr_reference("1").unwrap())
+            .unwrap();
+        assert!(bill.payload().is_err());
+
+        let bill = bill.with_message(&"a".repeat(100)).unwrap();
+        assert!(bill.with_bill_information(&"a".repeat(41)).is_err());
+    }
+}
@@ -1,8 +1,13 @@
 //! Protocol used to communicate with the printer
 
+#[cfg(all(feature = "graphics", feature = "barcodes"))]
+use super::barcode_image::BarcodeImageOption;
 #[cfg(feature = "graphics")]
-use super::bit_image::*;
-use super::{character::*, codes::*, common::get_parameters_number_2, constants::*, types::*, RealTimeStatusRequest};
+use super::{bit_image::*, text_image::TextImageFont};
+use super::{
+    character::*, codes::*, common::get_parameters_number_2, constants::*, thai::thai_normalize, types::*,
+    RealTimeStatusRequest,
+};
 use crate::{
     domain::page_codes::PageCodeTable,
     errors::{PrinterError, Result},
@@ -176,12 +181,19 @@ impl Protocol {
     }
 
     /// Print text
+    ///
+    /// With a Thai page code, the combining marks are reordered as expected by the printer firmware.
     pub(crate) fn text(&self, text: &str, page_code: Option<PageCode>) -> Result<Command> {
         match page_code {
             Some(page_code) => {
                 let table: PageCodeTable = page_code.try_into()?;
                 let table = table.get_table();
 
+                let text = match page_code.is_thai() {
+                    true => thai_normalize(text),
+                    false => text.to_string(),
+                };
+
                 let mut cmd = Vec::new();
                 let mut current = String::new();
 
@@ -266,12 +278,33 @@ impl Protocol {
 
     #[cfg(feature = "barcodes")]
     /// Print barcode
-    fn barcode_print(&self, system: BarcodeSystem, data: &str) -> Command {
+    fn barcode_print(&self, system: BarcodeSystem, data: &str) -> Result<Command> {
         let mut cmd = GS_BARCODE_PRINT.to_vec();
         cmd.push(system.into());
-        cmd.append(&mut data.as_bytes().to_vec());
-        cmd.push(NUL);
-        cmd
+
+        // Function B: data length instead of NUL terminator
+        let function_b_data = match system {
+            BarcodeSystem::CODE93 => Some(data.as_bytes().to_vec()),
+            BarcodeSystem::CODE128 => Some(code128_printer_data(&code128_encode(data)?)),
+            BarcodeSystem::GS1128 => Some(gs1_encode(&gs1_parse(data)?, "{1").into_bytes()),
+            _ => None,
+        };
+
+        match function_b_data {
+            Some(mut data) => {
+                cmd.push(
+                    u8::try_from(data.len())
+                        .map_err(|_| PrinterError::Input(format!("{system} data too long: {} bytes", data.len())))?,
+                );
+                cmd.append(&mut data);
+            }
+            None => {
+                cmd.append(&mut data.as_bytes().to_vec());
+                cmd.push(NUL);
+            }
+        }
+
+        Ok(cmd)
     }
 
     #[cfg(feature = "barcodes")]
@@ -282,7 +315,28 @@ impl Protocol {
             self.barcode_height(option.height().into())?,
             self.barcode_font(option.font()),
             self.barcode_position(option.position()),
-            self.barcode_print(system, data),
+            self.barcode_print(system, data)?,
+        ])
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Configure and print linear GS1 DataBar
+    pub(crate) fn gs1_databar(&self, data: &str, option: GS1DataBarOption) -> Result<Vec<Command>> {
+        let code_type = option.code_type();
+        let barcode_option = option.barcode_option();
+        let data = GS1DataBar::printer_data(data, code_type)?;
+
+        let mut cmd = GS_BARCODE_PRINT.to_vec();
+        cmd.push(code_type.into());
+        cmd.push(u8::try_from(data.len())?);
+        cmd.append(&mut data.into_bytes());
+
+        Ok(vec![
+            self.barcode_width(barcode_option.width().into())?,
+            self.barcode_height(barcode_option.height().into())?,
+            self.barcode_font(barcode_option.font()),
+            self.barcode_position(barcode_option.position()),
+            cmd,
         ])
     }
 
@@ -314,11 +368,11 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// QR code data
-    fn qrcode_data(&self, data: &str) -> Result<Command> {
+    fn qrcode_data(&self, data: &[u8]) -> Result<Command> {
         let mut cmd = GS_2D.to_vec();
         let (pl, ph) = get_parameters_number_2(data, 3)?;
         cmd.append(&mut vec![pl, ph, 49, 80, 48]);
-        cmd.append(&mut data.as_bytes().to_vec());
+        cmd.append(&mut data.to_vec());
         Ok(cmd)
     }
 
@@ -330,7 +384,7 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// QR code print
-    pub(crate) fn qrcode(&self, data: &str, option: QRCodeOption) -> Result<Vec<Command>> {
+    pub(crate) fn qrcode(&self, data: &[u8], option: QRCodeOption) -> Result<Vec<Command>> {
         Ok(vec![
             self.qrcode_model(option.model()),
             self.qrcode_size(option.size()),
@@ -349,19 +403,20 @@ impl Protocol {
     }
 
     #[cfg(feature = "codes_2d")]
-    /// 2D GS1 DataBar expanded max width
-    // TODO: To implement
-    fn gs1_databar_2d_expanded_width(&self, _max: u8) -> Command {
+    /// 2D GS1 DataBar expanded max width (in dots, 0 for no restriction)
+    fn gs1_databar_2d_expanded_width(&self, max: u16) -> Command {
         let mut cmd = GS_2D_GS1_DATABAR_WIDTH_EXTENDED.to_vec();
-        cmd.append(&mut vec![0, 0]);
+        cmd.extend(max.to_le_bytes());
         cmd
     }
 
     #[cfg(feature = "codes_2d")]
     /// 2D GS1 DataBar data
     fn gs1_databar_2d_data(&self, data: &str, code_type: GS1DataBar2DType) -> Result<Command> {
+        let data = GS1DataBar2D::printer_data(data, code_type)?;
+        let data = data.as_str();
         let mut cmd = GS_2D.to_vec();
-        let (pl, ph) = get_parameters_number_2(data, 4)?;
+        let (pl, ph) = get_parameters_number_2(data.as_bytes(), 4)?;
         cmd.push(pl);
         cmd.push(ph);
         cmd.append(&mut vec![51, 80, 48]);
@@ -382,7 +437,7 @@ impl Protocol {
     pub(crate) fn gs1_databar_2d(&self, data: &str, option: GS1DataBar2DOption) -> Result<Vec<Command>> {
         Ok(vec![
             self.gs1_databar_2d_width(option.width()),
-            self.gs1_databar_2d_expanded_width(0),
+            self.gs1_databar_2d_expanded_width(option.expanded_max_width().dots(option.width())?),
             self.gs1_databar_2d_data(data, option.code_type())?,
             self.gs1_databar_2d_print(),
         ])
@@ -440,13 +495,13 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// PDF417 data
-    fn pdf417_data(&self, data: &str) -> Result<Command> {
+    fn pdf417_data(&self, data: &[u8]) -> Result<Command> {
         let mut cmd = GS_2D.to_vec();
         let (pl, ph) = get_parameters_number_2(data, 3)?;
         cmd.push(pl);
         cmd.push(ph);
         cmd.append(&mut vec![48, 80, 48]);
-        cmd.append(&mut data.as_bytes().to_vec());
+        cmd.append(&mut data.to_vec());
         Ok(cmd)
     }
 
@@ -458,7 +513,7 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// PDF417
-    pub(crate) fn pdf417(&self, data: &str, option: Pdf417Option) -> Result<Vec<Command>> {
+    pub(crate) fn pdf417(&self, data: &[u8], option: Pdf417Option) -> Result<Vec<Command>> {
         Ok(vec![
             self.pdf417_columns(&option),
             self.pdf417_rows(&option),
@@ -481,13 +536,13 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// MaxiCode data
-    fn maxi_code_data(&self, data: &str) -> Result<Command> {
+    fn maxi_code_data(&self, data: &[u8]) -> Result<Command> {
         let mut cmd = GS_2D.to_vec();
         let (pl, ph) = get_parameters_number_2(data, 3)?;
         cmd.push(pl);
         cmd.push(ph);
         cmd.append(&mut vec![50, 80, 48]);
-        cmd.append(&mut data.as_bytes().to_vec());
+        cmd.append(&mut data.to_vec());
         Ok(cmd)
     }
 
@@ -499,12 +554,10 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// MaxiCode
-    pub(crate) fn maxi_code(&self, data: &str, mode: MaxiCodeMode) -> Result<Vec<Command>> {
-        let code = MaxiCode::new(data, mode);
-
+    pub(crate) fn maxi_code(&self, data: &[u8], mode: MaxiCodeMode) -> Result<Vec<Command>> {
         Ok(vec![
-            self.maxi_code_mode(code.mode),
-            self.maxi_code_data(&code.data)?,
+            self.maxi_code_mode(mode),
+            self.maxi_code_data(data)?,
             self.maxi_code_print(),
         ])
     }
@@ -530,13 +583,13 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// DataMatrix data
-    fn data_matrix_data(&self, data: &str) -> Result<Command> {
+    fn data_matrix_data(&self, data: &[u8]) -> Result<Command> {
         let mut cmd = GS_2D.to_vec();
         let (pl, ph) = get_parameters_number_2(data, 3)?;
         cmd.push(pl);
         cmd.push(ph);
         cmd.append(&mut vec![54, 80, 48]);
-        cmd.append(&mut data.as_bytes().to_vec());
+        cmd.append(&mut data.to_vec());
         Ok(cmd)
     }
 
@@ -548,7 +601,7 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// DataMatrix
-    pub(crate) fn data_matrix(&self, data: &str, option: DataMatrixOption) -> Result<Vec<Command>> {
+    pub(crate) fn data_matrix(&self, data: &[u8], option: DataMatrixOption) -> Result<Vec<Command>> {
         Ok(vec![
             self.data_matrix_type(option.code_type())?,
             self.data_matrix_size(option.size()),
@@ -585,13 +638,13 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// Aztec code data
-    fn aztec_data(&self, data: &str) -> Result<Command> {
+    fn aztec_data(&self, data: &[u8]) -> Result<Command> {
         let mut cmd = GS_2D.to_vec();
         let (pl, ph) = get_parameters_number_2(data, 3)?;
         cmd.push(pl);
         cmd.push(ph);
         cmd.append(&mut vec![53, 80, 48]);
-        cmd.append(&mut data.as_bytes().to_vec());
+        cmd.append(&mut data.to_vec());
         Ok(cmd)
     }
 
@@ -603,17 +656,72 @@ impl Protocol {
 
     #[cfg(feature = "codes_2d")]
     /// Aztec code
-    pub(crate) fn aztec(&self, data: &str, option: AztecOption) -> Result<Vec<Command>> {
-        let code = Aztec::new(data, option);
+    pub(crate) fn aztec(&self, data: &[u8], option: AztecOption) -> Result<Vec<Command>> {
         Ok(vec![
-            self.aztec_mode(code.option.mode())?,
-            self.aztec_size(code.option.size()),
-            self.aztec_correction_level(code.option.correction_level()),
+            self.aztec_mode(option.mode())?,
+            self.aztec_size(option.size()),
+            self.aztec_correction_level(option.correction_level()),
             self.aztec_data(data)?,
             self.aztec_print(),
         ])
     }
 
+    #[cfg(feature = "codes_2d")]
+    /// Composite module width
+    fn composite_width(&self, width: u8) -> Command {
+        let mut cmd = GS_2D_COMPOSITE_WIDTH.to_vec();
+        cmd.push(width);
+        cmd
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Composite GS1 DataBar Expanded Stacked max width (in dots, 0 for no restriction)
+    fn composite_expanded_width(&self, max: u16) -> Command {
+        let mut cmd = GS_2D_COMPOSITE_WIDTH_EXTENDED.to_vec();
+        cmd.extend(max.to_le_bytes());
+        cmd
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Composite HRI font
+    fn composite_font(&self, font: CompositeFont) -> Command {
+        let mut cmd = GS_2D_COMPOSITE_FONT.to_vec();
+        cmd.push(font.into());
+        cmd
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Composite element data (`a` = 48 for the linear element, 49 for the 2D component)
+    fn composite_data(&self, a: u8, n: u8, data: &str) -> Result<Command> {
+        let mut cmd = GS_2D.to_vec();
+        let (pl, ph) = get_parameters_number_2(data.as_bytes(), 5)?;
+        cmd.append(&mut vec![pl, ph, 52, 80, 48, a, n]);
+        cmd.append(&mut data.as_bytes().to_vec());
+        Ok(cmd)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Composite print
+    fn composite_print(&self) -> Command {
+        GS_2D_COMPOSITE_PRINT.to_vec()
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Composite symbology (the 2D component is selected automatically)
+    pub(crate) fn composite(&self, code: &Composite) -> Result<Vec<Command>> {
+        let option = &code.option;
+        let (linear_type, linear_data) = option.linear_type().printer_data(&code.linear_data)?;
+
+        Ok(vec![
+            self.composite_width(option.width()),
+            self.composite_expanded_width(option.expanded_max_width()),
+            self.composite_font(option.font()),
+            self.composite_data(48, linear_type, &linear_data)?,
+            self.composite_data(49, 65, &code.composite_printer_data()?)?,
+            self.composite_print(),
+        ])
+    }
+
     #[cfg(feature = "graphics")]
     /// Print bit image
     pub(crate) fn bit_image(&self, path: &str, option: BitImageOption) -> Result<Command> {
@@ -628,6 +736,96 @@ impl Protocol {
         self.build_bit_image(bit_image)
     }
 
+    #[cfg(feature = "graphics")]
+    /// Print text as bit image
+    pub(crate) fn text_image(
+        &self,
+        text: &str,
+        font: &TextImageFont,
+        size: f32,
+        justify: JustifyMode,
+        width: u32,
+    ) -> Result<Command> {
+        let bit_image = BitImage::from_text(text, font, size, justify, width)?;
+        self.build_bit_image(bit_image)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    /// Print barcode as bit image, at most `max_width` dots wide
+    pub(crate) fn barcode_image(
+        &self,
+        barcode: &Barcode,
+        option: &BarcodeImageOption,
+        max_width: u32,
+    ) -> Result<Command> {
+        let bit_image = BitImage::from_barcode(barcode, option)?;
+        if bit_image.image().width() > max_width {
+            return Err(PrinterError::Input(format!(
+                "{} barcode image too wide: {} dots (max: {max_width})",
+                barcode.system,
+                bit_image.image().width()
+            )));
+        }
+        self.build_bit_image(bit_image)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Print QR code as bit image, at most `max_width` dots wide
+    pub(crate) fn qrcode_image(&self, code: &QRCode, max_width: u32) -> Result<Command> {
+        self.code_2d_image("QR code", BitImage::from_qrcode(code)?, max_width)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Print QR code as bit image with the Swiss cross (Swiss QR-bill), at most `max_width` dots wide
+    pub(crate) fn swiss_qrcode_image(&self, code: &QRCode, max_width: u32) -> Result<Command> {
+        self.code_2d_image(
+            "Swiss QR code",
+            BitImage::from_qrcode_with_swiss_cross(code)?,
+            max_width,
+        )
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Print DataMatrix as bit image, at most `max_width` dots wide
+    pub(crate) fn data_matrix_image(&self, code: &DataMatrix, max_width: u32) -> Result<Command> {
+        self.code_2d_image("DataMatrix", BitImage::from_data_matrix(code)?, max_width)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Print Aztec code as bit image, at most `max_width` dots wide
+    pub(crate) fn aztec_image(&self, code: &Aztec, max_width: u32) -> Result<Command> {
+        self.code_2d_image("Aztec code", BitImage::from_aztec(code)?, max_width)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Print PDF417 as bit image, at most `max_width` dots wide
+    pub(crate) fn pdf417_image(&self, code: &Pdf417, max_width: u32) -> Result<Command> {
+        self.code_2d_image("PDF417", BitImage::from_pdf417(code)?, max_width)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "barcodes", feature = "codes_2d"))]
+    /// Print GS1 Composite code as bit image, at most `max_width` dots wide
+    pub(crate) fn composite_image(&self, code: &Composite, max_width: u32) -> Result<Command> {
+        self.code_2d_image("Composite", BitImage::from_composite(code)?, max_width)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Print a Macro PDF417 segment as bit image, at most `max_width` dots wide
+    pub(crate) fn macro_pdf417_image(&self, code: &MacroPdf417, index: usize, max_width: u32) -> Result<Command> {
+        self.code_2d_image("Macro PDF417", BitImage::from_macro_pdf417(code, index)?, max_width)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    fn code_2d_image(&self, name: &str, bit_image: BitImage, max_width: u32) -> Result<Command> {
+        if bit_image.image().width() > max_width {
+            return Err(PrinterError::Input(format!(
+                "{name} image too wide: {} dots (max: {max_width})",
+                bit_image.image().width()
+            )));
+        }
+        self.build_bit_image(bit_image)
+    }
+
     #[cfg(feature = "graphics")]
     fn build_bit_image(&self, bit_image: BitImage) -> Result<Command> {
         let mut cmd = GS_IMAGE_BITMAP_PREFIX.to_vec();
@@ -707,6 +905,7 @@ impl Protocol {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::CustomPageCode;
 
     #[test]
     fn test_init() {
@@ -988,6 +1187,45 @@ mod tests {
         assert!(protocol.text("My text", Some(PageCode::Hiragana)).is_err());
     }
 
+    #[test]
+    fn test_text_with_thai_page_code() {
+        let protocol = Protocol::new(Encoder::default());
+        assert_eq!(protocol.page_code(PageCode::Thai18), vec![27, 116, 26]);
+        assert_eq!(
+            protocol.text("My text ก ฿ ๙", Some(PageCode::Thai18)).unwrap(),
+            &[77, 121, 32, 116, 101, 120, 116, 32, 0xA1, 32, 0xDF, 32, 0xF9]
+        );
+
+        // Combining marks are reordered: below vowel, above vowel, tone mark
+        assert_eq!(
+            protocol.text("ก\u{0E49}\u{0E38}ง", Some(PageCode::Thai18)).unwrap(),
+            &[0xA1, 0xD8, 0xE9, 0xA7]
+        );
+        assert_eq!(
+            protocol.text("น้ำ", Some(PageCode::Thai18)).unwrap(),
+            &[0xB9, 0xE9, 0xD3]
+        );
+
+        // Thai page codes without table
+        assert!(protocol.text("My text", Some(PageCode::Thai42)).is_err());
+    }
+
+    #[test]
+    fn test_text_with_custom_page_code() {
+        let protocol = Protocol::new(Encoder::default());
+        assert!(protocol.text("My text", Some(PageCode::Custom(200))).is_err());
+
+        let page_code = CustomPageCode::from_text("number 200\n0x80 U+0416\n0x81 Щ")
+            .unwrap()
+            .register()
+            .unwrap();
+        assert_eq!(protocol.page_code(page_code), vec![27, 116, 200]);
+        assert_eq!(
+            protocol.text("My text Ж Щ", Some(page_code)).unwrap(),
+            &[77, 121, 32, 116, 101, 120, 116, 32, 0x80, 32, 0x81]
+        );
+    }
+
     #[test]
     fn test_motion_units() {
         let protocol = Protocol::new(Encoder::default());
@@ -1080,33 +1318,48 @@ mod tests {
     fn test_barcode_print() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::UPCA, "12587458745"),
+            protocol.barcode_print(BarcodeSystem::UPCA, "12587458745").unwrap(),
             vec![29, 107, 0, b'1', b'2', b'5', b'8', b'7', b'4', b'5', b'8', b'7', b'4', b'5', 0]
         );
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::UPCE, "02587458745"),
+            protocol.barcode_print(BarcodeSystem::UPCE, "02587458745").unwrap(),
             vec![29, 107, 1, b'0', b'2', b'5', b'8', b'7', b'4', b'5', b'8', b'7', b'4', b'5', 0]
         );
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::EAN13, "025874587456"),
+            protocol.barcode_print(BarcodeSystem::EAN13, "025874587456").unwrap(),
             vec![29, 107, 2, b'0', b'2', b'5', b'8', b'7', b'4', b'5', b'8', b'7', b'4', b'5', b'6', 0]
         );
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::EAN8, "0587456"),
+            protocol.barcode_print(BarcodeSystem::EAN8, "0587456").unwrap(),
             vec![29, 107, 3, b'0', b'5', b'8', b'7', b'4', b'5', b'6', 0]
         );
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::CODE39, "05A$"),
+            protocol.barcode_print(BarcodeSystem::CODE39, "05A$").unwrap(),
             vec![29, 107, 4, b'0', b'5', b'A', b'$', 0]
         );
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::ITF, "0585"),
+            protocol.barcode_print(BarcodeSystem::ITF, "0585").unwrap(),
             vec![29, 107, 5, b'0', b'5', b'8', b'5', 0]
         );
         assert_eq!(
-            protocol.barcode_print(BarcodeSystem::CODABAR, "A05A$C"),
+            protocol.barcode_print(BarcodeSystem::CODABAR, "A05A$C").unwrap(),
             vec![29, 107, 6, b'A', b'0', b'5', b'A', b'$', b'C', 0]
         );
+        assert_eq!(
+            protocol.barcode_print(BarcodeSystem::CODE128, "AB123456").unwrap(),
+            vec![29, 107, 73, 9, b'{', b'B', b'A', b'B', b'{', b'C', 12, 34, 56]
+        );
+        assert!(protocol.barcode_print(BarcodeSystem::CODE128, "Café").is_err());
+        assert_eq!(
+            protocol.barcode_print(BarcodeSystem::CODE93, "AB-1").unwrap(),
+            vec![29, 107, 72, 4, b'A', b'B', b'-', b'1']
+        );
+        assert_eq!(
+            protocol
+                .barcode_print(BarcodeSystem::GS1128, "(10)AB(17)250101")
+                .unwrap(),
+            vec![29, 107, 74, 14, b'1', b'0', b'A', b'B', b'{', b'1', b'1', b'7', b'2', b'5', b'0', b'1', b'0', b'1']
+        );
     }
 
     #[cfg(feature = "barcodes")]
@@ -1133,6 +1386,34 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "barcodes")]
+    #[test]
+    fn test_gs1_databar() {
+        let protocol = Protocol::new(Encoder::default());
+        let option = |code_type| GS1DataBarOption::new(code_type, BarcodeOption::default());
+
+        let commands = protocol
+            .gs1_databar("09501101530003", option(GS1DataBarType::Truncated))
+            .unwrap();
+        assert_eq!(commands.len(), 5);
+        assert_eq!(
+            commands[4],
+            vec![29, 107, 76, 13, b'0', b'9', b'5', b'0', b'1', b'1', b'0', b'1', b'5', b'3', b'0', b'0', b'0']
+        );
+
+        let commands = protocol
+            .gs1_databar("(10)A1(17)250101", option(GS1DataBarType::Expanded))
+            .unwrap();
+        assert_eq!(
+            commands[4],
+            vec![29, 107, 78, 14, b'1', b'0', b'A', b'1', b'{', b'1', b'1', b'7', b'2', b'5', b'0', b'1', b'0', b'1']
+        );
+
+        assert!(protocol
+            .gs1_databar("09501101530004", option(GS1DataBarType::Omnidirectional))
+            .is_err());
+    }
+
     #[cfg(feature = "codes_2d")]
     #[test]
     fn test_qrcode_model() {
@@ -1190,13 +1471,13 @@ mod tests {
     fn test_qrcode_data() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.qrcode_data("test data qrcode").unwrap(),
+            protocol.qrcode_data(b"test data qrcode").unwrap(),
             vec![
                 29, 40, 107, 19, 0, 49, 80, 48, 116, 101, 115, 116, 32, 100, 97, 116, 97, 32, 113, 114, 99, 111, 100,
                 101
             ]
         );
-        assert_eq!(protocol.qrcode_data("").unwrap(), vec![29, 40, 107, 3, 0, 49, 80, 48]);
+        assert_eq!(protocol.qrcode_data(b"").unwrap(), vec![29, 40, 107, 3, 0, 49, 80, 48]);
     }
 
     #[cfg(feature = "codes_2d")]
@@ -1220,7 +1501,7 @@ mod tests {
         assert_eq!(
             protocol
                 .qrcode(
-                    "test",
+                    b"test",
                     QRCodeOption::new(QRCodeModel::Model1, 4, QRCodeCorrectionLevel::L)
                 )
                 .unwrap(),
@@ -1252,7 +1533,11 @@ mod tests {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
             protocol.gs1_databar_2d_expanded_width(0),
-            vec![29, 40, 107, 3, 0, 51, 71, 0, 0]
+            vec![29, 40, 107, 4, 0, 51, 71, 0, 0]
+        );
+        assert_eq!(
+            protocol.gs1_databar_2d_expanded_width(384),
+            vec![29, 40, 107, 4, 0, 51, 71, 128, 1]
         );
     }
 
@@ -1285,13 +1570,36 @@ mod tests {
                 .unwrap(),
             vec![
                 vec![29, 40, 107, 3, 0, 51, 67, 1],
-                vec![29, 40, 107, 3, 0, 51, 71, 0, 0],
+                vec![29, 40, 107, 4, 0, 51, 71, 0, 0],
                 vec![29, 40, 107, 17, 0, 51, 80, 48, 72, 56, 50, 52, 53, 55, 56, 57, 54, 53, 56, 55, 52, 53],
                 vec![29, 40, 107, 3, 0, 51, 81, 48]
             ]
         );
     }
 
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_composite() {
+        let protocol = Protocol::new(Encoder::default());
+        let code = Composite::new(
+            "9638507",
+            "(10)A1",
+            CompositeOption::new(CompositeLinearType::Ean8, 3, CompositeFont::B).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            protocol.composite(&code).unwrap(),
+            vec![
+                vec![29, 40, 107, 3, 0, 52, 67, 3],
+                vec![29, 40, 107, 4, 0, 52, 71, 0, 0],
+                vec![29, 40, 107, 3, 0, 52, 72, 2],
+                vec![29, 40, 107, 12, 0, 52, 80, 48, 48, 65, 57, 54, 51, 56, 53, 48, 55],
+                vec![29, 40, 107, 9, 0, 52, 80, 48, 49, 65, 49, 48, 65, 49],
+                vec![29, 40, 107, 3, 0, 52, 81, 48]
+            ]
+        );
+    }
+
     #[cfg(feature = "codes_2d")]
     #[test]
     fn test_pdf417_columns() {
@@ -1361,7 +1669,7 @@ mod tests {
     fn test_pdf417_data() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.pdf417_data("test").unwrap(),
+            protocol.pdf417_data(b"test").unwrap(),
             vec![29, 40, 107, 7, 0, 48, 80, 48, 116, 101, 115, 116]
         );
     }
@@ -1379,7 +1687,7 @@ mod tests {
         let protocol = Protocol::new(Encoder::default());
         let option = Pdf417Option::default();
         assert_eq!(
-            protocol.pdf417("test", option).unwrap(),
+            protocol.pdf417(b"test", option).unwrap(),
             vec![
                 vec![29, 40, 107, 3, 0, 48, 65, 0],
                 vec![29, 40, 107, 3, 0, 48, 66, 0],
@@ -1428,7 +1736,7 @@ mod tests {
     fn test_maxi_code_data() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.maxi_code_data("1245").unwrap(),
+            protocol.maxi_code_data(b"1245").unwrap(),
             vec![29, 40, 107, 7, 0, 50, 80, 48, 49, 50, 52, 53]
         );
     }
@@ -1445,7 +1753,7 @@ mod tests {
     fn test_maxi_code() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.maxi_code("1245", MaxiCodeMode::default()).unwrap(),
+            protocol.maxi_code(b"1245", MaxiCodeMode::default()).unwrap(),
             vec![
                 vec![29, 40, 107, 3, 0, 50, 65, 50],
                 vec![29, 40, 107, 7, 0, 50, 80, 48, 49, 50, 52, 53],
@@ -1453,7 +1761,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            protocol.maxi_code("test1245", MaxiCodeMode::default()).unwrap(),
+            protocol.maxi_code(b"test1245", MaxiCodeMode::default()).unwrap(),
             vec![
                 vec![29, 40, 107, 3, 0, 50, 65, 50],
                 vec![29, 40, 107, 11, 0, 50, 80, 48, 116, 101, 115, 116, 49, 50, 52, 53],
@@ -1497,7 +1805,7 @@ mod tests {
     fn test_data_matrix_data() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.data_matrix_data("test123").unwrap(),
+            protocol.data_matrix_data(b"test123").unwrap(),
             vec![29, 40, 107, 10, 0, 54, 80, 48, 116, 101, 115, 116, 49, 50, 51]
         );
     }
@@ -1514,7 +1822,7 @@ mod tests {
     fn test_data_matrix() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.data_matrix("test123", DataMatrixOption::default()).unwrap(),
+            protocol.data_matrix(b"test123", DataMatrixOption::default()).unwrap(),
             vec![
                 vec![29, 40, 107, 5, 0, 54, 66, 0, 0, 0],
                 vec![29, 40, 107, 3, 0, 54, 67, 3],
@@ -1524,7 +1832,7 @@ mod tests {
         );
         let option = DataMatrixOption::new(DataMatrixType::Rectangle(8, 0), 16).unwrap();
         assert_eq!(
-            protocol.data_matrix("test123", option).unwrap(),
+            protocol.data_matrix(b"test123", option).unwrap(),
             vec![
                 vec![29, 40, 107, 5, 0, 54, 66, 1, 8, 0],
                 vec![29, 40, 107, 3, 0, 54, 67, 16],
@@ -1573,7 +1881,7 @@ mod tests {
     fn test_aztec_data() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.aztec_data("test123").unwrap(),
+            protocol.aztec_data(b"test123").unwrap(),
             vec![29, 40, 107, 10, 0, 53, 80, 48, 116, 101, 115, 116, 49, 50, 51]
         );
     }
@@ -1590,7 +1898,7 @@ mod tests {
     fn test_aztec() {
         let protocol = Protocol::new(Encoder::default());
         assert_eq!(
-            protocol.aztec("test123", AztecOption::default()).unwrap(),
+            protocol.aztec(b"test123", AztecOption::default()).unwrap(),
             vec![
                 vec![29, 40, 107, 4, 0, 53, 66, 0, 0],
                 vec![29, 40, 107, 3, 0, 53, 67, 3],
@@ -1639,6 +1947,62 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn test_text_image() {
+        let protocol = Protocol::new(Encoder::default());
+        let font = crate::domain::TextImageFont::new("./resources/fonts/blocks.ttf").unwrap();
+        let cmd = protocol
+            .text_image("AB CD", &font, 10.0, JustifyMode::LEFT, 16)
+            .unwrap();
+
+        assert_eq!(&cmd[..8], &[29, 118, 48, 0, 2, 0, 20, 0]);
+        assert_eq!(cmd.len(), 8 + 2 * 20);
+        // Second row: "AB" blocks
+        assert_eq!(&cmd[10..12], &[0b1111_1011, 0b1110_0000]);
+    }
+
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    #[test]
+    fn test_barcode_image() {
+        let protocol = Protocol::new(Encoder::default());
+        let barcode = Barcode::new(BarcodeSystem::EAN8, "9638507", BarcodeOption::default()).unwrap();
+        let option = BarcodeImageOption::new(1, 2).unwrap();
+        let cmd = protocol.barcode_image(&barcode, &option, 512).unwrap();
+
+        // (67 + 2 * 10) dots rounded up to 88: 11 bytes
+        assert_eq!(&cmd[..8], &[29, 118, 48, 0, 11, 0, 2, 0]);
+        assert_eq!(cmd.len(), 8 + 11 * 2);
+        // Quiet zone and start guard
+        assert_eq!(&cmd[8..10], &[0, 0b0010_1000]);
+
+        assert!(protocol.barcode_image(&barcode, &option, 80).is_err());
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    #[test]
+    fn test_code_2d_image() {
+        let protocol = Protocol::new(Encoder::default());
+        let code = DataMatrix::new("123456", DataMatrixOption::new(DataMatrixType::Square(0), 2).unwrap()).unwrap();
+        let cmd = protocol.data_matrix_image(&code, 512).unwrap();
+
+        // (10 + 2) * 2 dots rounded up to 24: 3 bytes
+        assert_eq!(&cmd[..8], &[29, 118, 48, 0, 3, 0, 24, 0]);
+        // Quiet zone and alternating top edge
+        assert_eq!(&cmd[8..11], &[0, 0, 0]);
+        assert_eq!(&cmd[14..17], &[0b0011_0011, 0b0011_0011, 0b0011_0000]);
+
+        assert!(protocol.data_matrix_image(&code, 16).is_err());
+
+        let code = QRCode::new("ABC", None).unwrap();
+        assert!(protocol.qrcode_image(&code, 512).is_err());
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::H);
+        let code = QRCode::new("ABC", Some(option)).unwrap();
+        assert!(protocol.qrcode_image(&code, 512).is_ok());
+        let code = Aztec::new("ABC", AztecOption::default());
+        assert!(protocol.aztec_image(&code, 512).is_ok());
+    }
+
     // #[cfg(feature = "graphics")]
     // #[test]
     // fn test_graphic_density() {
@@ -0,0 +1,48 @@
+//! QR code payloads
+//!
+//! Common trait of the payment and fiscal receipt QR code payloads, and the checks of their fields.
+
+#![cfg(feature = "codes_2d")]
+
+use super::codes::QRCodeCorrectionLevel;
+use crate::errors::{PrinterError, Result};
+
+/// QR code payload (payment or fiscal receipt)
+pub trait QRCodePayload {
+    /// Get the validated payload
+    fn payload(&self) -> Result<String>;
+
+    /// Get the error correction level mandated by the format
+    fn correction_level(&self) -> QRCodeCorrectionLevel {
+        QRCodeCorrectionLevel::M
+    }
+}
+
+/// Check a payload field: no control characters and at most `max_len` characters
+pub(crate) fn check_field(name: &str, value: &str, max_len: usize) -> Result<()> {
+    if value.chars().any(char::is_control) {
+        return Err(PrinterError::Input(format!(
+            "invalid {name}: control characters are not allowed"
+        )));
+    }
+    let len = value.chars().count();
+    if len > max_len {
+        return Err(PrinterError::Input(format!(
+            "{name} too long: {len} characters (max {max_len})"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_field() {
+        assert!(check_field("name", "Café", 4).is_ok());
+        assert!(check_field("name", "", 4).is_ok());
+        assert!(check_field("name", "Cafés", 4).is_err());
+        assert!(check_field("name", "a\nb", 4).is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! Text image
+//!
+//! Render text as a bit image with a TrueType font.
+//! Useful for scripts or symbols (emoji, CJK, etc.) not available in the printer page codes.
+
+#![cfg(feature = "graphics")]
+
+use super::{bit_image::*, types::JustifyMode};
+use crate::errors::{PrinterError, Result};
+use ab_glyph::{point, Font, FontArc, Glyph, PxScale, ScaleFont};
+use image::{DynamicImage, GrayImage, Luma};
+use std::fmt;
+
+/// TrueType (or OpenType) font used to render text as an image
+#[derive(Clone)]
+pub struct TextImageFont {
+    font: FontArc,
+}
+
+impl fmt::Debug for TextImageFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextImageFont")
+            .field("glyph_count", &self.font.glyph_count())
+            .finish()
+    }
+}
+
+impl TextImageFont {
+    /// Load a font from a file
+    pub fn new(path: &str) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Load a font from bytes
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let font = FontArc::try_from_vec(bytes).map_err(|err| PrinterError::Input(format!("invalid font: {err}")))?;
+        Ok(Self { font })
+    }
+
+    /// Render the text into an image `width` dots wide
+    ///
+    /// Lines are wrapped on whitespace (or anywhere if a word is too long) and justified.
+    pub(crate) fn render(&self, text: &str, size: f32, justify: JustifyMode, width: u32) -> Result<DynamicImage> {
+        if text.trim().is_empty() {
+            return Err(PrinterError::Input("text image cannot be empty".to_owned()));
+        }
+        if size <= 0.0 {
+            return Err(PrinterError::Input(format!("invalid text image font size: {size}")));
+        }
+        if width == 0 {
+            return Err(PrinterError::Input("text image width cannot be 0".to_owned()));
+        }
+
+        let font = self.font.as_scaled(PxScale::from(size));
+        let lines = self.wrap(text, size, width as f32);
+        let line_height = (font.ascent() - font.descent() + font.line_gap()).ceil();
+        let height = (line_height * lines.len() as f32).ceil() as u32;
+        let mut img = GrayImage::from_pixel(width, height, Luma([255]));
+
+        for (index, line) in lines.iter().enumerate() {
+            let line_width = self.line_width(line, size);
+            let offset = match justify {
+                JustifyMode::LEFT => 0.0,
+                JustifyMode::CENTER => ((width as f32 - line_width) / 2.0).max(0.0).floor(),
+                JustifyMode::RIGHT => (width as f32 - line_width).max(0.0).floor(),
+            };
+            let baseline = line_height * index as f32 + font.ascent();
+
+            for glyph in self.layout(line, size, offset, baseline) {
+                if let Some(outlined) = font.outline_glyph(glyph) {
+                    let bounds = outlined.px_bounds();
+                    outlined.draw(|x, y, coverage| {
+                        let x = bounds.min.x as i64 + i64::from(x);
+                        let y = bounds.min.y as i64 + i64::from(y);
+                        if x >= 0 && y >= 0 && x < i64::from(width) && y < i64::from(height) {
+                            let pixel = img.get_pixel_mut(x as u32, y as u32);
+                            let value = 255 - (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                            pixel.0[0] = pixel.0[0].min(value);
+                        }
+                    });
+                }
+            }
+        }
+
+        Ok(DynamicImage::ImageLuma8(img))
+    }
+
+    /// Position the glyphs of a line
+    fn layout(&self, line: &str, size: f32, x: f32, baseline: f32) -> Vec<Glyph> {
+        let font = self.font.as_scaled(PxScale::from(size));
+        let mut glyphs = Vec::with_capacity(line.len());
+        let mut caret = x;
+        let mut previous = None;
+
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if let Some(previous) = previous {
+                caret += font.kern(previous, id);
+            }
+            glyphs.push(id.with_scale_and_position(font.scale(), point(caret, baseline)));
+            caret += font.h_advance(id);
+            previous = Some(id);
+        }
+
+        glyphs
+    }
+
+    /// Get the width of a line in dots
+    fn line_width(&self, line: &str, size: f32) -> f32 {
+        let font = self.font.as_scaled(PxScale::from(size));
+        let mut width = 0.0;
+        let mut previous = None;
+
+        for c in line.chars() {
+            let id = font.glyph_id(c);
+            if let Some(previous) = previous {
+                width += font.kern(previous, id);
+            }
+            width += font.h_advance(id);
+            previous = Some(id);
+        }
+
+        width
+    }
+
+    /// Split the text into lines fitting in `max_width` dots
+    fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for paragraph in text.lines() {
+            let mut line = String::new();
+
+            for word in paragraph.split_whitespace() {
+                let candidate = match line.is_empty() {
+                    true => word.to_owned(),
+                    false => format!("{line} {word}"),
+                };
+
+                if self.line_width(&candidate, size) <= max_width {
+                    line = candidate;
+                    continue;
+                }
+
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+
+                // Word too long to fit on a line: break it anywhere
+                for c in word.chars() {
+                    line.push(c);
+                    if self.line_width(&line, size) > max_width && line.chars().count() > 1 {
+                        line.pop();
+                        lines.push(std::mem::take(&mut line));
+                        line.push(c);
+                    }
+                }
+            }
+
+            lines.push(line);
+        }
+
+        lines
+    }
+}
+
+impl BitImage {
+    /// Create a new image from a text rendered with a TrueType font
+    pub fn from_text(text: &str, font: &TextImageFont, size: f32, justify: JustifyMode, width: u32) -> Result<Self> {
+        if !width.is_multiple_of(8) {
+            return Err(PrinterError::Input(
+                "text image width must be a multiple of 8".to_owned(),
+            ));
+        }
+
+        let img = font.render(text, size, justify, width)?;
+        Self::from_dynamic_image(img, BitImageOption::new(None, None, BitImageSize::Normal)?, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font() -> TextImageFont {
+        TextImageFont::new("./resources/fonts/blocks.ttf").unwrap()
+    }
+
+    #[test]
+    fn test_text_image_font_invalid() {
+        assert!(TextImageFont::from_bytes(vec![0, 1, 2, 3]).is_err());
+        assert!(TextImageFont::new("./resources/fonts/unknown.ttf").is_err());
+    }
+
+    #[test]
+    fn test_text_image_wrap() {
+        let font = font();
+
+        // Glyphs are 6 dots wide at size 10, spaces 3 dots
+        assert_eq!(font.wrap("AB CD", 10.0, 64.0), vec!["AB CD"]);
+        assert_eq!(font.wrap("AB CD", 10.0, 24.0), vec!["AB", "CD"]);
+        assert_eq!(font.wrap("ABCDEF", 10.0, 24.0), vec!["ABCD", "EF"]);
+        assert_eq!(font.wrap("AB\n\nCD", 10.0, 64.0), vec!["AB", "", "CD"]);
+    }
+
+    #[test]
+    fn test_text_image_render() {
+        let font = font();
+
+        assert!(font.render("", 10.0, JustifyMode::LEFT, 64).is_err());
+        assert!(font.render("A", 0.0, JustifyMode::LEFT, 64).is_err());
+        assert!(font.render("A", 10.0, JustifyMode::LEFT, 0).is_err());
+
+        let img = font.render("A", 10.0, JustifyMode::LEFT, 64).unwrap().to_luma8();
+        assert_eq!((img.width(), img.height()), (64, 10));
+        assert_eq!(img.get_pixel(2, 0).0[0], 255);
+        assert_eq!(img.get_pixel(2, 5).0[0], 0);
+        assert_eq!(img.get_pixel(5, 5).0[0], 255);
+        assert_eq!(img.get_pixel(10, 5).0[0], 255);
+
+        let img = font.render("A", 10.0, JustifyMode::RIGHT, 64).unwrap().to_luma8();
+        assert_eq!(img.get_pixel(2, 5).0[0], 255);
+        assert_eq!(img.get_pixel(57, 5).0[0], 255);
+        assert_eq!(img.get_pixel(60, 5).0[0], 0);
+
+        let img = font.render("A", 10.0, JustifyMode::CENTER, 64).unwrap().to_luma8();
+        assert_eq!(img.get_pixel(28, 5).0[0], 255);
+        assert_eq!(img.get_pixel(31, 5).0[0], 0);
+        assert_eq!(img.get_pixel(60, 5).0[0], 255);
+    }
+
+    #[test]
+    fn test_bit_image_from_text() {
+        let font = font();
+
+        assert!(BitImage::from_text("A", &font, 10.0, JustifyMode::LEFT, 60).is_err());
+
+        let bit_image = BitImage::from_text("AB CD", &font, 10.0, JustifyMode::LEFT, 24).unwrap();
+        assert_eq!(bit_image.width_bytes().unwrap(), 3);
+        assert_eq!(bit_image.height_u8().unwrap(), vec![20, 0]);
+    }
+}
@@ -0,0 +1,123 @@
+//! Thai text
+//!
+//! Thai combining vowels and tone marks are printed above or below the base consonant.
+//! Epson Thai models compose them with the base character (three-pass printing) as long as they
+//! follow the base character in a fixed order: below vowel, above vowel, tone mark and other diacritics.
+
+/// Thai combining mark position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum ThaiMark {
+    /// Below vowels (SARA U, SARA UU, PHINTHU)
+    BelowVowel,
+    /// Above vowels (MAI HAN-AKAT, SARA I, SARA II, SARA UE, SARA UEE, MAITAIKHU)
+    AboveVowel,
+    /// Tone marks (MAI EK, MAI THO, MAI TRI, MAI CHATTAWA)
+    Tone,
+    /// Other above diacritics (THANTHAKHAT, NIKHAHIT, YAMAKKAN)
+    Diacritic,
+}
+
+impl ThaiMark {
+    /// Get the combining mark position of a character, if it is a Thai combining mark
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '\u{0E38}'..='\u{0E3A}' => Some(Self::BelowVowel),
+            '\u{0E31}' | '\u{0E34}'..='\u{0E37}' | '\u{0E47}' => Some(Self::AboveVowel),
+            '\u{0E48}'..='\u{0E4B}' => Some(Self::Tone),
+            '\u{0E4C}'..='\u{0E4E}' => Some(Self::Diacritic),
+            _ => None,
+        }
+    }
+}
+
+/// Is the character a Thai combining mark (zero width when printed)?
+pub fn is_thai_combining_mark(c: char) -> bool {
+    ThaiMark::from_char(c).is_some()
+}
+
+/// Get the number of columns used by the text once printed
+///
+/// Thai combining marks are printed above or below the previous character and do not use a column.
+///
+/// ```
+/// use escpos::utils::thai_display_width;
+///
+/// assert_eq!(thai_display_width("Total"), 5);
+/// assert_eq!(thai_display_width("ที่นี่"), 2);
+/// assert_eq!(thai_display_width("น้ำ"), 2);
+/// ```
+pub fn thai_display_width(text: &str) -> usize {
+    text.chars().filter(|c| !is_thai_combining_mark(*c)).count()
+}
+
+/// Reorder Thai combining marks in the order expected by the printer firmware
+///
+/// The combining marks following a base character are sorted by position
+/// (below vowel, above vowel, tone mark, other diacritics).
+pub(crate) fn thai_normalize(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut marks: Vec<(ThaiMark, char)> = Vec::new();
+
+    for c in text.chars() {
+        match ThaiMark::from_char(c) {
+            Some(mark) => marks.push((mark, c)),
+            None => {
+                flush_marks(&mut result, &mut marks);
+                result.push(c);
+            }
+        }
+    }
+    flush_marks(&mut result, &mut marks);
+
+    result
+}
+
+/// Append the combining marks of the current cluster in firmware order
+fn flush_marks(result: &mut String, marks: &mut Vec<(ThaiMark, char)>) {
+    // Stable sort keeps the original order of marks at the same position
+    marks.sort_by_key(|(mark, _)| *mark);
+    result.extend(marks.drain(..).map(|(_, c)| c));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_thai_combining_mark() {
+        assert!(is_thai_combining_mark('\u{0E31}'));
+        assert!(is_thai_combining_mark('\u{0E38}'));
+        assert!(is_thai_combining_mark('\u{0E48}'));
+        assert!(is_thai_combining_mark('\u{0E4C}'));
+
+        assert!(!is_thai_combining_mark('ก'));
+        assert!(!is_thai_combining_mark('ำ'));
+        assert!(!is_thai_combining_mark('a'));
+    }
+
+    #[test]
+    fn test_thai_display_width() {
+        assert_eq!(thai_display_width(""), 0);
+        assert_eq!(thai_display_width("abc"), 3);
+        assert_eq!(thai_display_width("สวัสดี"), 4);
+        assert_eq!(thai_display_width("กุ้ง 100฿"), 7);
+    }
+
+    #[test]
+    fn test_thai_normalize() {
+        assert_eq!(thai_normalize("abc"), "abc");
+        assert_eq!(thai_normalize("สวัสดี"), "สวัสดี");
+
+        // Tone mark before below vowel
+        assert_eq!(thai_normalize("ก\u{0E49}\u{0E38}ง"), "ก\u{0E38}\u{0E49}ง");
+
+        // Tone mark before above vowel
+        assert_eq!(thai_normalize("ท\u{0E48}\u{0E35}"), "ท\u{0E35}\u{0E48}");
+
+        // Marks of different clusters are not mixed
+        assert_eq!(
+            thai_normalize("ก\u{0E49}\u{0E38}ข\u{0E48}\u{0E34}"),
+            "ก\u{0E38}\u{0E49}ข\u{0E34}\u{0E48}"
+        );
+    }
+}
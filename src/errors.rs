@@ -2,7 +2,12 @@
 
 #[cfg(feature = "graphics")]
 use image::ImageError;
-use std::{borrow::Cow, cell::BorrowMutError, fmt, io, num::TryFromIntError};
+use std::{
+    borrow::Cow,
+    cell::{BorrowError, BorrowMutError},
+    fmt, io,
+    num::TryFromIntError,
+};
 
 /// Custom Result for `PrinterError`
 pub type Result<T> = std::result::Result<T, PrinterError>;
@@ -39,6 +44,12 @@ impl From<Cow<'_, str>> for PrinterError {
     }
 }
 
+impl From<BorrowError> for PrinterError {
+    fn from(err: BorrowError) -> Self {
+        PrinterError::Io(err.to_string())
+    }
+}
+
 impl From<BorrowMutError> for PrinterError {
     fn from(err: BorrowMutError) -> Self {
         PrinterError::Io(err.to_string())
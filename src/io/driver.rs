@@ -21,9 +21,14 @@ use std::{
     time::Duration,
 };
 
+mod lpd;
+mod middleware;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
+pub use lpd::*;
+pub use middleware::*;
+
 /// Default timeout in seconds for read/write operations
 const DEFAULT_TIMEOUT_SECONDS: u64 = 5;
 
@@ -37,6 +42,15 @@ pub trait Driver {
     /// Write data
     fn write(&self, data: &[u8]) -> Result<()>;
 
+    /// Write part of the data and get the number of bytes written (see [`io::Write::write`])
+    ///
+    /// An error means that no data was written. The default implementation writes all the data with
+    /// [`Driver::write`], so data partially written before an error is not known.
+    fn write_some(&self, data: &[u8]) -> Result<usize> {
+        self.write(data)?;
+        Ok(data.len())
+    }
+
     /// Read data
     fn read(&self, buf: &mut [u8]) -> Result<usize>;
 
@@ -150,6 +164,13 @@ impl Driver for NetworkDriver {
         Ok(stream.write_all(data)?)
     }
 
+    fn write_some(&self, data: &[u8]) -> Result<usize> {
+        let mut stream = self.stream.try_borrow_mut()?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        Ok(stream.write(data)?)
+    }
+
     fn read(&self, buf: &mut [u8]) -> Result<usize> {
         let mut stream = self.stream.try_borrow_mut()?;
         stream.set_read_timeout(Some(self.timeout))?;
@@ -205,6 +226,10 @@ impl Driver for FileDriver {
         Ok(())
     }
 
+    fn write_some(&self, data: &[u8]) -> Result<usize> {
+        Ok(self.file.try_borrow_mut()?.write(data)?)
+    }
+
     fn read(&self, buf: &mut [u8]) -> Result<usize> {
         Ok(self.file.try_borrow_mut()?.read(buf)?)
     }
@@ -606,6 +631,10 @@ impl Driver for SerialPortDriver {
         Ok(())
     }
 
+    fn write_some(&self, data: &[u8]) -> Result<usize> {
+        Ok(self.port.try_borrow_mut()?.write(data)?)
+    }
+
     fn read(&self, buf: &mut [u8]) -> Result<usize> {
         let mut port = self.port.try_borrow_mut()?;
         port.set_timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECONDS))
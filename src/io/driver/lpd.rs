@@ -0,0 +1,381 @@
+//! LPD/LPR driver (RFC 1179)
+
+use super::{Driver, DEFAULT_TIMEOUT_SECONDS};
+use crate::errors::{PrinterError, Result};
+use log::debug;
+use std::{
+    cell::{Cell, RefCell},
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    process,
+    rc::Rc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default LPD port
+pub const LPD_DEFAULT_PORT: u16 = 515;
+
+/// Default host, user and job name sent in the control file
+const DEFAULT_NAME: &str = "escpos";
+
+/// Maximum length of the host and user names (RFC 1179)
+const MAX_NAME_LENGTH: usize = 31;
+
+/// Maximum length of the job name (RFC 1179)
+const MAX_JOB_NAME_LENGTH: usize = 99;
+
+/// Maximum length of the queue name (no limit in RFC 1179, common server limit)
+const MAX_QUEUE_LENGTH: usize = 255;
+
+/// Number of job numbers (000 to 999)
+const JOB_NUMBERS: u16 = 1000;
+
+/// First job number of a driver, from the process ID and the clock, so that jobs sent by successive processes
+/// or drivers do not reuse the same data and control file names on the server
+fn initial_job_number() -> u16 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or_default();
+    (process::id().wrapping_add(nanos / 1000) % u32::from(JOB_NUMBERS)) as u16
+}
+
+/// LPD commands and subcommands
+const RECEIVE_JOB: u8 = 0x02;
+const RECEIVE_CONTROL_FILE: u8 = 0x02;
+const RECEIVE_DATA_FILE: u8 = 0x03;
+
+/// Check a field sent in a command or a control file line
+fn check_lpd_field(name: &str, value: &str, max_length: usize) -> Result<()> {
+    if value.is_empty() || value.len() > max_length {
+        return Err(PrinterError::Input(format!(
+            "LPD {name} must contain 1 to {max_length} characters"
+        )));
+    }
+    if !value.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(PrinterError::Input(format!(
+            "LPD {name} must only contain printable ASCII characters without spaces"
+        )));
+    }
+    Ok(())
+}
+
+/// Driver for network printer or print server accepting LPR jobs (port 515)
+///
+/// Written data is buffered and sent as one raw job (`l` filter) on flush, a new connection is opened for each job.
+/// The protocol has no back channel, so nothing can be read from the printer.
+#[derive(Clone)]
+pub struct LpdDriver {
+    host: String,
+    port: u16,
+    queue: String,
+    hostname: String,
+    user: String,
+    job_name: String,
+    timeout: Duration,
+    job_number: Rc<Cell<u16>>,
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl LpdDriver {
+    /// Open the LPD driver (the connection is opened when a job is sent)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use escpos::printer::Printer;
+    /// use escpos::utils::*;
+    /// use escpos::driver::*;
+    /// use std::time::Duration;
+    ///
+    /// let driver = LpdDriver::open("192.168.1.248", "raw", Some(Duration::from_secs(2)))
+    ///     .unwrap()
+    ///     .with_job_name("receipt-42")
+    ///     .unwrap();
+    /// let mut printer = Printer::new(driver, Protocol::default(), None);
+    /// ```
+    pub fn open(host: &str, queue: &str, timeout: Option<Duration>) -> Result<Self> {
+        check_lpd_field("queue", queue, MAX_QUEUE_LENGTH)?;
+
+        Ok(Self {
+            host: host.to_string(),
+            port: LPD_DEFAULT_PORT,
+            queue: queue.to_string(),
+            hostname: DEFAULT_NAME.to_string(),
+            user: DEFAULT_NAME.to_string(),
+            job_name: DEFAULT_NAME.to_string(),
+            timeout: timeout.unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT_SECONDS)),
+            job_number: Rc::new(Cell::new(initial_job_number())),
+            buffer: Rc::new(RefCell::new(vec![])),
+        })
+    }
+
+    /// Set the server port (default: 515)
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Set the client host name sent in the control file (default: `escpos`)
+    pub fn with_hostname(mut self, hostname: &str) -> Result<Self> {
+        check_lpd_field("host name", hostname, MAX_NAME_LENGTH)?;
+        self.hostname = hostname.to_string();
+        Ok(self)
+    }
+
+    /// Set the user name sent in the control file (default: `escpos`)
+    pub fn with_user(mut self, user: &str) -> Result<Self> {
+        check_lpd_field("user", user, MAX_NAME_LENGTH)?;
+        self.user = user.to_string();
+        Ok(self)
+    }
+
+    /// Set the job name sent in the control file (default: `escpos`)
+    pub fn with_job_name(mut self, job_name: &str) -> Result<Self> {
+        check_lpd_field("job name", job_name, MAX_JOB_NAME_LENGTH)?;
+        self.job_name = job_name.to_string();
+        Ok(self)
+    }
+
+    /// Set the number of the next job (000 to 999, default: derived from the process ID and the clock)
+    ///
+    /// The number is incremented after each job, it can be saved and restored to avoid reusing recent numbers.
+    pub fn with_job_number(self, number: u16) -> Result<Self> {
+        if number >= JOB_NUMBERS {
+            return Err(PrinterError::Input(format!(
+                "invalid LPD job number: {number} (0 - 999)"
+            )));
+        }
+        self.job_number.set(number);
+        Ok(self)
+    }
+
+    /// Get the number of the next job
+    pub fn job_number(&self) -> u16 {
+        self.job_number.get()
+    }
+
+    /// Next job number (000 to 999)
+    fn next_job_number(&self) -> u16 {
+        let number = self.job_number.get();
+        self.job_number.set((number + 1) % JOB_NUMBERS);
+        number
+    }
+
+    /// Control file content
+    fn control_file(&self, data_file: &str) -> String {
+        format!(
+            "H{}\nP{}\nJ{}\nl{data_file}\nU{data_file}\nN{}\n",
+            self.hostname, self.user, self.job_name, self.job_name
+        )
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let mut last_error = None;
+        for addr in (self.host.as_str(), self.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.map_or_else(
+            || PrinterError::Io(format!("no address found for {}", self.host)),
+            PrinterError::from,
+        ))
+    }
+
+    /// Send a command or a file and wait for the acknowledgement (a zero byte)
+    fn send(stream: &mut TcpStream, data: &[u8], step: &str) -> Result<()> {
+        stream.write_all(data)?;
+        stream.flush()?;
+
+        let mut ack = [0u8; 1];
+        match stream.read(&mut ack)? {
+            0 => Err(PrinterError::InvalidResponse(format!(
+                "LPD server closed the connection ({step})"
+            ))),
+            _ if ack[0] != 0 => Err(PrinterError::InvalidResponse(format!(
+                "LPD server refused the {step} (code {})",
+                ack[0]
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Send a job
+    fn send_job(&self, data: &[u8]) -> Result<()> {
+        let number = self.next_job_number();
+        let control_name = format!("cfA{number:03}{}", self.hostname);
+        let data_name = format!("dfA{number:03}{}", self.hostname);
+        let control_file = self.control_file(&data_name);
+
+        let mut stream = self.connect()?;
+        let mut command = vec![RECEIVE_JOB];
+        command.extend_from_slice(format!("{}\n", self.queue).as_bytes());
+        Self::send(&mut stream, &command, "queue")?;
+        debug!("[lpd] job {number:03} accepted by queue {}", self.queue);
+
+        let mut command = vec![RECEIVE_CONTROL_FILE];
+        command.extend_from_slice(format!("{} {control_name}\n", control_file.len()).as_bytes());
+        Self::send(&mut stream, &command, "control file command")?;
+        Self::send(&mut stream, &[control_file.as_bytes(), &[0]].concat(), "control file")?;
+
+        let mut command = vec![RECEIVE_DATA_FILE];
+        command.extend_from_slice(format!("{} {data_name}\n", data.len()).as_bytes());
+        Self::send(&mut stream, &command, "data file command")?;
+        Self::send(&mut stream, &[data, &[0]].concat(), "data file")?;
+        debug!("[lpd] job {number:03} sent, {} bytes", data.len());
+
+        Ok(())
+    }
+}
+
+impl Driver for LpdDriver {
+    fn name(&self) -> String {
+        format!("lpd ({}:{}/{})", self.host, self.port, self.queue)
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        self.buffer.try_borrow_mut()?.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn read(&self, _buf: &mut [u8]) -> Result<usize> {
+        Ok(0)
+    }
+
+    /// Send the buffered data as one job (the data is kept if the job is not accepted)
+    fn flush(&self) -> Result<()> {
+        let data = self.buffer.try_borrow()?.clone();
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.send_job(&data)?;
+        self.buffer.try_borrow_mut()?.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    /// Job received by the stand-in LPD server: queue, control file and data file
+    type ReceivedJob = (String, String, Vec<u8>);
+
+    /// Stand-in LPD server accepting one job, the queue is refused if it is not `raw`
+    fn lpd_server() -> (u16, JoinHandle<Option<ReceivedJob>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut line = vec![];
+            reader.read_until(b'\n', &mut line).unwrap();
+            assert_eq!(line[0], RECEIVE_JOB);
+            let queue = String::from_utf8(line[1..line.len() - 1].to_vec()).unwrap();
+            if queue != "raw" {
+                writer.write_all(&[1]).unwrap();
+                return None;
+            }
+            writer.write_all(&[0]).unwrap();
+
+            let mut files = vec![];
+            for subcommand in [RECEIVE_CONTROL_FILE, RECEIVE_DATA_FILE] {
+                let mut line = vec![];
+                reader.read_until(b'\n', &mut line).unwrap();
+                assert_eq!(line[0], subcommand);
+                let header = String::from_utf8(line[1..line.len() - 1].to_vec()).unwrap();
+                let length: usize = header.split(' ').next().unwrap().parse().unwrap();
+                writer.write_all(&[0]).unwrap();
+
+                let mut file = vec![0; length + 1];
+                reader.read_exact(&mut file).unwrap();
+                assert_eq!(file.pop(), Some(0));
+                writer.write_all(&[0]).unwrap();
+                files.push(file);
+            }
+            let data = files.pop().unwrap();
+            let control = String::from_utf8(files.pop().unwrap()).unwrap();
+
+            Some((queue, control, data))
+        });
+
+        (port, handle)
+    }
+
+    #[test]
+    fn test_lpd_driver() {
+        let (port, server) = lpd_server();
+        let driver = LpdDriver::open("127.0.0.1", "raw", Some(Duration::from_secs(2)))
+            .unwrap()
+            .with_port(port)
+            .with_hostname("pos1")
+            .unwrap()
+            .with_user("cashier")
+            .unwrap()
+            .with_job_name("receipt-42")
+            .unwrap()
+            .with_job_number(0)
+            .unwrap();
+        assert_eq!(driver.name(), format!("lpd (127.0.0.1:{port}/raw)"));
+
+        driver.write(b"\x1B@Hello").unwrap();
+        driver.write(b"\n\x1DVA\x00").unwrap();
+        driver.flush().unwrap();
+
+        let (queue, control, data) = server.join().unwrap().unwrap();
+        assert_eq!(queue, "raw");
+        assert_eq!(
+            control,
+            "Hpos1\nPcashier\nJreceipt-42\nldfA000pos1\nUdfA000pos1\nNreceipt-42\n"
+        );
+        assert_eq!(data, b"\x1B@Hello\n\x1DVA\x00");
+
+        // Empty buffer: no job
+        driver.flush().unwrap();
+    }
+
+    #[test]
+    fn test_lpd_driver_refused() {
+        let (port, server) = lpd_server();
+        let driver = LpdDriver::open("127.0.0.1", "unknown", None).unwrap().with_port(port);
+        driver.write(b"\x1B@").unwrap();
+
+        assert!(matches!(driver.flush(), Err(PrinterError::InvalidResponse(_))));
+        assert!(server.join().unwrap().is_none());
+        // The data is kept for a new attempt
+        assert_eq!(*driver.buffer.borrow(), b"\x1B@");
+    }
+
+    #[test]
+    fn test_lpd_driver_fields() {
+        assert!(LpdDriver::open("127.0.0.1", "", None).is_err());
+        assert!(LpdDriver::open("127.0.0.1", "my queue", None).is_err());
+
+        let driver = LpdDriver::open("127.0.0.1", "raw", None).unwrap();
+        assert!(driver.clone().with_hostname(&"a".repeat(32)).is_err());
+        assert!(driver.clone().with_user("").is_err());
+        assert!(driver.clone().with_job_name("receipt\n").is_err());
+
+        assert!(driver.job_number() < 1000);
+        assert!(driver.clone().with_job_number(1000).is_err());
+        let driver = driver.with_job_number(998).unwrap();
+        assert_eq!(driver.next_job_number(), 998);
+        assert_eq!(driver.next_job_number(), 999);
+        assert_eq!(driver.next_job_number(), 0);
+        assert_eq!(driver.job_number(), 1);
+    }
+}
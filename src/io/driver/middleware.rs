@@ -0,0 +1,548 @@
+//! Driver middleware: drivers wrapping another driver (logging, chunking, mirroring and retry)
+
+use super::Driver;
+use crate::errors::{PrinterError, Result};
+use log::{log, warn, Level};
+use std::{
+    cell::{Ref, RefCell},
+    rc::Rc,
+    thread::sleep,
+    time::Duration,
+};
+
+/// Number of bytes per hex dump line
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Default initial delay before retrying
+const DEFAULT_RETRY_DELAY_MILLISECONDS: u64 = 100;
+
+/// Hex dump lines: offset, bytes in hexadecimal and printable ASCII characters
+fn hex_dump(data: &[u8]) -> Vec<String> {
+    data.chunks(HEX_DUMP_WIDTH)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex = chunk.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(" ");
+            let ascii: String = chunk
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}  {hex:<width$}  |{ascii}|",
+                i * HEX_DUMP_WIDTH,
+                width = HEX_DUMP_WIDTH * 3 - 1
+            )
+        })
+        .collect()
+}
+
+// ================ Logger driver ================
+
+/// Driver logging a hex dump of written and read data with the `log` crate
+///
+/// # Example
+///
+/// ```rust
+/// use escpos::printer::Printer;
+/// use escpos::utils::*;
+/// use escpos::driver::*;
+///
+/// let driver = LoggerDriver::new(ConsoleDriver::open(false)).with_level(log::Level::Info);
+/// let mut printer = Printer::new(driver, Protocol::default(), None);
+/// ```
+#[derive(Clone)]
+pub struct LoggerDriver<D: Driver> {
+    inner: D,
+    level: Level,
+}
+
+impl<D: Driver> LoggerDriver<D> {
+    /// Create a new `LoggerDriver` (debug level)
+    pub fn new(inner: D) -> Self {
+        Self {
+            inner,
+            level: Level::Debug,
+        }
+    }
+
+    /// Set the log level
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Get the wrapped driver
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    fn log_data(&self, operation: &str, data: &[u8]) {
+        log!(self.level, "[{}] {operation} {} bytes", self.inner.name(), data.len());
+        for line in hex_dump(data) {
+            log!(self.level, "{line}");
+        }
+    }
+}
+
+impl<D: Driver> Driver for LoggerDriver<D> {
+    fn name(&self) -> String {
+        format!("logger ({})", self.inner.name())
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        self.log_data("write", data);
+        self.inner.write(data)
+    }
+
+    fn write_some(&self, data: &[u8]) -> Result<usize> {
+        let len = self.inner.write_some(data)?;
+        self.log_data("write", &data[..len]);
+        Ok(len)
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.log_data("read", &buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&self) -> Result<()> {
+        log!(self.level, "[{}] flush", self.inner.name());
+        self.inner.flush()
+    }
+}
+
+// ================ Chunked driver ================
+
+/// Driver splitting writes into packets of at most `chunk_size` bytes, with an optional delay between packets
+///
+/// # Example
+///
+/// ```rust
+/// use escpos::printer::Printer;
+/// use escpos::utils::*;
+/// use escpos::driver::*;
+/// use std::time::Duration;
+///
+/// let driver = ChunkedDriver::new(ConsoleDriver::open(false), 64)
+///     .unwrap()
+///     .with_delay(Duration::from_millis(5));
+/// let mut printer = Printer::new(driver, Protocol::default(), None);
+/// ```
+#[derive(Clone)]
+pub struct ChunkedDriver<D: Driver> {
+    inner: D,
+    chunk_size: usize,
+    delay: Option<Duration>,
+}
+
+impl<D: Driver> ChunkedDriver<D> {
+    /// Create a new `ChunkedDriver` (`chunk_size` > 0)
+    pub fn new(inner: D, chunk_size: usize) -> Result<Self> {
+        if chunk_size == 0 {
+            return Err(PrinterError::Input("chunk size must be greater than 0".to_string()));
+        }
+
+        Ok(Self {
+            inner,
+            chunk_size,
+            delay: None,
+        })
+    }
+
+    /// Wait between two packets
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Get the wrapped driver
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    /// Get chunk size
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+}
+
+impl<D: Driver> Driver for ChunkedDriver<D> {
+    fn name(&self) -> String {
+        format!("chunked ({})", self.inner.name())
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        for (i, chunk) in data.chunks(self.chunk_size).enumerate() {
+            if let (true, Some(delay)) = (i > 0, self.delay) {
+                sleep(delay);
+            }
+            self.inner.write(chunk)?;
+        }
+        Ok(())
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+// ================ Tee driver ================
+
+/// Driver mirroring writes to a second driver (e.g. a [`FileDriver`](super::FileDriver) to archive receipts)
+///
+/// Data is read from the primary driver only.
+///
+/// # Example
+///
+/// ```no_run
+/// use escpos::printer::Printer;
+/// use escpos::utils::*;
+/// use escpos::driver::*;
+/// use std::path::Path;
+///
+/// let archive = FileDriver::open(Path::new("./receipts.bin")).unwrap();
+/// let driver = TeeDriver::new(NetworkDriver::open("192.168.1.248", 9100, None).unwrap(), archive)
+///     .with_ignore_mirror_errors(true);
+/// let mut printer = Printer::new(driver, Protocol::default(), None);
+/// ```
+#[derive(Clone)]
+pub struct TeeDriver<D: Driver, M: Driver> {
+    primary: D,
+    mirror: M,
+    ignore_mirror_errors: bool,
+}
+
+impl<D: Driver, M: Driver> TeeDriver<D, M> {
+    /// Create a new `TeeDriver`
+    pub fn new(primary: D, mirror: M) -> Self {
+        Self {
+            primary,
+            mirror,
+            ignore_mirror_errors: false,
+        }
+    }
+
+    /// Log mirror errors as warnings instead of returning them
+    pub fn with_ignore_mirror_errors(mut self, ignore_mirror_errors: bool) -> Self {
+        self.ignore_mirror_errors = ignore_mirror_errors;
+        self
+    }
+
+    /// Get the primary driver
+    pub fn primary(&self) -> &D {
+        &self.primary
+    }
+
+    /// Get the mirror driver
+    pub fn mirror(&self) -> &M {
+        &self.mirror
+    }
+
+    fn mirror_result(&self, result: Result<()>) -> Result<()> {
+        match result {
+            Err(e) if self.ignore_mirror_errors => {
+                warn!("[{}] mirror error: {e}", self.name());
+                Ok(())
+            }
+            result => result,
+        }
+    }
+}
+
+impl<D: Driver, M: Driver> Driver for TeeDriver<D, M> {
+    fn name(&self) -> String {
+        format!("tee ({}, {})", self.primary.name(), self.mirror.name())
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        self.primary.write(data)?;
+        self.mirror_result(self.mirror.write(data))
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.primary.read(buf)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.primary.flush()?;
+        self.mirror_result(self.mirror.flush())
+    }
+}
+
+// ================ Retry driver ================
+
+/// Driver retrying operations failing with `PrinterError::Io`, with an exponential backoff
+///
+/// Writes are sent with [`Driver::write_some`]: after a partial write, only the unsent data is retried.
+/// Drivers writing all the data or nothing (the default implementation) send it again entirely.
+///
+/// A connection closed by the printer cannot succeed on a retry: a reconnect function replacing the wrapped
+/// driver before each retry can be set with [`RetryDriver::with_reconnect`].
+///
+/// # Example
+///
+/// ```rust
+/// use escpos::printer::Printer;
+/// use escpos::utils::*;
+/// use escpos::driver::*;
+/// use std::time::Duration;
+///
+/// let driver = RetryDriver::new(ConsoleDriver::open(false), 3)
+///     .with_backoff(Duration::from_millis(50), 2)
+///     .with_reconnect(|| Ok(ConsoleDriver::open(false)));
+/// let mut printer = Printer::new(driver, Protocol::default(), None);
+/// ```
+#[derive(Clone)]
+pub struct RetryDriver<D: Driver> {
+    inner: Rc<RefCell<D>>,
+    reconnect: Option<Rc<dyn Fn() -> Result<D>>>,
+    max_retries: u32,
+    delay: Duration,
+    factor: u32,
+}
+
+impl<D: Driver> RetryDriver<D> {
+    /// Create a new `RetryDriver`, retrying at most `max_retries` times (100 ms delay, doubled after each retry)
+    pub fn new(inner: D, max_retries: u32) -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(inner)),
+            reconnect: None,
+            max_retries,
+            delay: Duration::from_millis(DEFAULT_RETRY_DELAY_MILLISECONDS),
+            factor: 2,
+        }
+    }
+
+    /// Set the delay before the first retry, multiplied by `factor` after each retry
+    pub fn with_backoff(mut self, delay: Duration, factor: u32) -> Self {
+        self.delay = delay;
+        self.factor = factor;
+        self
+    }
+
+    /// Open a new driver replacing the wrapped one before each retry (a failed reconnection counts as a retry)
+    pub fn with_reconnect<F: Fn() -> Result<D> + 'static>(mut self, reconnect: F) -> Self {
+        self.reconnect = Some(Rc::new(reconnect));
+        self
+    }
+
+    /// Get the wrapped driver
+    pub fn inner(&self) -> Ref<'_, D> {
+        self.inner.borrow()
+    }
+
+    /// Get the maximum number of retries
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    fn retry<T>(&self, operation: &str, mut f: impl FnMut(&D) -> Result<T>) -> Result<T> {
+        let mut delay = self.delay;
+        let mut retries = 0;
+        loop {
+            let result = f(&*self.inner.try_borrow()?);
+            match result {
+                Err(PrinterError::Io(e)) if retries < self.max_retries => {
+                    retries += 1;
+                    let name = self.inner.try_borrow()?.name();
+                    warn!(
+                        "[{name}] {operation} failed: {e}, retry {retries}/{} in {delay:?}",
+                        self.max_retries
+                    );
+                    sleep(delay);
+                    delay = delay.saturating_mul(self.factor);
+
+                    if let Some(reconnect) = &self.reconnect {
+                        match reconnect() {
+                            Ok(driver) => *self.inner.try_borrow_mut()? = driver,
+                            Err(e) => warn!("[{name}] reconnection failed: {e}"),
+                        }
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<D: Driver> Driver for RetryDriver<D> {
+    fn name(&self) -> String {
+        format!("retry ({})", self.inner.borrow().name())
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        let mut sent = 0;
+        while sent < data.len() {
+            match self.retry("write", |driver| driver.write_some(&data[sent..]))? {
+                0 => return Err(PrinterError::Io("write returned 0 bytes".to_string())),
+                len => sent += len,
+            }
+        }
+        Ok(())
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.retry("read", |driver| driver.read(buf))
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.retry("flush", |driver| driver.flush())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockDriver, MockEvent, MockOperation};
+
+    #[test]
+    fn test_hex_dump() {
+        let dump = hex_dump(b"\x1B@Hello, world!\n\x1DVA\x00");
+        assert_eq!(dump.len(), 2);
+        assert_eq!(
+            dump[0],
+            "00000000  1b 40 48 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 0a  |.@Hello, world!.|"
+        );
+        assert_eq!(dump[1], format!("00000010  1d 56 41 00{}  |.VA.|", " ".repeat(36)));
+    }
+
+    #[test]
+    fn test_logger_driver() {
+        let mock = MockDriver::new();
+        mock.push_read(&[0x12]);
+        let driver = LoggerDriver::new(mock.clone());
+        driver.write(b"abc").unwrap();
+        assert_eq!(driver.read(&mut [0; 4]).unwrap(), 1);
+        driver.flush().unwrap();
+
+        assert_eq!(driver.name(), "logger (mock)");
+        assert_eq!(
+            mock.events(),
+            vec![
+                MockEvent::Write(b"abc".to_vec()),
+                MockEvent::Read(vec![0x12]),
+                MockEvent::Flush
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunked_driver() {
+        assert!(ChunkedDriver::new(MockDriver::new(), 0).is_err());
+
+        let mock = MockDriver::new();
+        let driver = ChunkedDriver::new(mock.clone(), 3)
+            .unwrap()
+            .with_delay(Duration::from_millis(1));
+        driver.write(b"abcdefgh").unwrap();
+        assert_eq!(mock.writes(), vec![b"abc".to_vec(), b"def".to_vec(), b"gh".to_vec()]);
+    }
+
+    #[test]
+    fn test_tee_driver() {
+        let (primary, mirror) = (MockDriver::new(), MockDriver::new());
+        primary.push_read(&[1]);
+        mirror.push_read(&[2]);
+        let driver = TeeDriver::new(primary.clone(), mirror.clone());
+        driver.write(b"abc").unwrap();
+        driver.flush().unwrap();
+        let mut buf = [0; 1];
+        driver.read(&mut buf).unwrap();
+
+        assert_eq!(buf, [1]);
+        assert_eq!(primary.written(), b"abc");
+        assert_eq!(mirror.written(), b"abc");
+        assert_eq!(mirror.flush_count(), 1);
+
+        let mirror = MockDriver::new().with_error(MockOperation::Write, 1, "disk full");
+        let driver = TeeDriver::new(MockDriver::new(), mirror.clone());
+        assert!(driver.write(b"abc").is_err());
+        let driver = driver.with_ignore_mirror_errors(true);
+        assert!(driver.write(b"abc").is_ok());
+        assert_eq!(driver.primary().written(), b"abcabc");
+    }
+
+    #[test]
+    fn test_retry_driver() {
+        let mock = MockDriver::new()
+            .with_error(MockOperation::Write, 1, "timeout")
+            .with_error(MockOperation::Write, 2, "timeout")
+            .with_error(MockOperation::Flush, 1, "timeout")
+            .with_error(MockOperation::Flush, 2, "timeout");
+        let driver = RetryDriver::new(mock.clone(), 2).with_backoff(Duration::from_millis(1), 2);
+
+        driver.write(b"abc").unwrap();
+        assert_eq!(mock.written(), b"abc");
+        assert!(driver.flush().is_ok());
+
+        let driver = RetryDriver::new(MockDriver::new().with_error(MockOperation::Flush, 1, "timeout"), 0);
+        assert!(driver.flush().is_err());
+    }
+
+    /// Driver writing at most 2 bytes at a time
+    struct PartialDriver(MockDriver);
+
+    impl Driver for PartialDriver {
+        fn name(&self) -> String {
+            "partial".to_string()
+        }
+
+        fn write(&self, data: &[u8]) -> Result<()> {
+            self.0.write(data)
+        }
+
+        fn write_some(&self, data: &[u8]) -> Result<usize> {
+            let len = data.len().min(2);
+            self.0.write(&data[..len])?;
+            Ok(len)
+        }
+
+        fn read(&self, buf: &mut [u8]) -> Result<usize> {
+            self.0.read(buf)
+        }
+
+        fn flush(&self) -> Result<()> {
+            self.0.flush()
+        }
+    }
+
+    #[test]
+    fn test_retry_driver_partial_write() {
+        let mock = MockDriver::new().with_error(MockOperation::Write, 2, "timeout");
+        let driver = RetryDriver::new(PartialDriver(mock.clone()), 1).with_backoff(Duration::from_millis(1), 2);
+
+        // Only the unsent data is written again
+        driver.write(b"abcde").unwrap();
+        assert_eq!(mock.writes(), vec![b"ab".to_vec(), b"cd".to_vec(), b"e".to_vec()]);
+    }
+
+    #[test]
+    fn test_retry_driver_reconnect() {
+        let broken = MockDriver::new()
+            .with_error(MockOperation::Write, 1, "connection reset")
+            .with_error(MockOperation::Write, 2, "connection reset");
+        let reconnected = MockDriver::new();
+        let reconnect = {
+            let reconnected = reconnected.clone();
+            move || Ok(reconnected.clone())
+        };
+        let driver = RetryDriver::new(broken.clone(), 2)
+            .with_backoff(Duration::from_millis(1), 2)
+            .with_reconnect(reconnect);
+
+        driver.write(b"abc").unwrap();
+        assert!(broken.written().is_empty());
+        assert_eq!(reconnected.written(), b"abc");
+        assert_eq!(driver.inner().written(), b"abc");
+    }
+}
@@ -186,9 +186,22 @@ pub(crate) mod io;
 /// Print document
 pub mod printer;
 
+/// Printer pool with failover
+pub mod pool;
+
 /// Printer options
 pub mod printer_options;
 
+/// Job routing to stations
+pub mod router;
+
+/// Persistent spool queue with retry
+pub mod spool;
+
+/// Test utilities (mock driver and golden files)
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 /// Utils module contains protocol and all needed constants and enums
 pub mod utils {
     pub use super::domain::*;
@@ -0,0 +1,420 @@
+//! Printer pool with failover
+//!
+//! A job is sent to the first healthy printer of the pool, checked with real-time status requests (`DLE EOT`).
+
+use crate::{
+    driver::Driver,
+    errors::{PrinterError, Result},
+    utils::{Protocol, RealTimeStatusRequest, RealTimeStatusResponse},
+};
+use log::warn;
+use std::{cell::RefCell, fmt, rc::Rc};
+
+/// Printer selection strategy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PoolStrategy {
+    /// Printers are tried in the order they were added
+    #[default]
+    Priority,
+    /// Each job starts with the printer after the one used by the previous job
+    RoundRobin,
+}
+
+/// Printer health
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrinterHealth {
+    /// Online with paper
+    Healthy,
+    /// Offline (cover open, error...)
+    Offline,
+    /// Roll paper end detected
+    PaperEnd,
+    /// No (or invalid) response to the status requests
+    NoResponse,
+    /// Communication error
+    Error(String),
+}
+
+impl PrinterHealth {
+    /// Is the printer healthy?
+    pub fn is_healthy(&self) -> bool {
+        *self == Self::Healthy
+    }
+}
+
+impl fmt::Display for PrinterHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Healthy => write!(f, "healthy"),
+            Self::Offline => write!(f, "offline"),
+            Self::PaperEnd => write!(f, "paper end"),
+            Self::NoResponse => write!(f, "no response"),
+            Self::Error(e) => write!(f, "error: {e}"),
+        }
+    }
+}
+
+/// Delivery report of a job
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolReport {
+    /// Index of the printer which printed the job
+    pub index: usize,
+    /// Name of the printer which printed the job
+    pub name: String,
+    /// Printers skipped before (name and health)
+    pub skipped: Vec<(String, PrinterHealth)>,
+}
+
+struct PoolPrinter {
+    name: String,
+    driver: Box<dyn Driver>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    printers: Vec<PoolPrinter>,
+    /// Next printer tried first (round-robin)
+    next: usize,
+    /// Data written through the `Driver` implementation, sent on flush
+    buffer: Vec<u8>,
+    last_report: Option<PoolReport>,
+}
+
+/// Pool of printers with failover
+///
+/// A pool is also a [`Driver`]: data written by a [`Printer`](crate::printer::Printer) is buffered and sent as
+/// one job to the first healthy printer when the printer is flushed (`print`, `print_cut`...).
+/// Clones share the same printers.
+///
+/// # Example
+///
+#[cfg_attr(feature = "testing", doc = "```rust")]
+#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+/// use escpos::printer::Printer;
+/// use escpos::pool::*;
+/// use escpos::testing::MockDriver;
+/// use escpos::utils::*;
+///
+/// // Roll paper end on the first printer
+/// let counter_1 = MockDriver::new()
+///     .with_response(&[0x10, 0x04, 0x01], &[0x12])
+///     .with_response(&[0x10, 0x04, 0x04], &[0x72]);
+/// let counter_2 = MockDriver::new()
+///     .with_response(&[0x10, 0x04, 0x01], &[0x12])
+///     .with_response(&[0x10, 0x04, 0x04], &[0x12]);
+///
+/// let pool = PrinterPool::new(PoolStrategy::Priority)
+///     .with_printer("counter 1", counter_1)
+///     .with_printer("counter 2", counter_2.clone());
+/// Printer::new(pool.clone(), Protocol::default(), None)
+///     .init()
+///     .unwrap()
+///     .writeln("Receipt")
+///     .unwrap()
+///     .print()
+///     .unwrap();
+///
+/// let report = pool.last_report().unwrap();
+/// assert_eq!(report.name, "counter 2");
+/// assert_eq!(report.skipped, vec![("counter 1".to_string(), PrinterHealth::PaperEnd)]);
+/// assert!(counter_2.written().ends_with(b"Receipt\x1Bd\x01"));
+/// ```
+#[derive(Clone, Default)]
+pub struct PrinterPool {
+    strategy: PoolStrategy,
+    state: Rc<RefCell<PoolState>>,
+}
+
+impl PrinterPool {
+    /// Create a new empty `PrinterPool`
+    pub fn new(strategy: PoolStrategy) -> Self {
+        Self {
+            strategy,
+            state: Rc::default(),
+        }
+    }
+
+    /// Add a printer
+    pub fn with_printer<D: Driver + 'static>(self, name: &str, driver: D) -> Self {
+        self.state.borrow_mut().printers.push(PoolPrinter {
+            name: name.to_string(),
+            driver: Box::new(driver),
+        });
+        self
+    }
+
+    /// Get strategy
+    pub fn strategy(&self) -> PoolStrategy {
+        self.strategy
+    }
+
+    /// Get the printer names
+    pub fn names(&self) -> Vec<String> {
+        self.state.borrow().printers.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Get the report of the last job
+    pub fn last_report(&self) -> Option<PoolReport> {
+        self.state.borrow().last_report.clone()
+    }
+
+    /// Check the health of a printer (printer status and roll paper sensor status)
+    pub fn health(&self, index: usize) -> Result<PrinterHealth> {
+        let state = self.state.try_borrow()?;
+        let printer = state
+            .printers
+            .get(index)
+            .ok_or_else(|| PrinterError::Input(format!("invalid printer index: {index}")))?;
+        Ok(Self::check_health(printer.driver.as_ref()))
+    }
+
+    /// Check the health of all printers
+    pub fn health_all(&self) -> Result<Vec<(String, PrinterHealth)>> {
+        let state = self.state.try_borrow()?;
+        Ok(state
+            .printers
+            .iter()
+            .map(|printer| (printer.name.clone(), Self::check_health(printer.driver.as_ref())))
+            .collect())
+    }
+
+    fn check_health(driver: &dyn Driver) -> PrinterHealth {
+        let protocol = Protocol::default();
+        let requests = [RealTimeStatusRequest::Printer, RealTimeStatusRequest::RollPaperSensor];
+
+        let mut responses = vec![];
+        for request in requests {
+            if let Err(e) = driver.write(&protocol.real_time_status(request)) {
+                return PrinterHealth::Error(e.to_string());
+            }
+            if let Err(e) = driver.flush() {
+                return PrinterHealth::Error(e.to_string());
+            }
+            let mut buf = [0; 1];
+            match driver.read(&mut buf) {
+                Ok(1) => match RealTimeStatusResponse::parse(request, buf[0]) {
+                    Ok(status) => responses.push(status),
+                    Err(_) => return PrinterHealth::NoResponse,
+                },
+                Ok(_) => return PrinterHealth::NoResponse,
+                Err(e) => return PrinterHealth::Error(e.to_string()),
+            }
+        }
+
+        let is_set = |i: usize, response| responses[i].get(&response).copied().unwrap_or(false);
+        if !is_set(0, RealTimeStatusResponse::Online) {
+            PrinterHealth::Offline
+        } else if !is_set(1, RealTimeStatusResponse::RollPaperEndSensorPaperPresent) {
+            PrinterHealth::PaperEnd
+        } else {
+            PrinterHealth::Healthy
+        }
+    }
+
+    /// Send a job to the first healthy printer
+    ///
+    /// A printer failing while receiving the job is skipped, the whole job is then sent to the next healthy printer.
+    /// The part of the job already received by the failing printer is not cancelled and may still be printed
+    /// (a truncated receipt) when the printer recovers.
+    pub fn print_job(&self, job: &[u8]) -> Result<PoolReport> {
+        let mut state = self.state.try_borrow_mut()?;
+        let count = state.printers.len();
+        if count == 0 {
+            return Err(PrinterError::Input("the printer pool is empty".to_string()));
+        }
+
+        let start = match self.strategy {
+            PoolStrategy::Priority => 0,
+            PoolStrategy::RoundRobin => state.next % count,
+        };
+
+        let mut skipped = vec![];
+        for index in (start..count).chain(0..start) {
+            let printer = &state.printers[index];
+            let health = Self::check_health(printer.driver.as_ref());
+            if !health.is_healthy() {
+                warn!("[pool] printer {} skipped: {health}", printer.name);
+                skipped.push((printer.name.clone(), health));
+                continue;
+            }
+
+            match printer.driver.write(job).and_then(|_| printer.driver.flush()) {
+                Ok(()) => {
+                    let report = PoolReport {
+                        index,
+                        name: printer.name.clone(),
+                        skipped,
+                    };
+                    state.next = index + 1;
+                    state.last_report = Some(report.clone());
+                    return Ok(report);
+                }
+                Err(e) => {
+                    warn!("[pool] printer {} failed: {e}", printer.name);
+                    skipped.push((printer.name.clone(), PrinterHealth::Error(e.to_string())));
+                }
+            }
+        }
+
+        let causes: Vec<_> = skipped
+            .iter()
+            .map(|(name, health)| format!("{name}: {health}"))
+            .collect();
+        Err(PrinterError::Io(format!(
+            "no healthy printer in the pool ({})",
+            causes.join(", ")
+        )))
+    }
+}
+
+impl Driver for PrinterPool {
+    fn name(&self) -> String {
+        format!("pool ({})", self.names().join(", "))
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        self.state.try_borrow_mut()?.buffer.extend_from_slice(data);
+        Ok(())
+    }
+
+    /// Read from the printer of the last job
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        let state = self.state.try_borrow()?;
+        match &state.last_report {
+            Some(report) => state.printers[report.index].driver.read(buf),
+            None => Ok(0),
+        }
+    }
+
+    /// Send the buffered data as one job
+    ///
+    /// The buffer is cleared once the job is printed: when no printer is healthy, the job is kept and sent
+    /// again by the next flush.
+    fn flush(&self) -> Result<()> {
+        let job = self.state.try_borrow()?.buffer.clone();
+        if job.is_empty() {
+            return Ok(());
+        }
+        self.print_job(&job)?;
+        self.state.try_borrow_mut()?.buffer.drain(..job.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockDriver, MockOperation};
+
+    /// Printer replying to status requests (0x12: online, paper present)
+    fn printer(status: u8, paper: u8) -> MockDriver {
+        MockDriver::new()
+            .with_response(&[0x10, 0x04, 0x01], &[status])
+            .with_response(&[0x10, 0x04, 0x04], &[paper])
+    }
+
+    #[test]
+    fn test_printer_health() {
+        let pool = PrinterPool::new(PoolStrategy::Priority)
+            .with_printer("ok", printer(0x12, 0x12))
+            .with_printer("offline", printer(0x1A, 0x12))
+            .with_printer("paper end", printer(0x12, 0x72))
+            .with_printer("no response", MockDriver::new())
+            .with_printer(
+                "error",
+                MockDriver::new().with_error(MockOperation::Write, 1, "unreachable"),
+            );
+
+        assert_eq!(
+            pool.health_all().unwrap(),
+            vec![
+                ("ok".to_string(), PrinterHealth::Healthy),
+                ("offline".to_string(), PrinterHealth::Offline),
+                ("paper end".to_string(), PrinterHealth::PaperEnd),
+                ("no response".to_string(), PrinterHealth::NoResponse),
+                (
+                    "error".to_string(),
+                    PrinterHealth::Error("IO error: unreachable".to_string())
+                ),
+            ]
+        );
+        assert!(pool.health(5).is_err());
+    }
+
+    #[test]
+    fn test_pool_priority() {
+        let (first, second) = (printer(0x12, 0x72), printer(0x12, 0x12));
+        let pool = PrinterPool::new(PoolStrategy::Priority)
+            .with_printer("first", first.clone())
+            .with_printer("second", second.clone());
+
+        for _ in 0..2 {
+            let report = pool.print_job(b"job").unwrap();
+            assert_eq!((report.index, report.name.as_str()), (1, "second"));
+        }
+        assert!(!first.written().ends_with(b"job"));
+        assert!(second.written().ends_with(b"job"));
+    }
+
+    #[test]
+    fn test_pool_round_robin() {
+        let pool = PrinterPool::new(PoolStrategy::RoundRobin)
+            .with_printer("a", printer(0x12, 0x12))
+            .with_printer("b", printer(0x12, 0x12))
+            .with_printer("c", printer(0x1A, 0x12));
+
+        let indexes: Vec<_> = (0..4).map(|_| pool.print_job(b"job").unwrap().index).collect();
+        assert_eq!(indexes, vec![0, 1, 0, 1]);
+        assert_eq!(pool.last_report().unwrap().skipped.len(), 0);
+    }
+
+    #[test]
+    fn test_pool_failover() {
+        // Healthy but failing while receiving the job
+        let failing = printer(0x12, 0x12).with_error(MockOperation::Write, 3, "connection reset");
+        let backup = printer(0x12, 0x12);
+        let pool = PrinterPool::new(PoolStrategy::Priority)
+            .with_printer("failing", failing)
+            .with_printer("backup", backup.clone());
+
+        let report = pool.print_job(b"job").unwrap();
+        assert_eq!(report.name, "backup");
+        assert!(matches!(report.skipped[0].1, PrinterHealth::Error(_)));
+
+        let pool = PrinterPool::new(PoolStrategy::Priority).with_printer("offline", printer(0x1A, 0x12));
+        assert!(matches!(pool.print_job(b"job"), Err(PrinterError::Io(_))));
+        assert!(PrinterPool::default().print_job(b"job").is_err());
+    }
+
+    #[test]
+    fn test_pool_driver() {
+        let target = printer(0x12, 0x12);
+        let pool = PrinterPool::new(PoolStrategy::Priority).with_printer("target", target.clone());
+        pool.write(b"a").unwrap();
+        pool.write(b"b").unwrap();
+        assert!(target.writes().is_empty());
+
+        pool.flush().unwrap();
+        assert_eq!(target.writes().last(), Some(&b"ab".to_vec()));
+        assert_eq!(pool.name(), "pool (target)");
+
+        target.push_read(&[0x12]);
+        assert_eq!(pool.read(&mut [0; 1]).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_pool_driver_keeps_job() {
+        let pool = PrinterPool::new(PoolStrategy::Priority).with_printer("offline", printer(0x1A, 0x12));
+        pool.write(b"job").unwrap();
+        assert!(pool.flush().is_err());
+
+        // The job is sent by the next flush
+        let backup = printer(0x12, 0x12);
+        let pool = pool.with_printer("backup", backup.clone());
+        pool.flush().unwrap();
+        assert_eq!(backup.writes().last(), Some(&b"job".to_vec()));
+
+        pool.flush().unwrap();
+        assert_eq!(backup.writes().iter().filter(|w| *w == b"job").count(), 1);
+    }
+}
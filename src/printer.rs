@@ -2,6 +2,7 @@
 
 use super::errors::Result;
 use crate::printer_options::PrinterOptions;
+use crate::spool::Spool;
 use crate::{domain::*, driver::Driver, utils::Protocol};
 use log::debug;
 
@@ -103,6 +104,21 @@ impl<D: Driver> Printer<D> {
         Ok(self)
     }
 
+    /// Add the data to a spool queue instead of sending it to the driver, returns the job ID
+    ///
+    /// The instructions are cleaned, the job is delivered later by the spool (see [`Spool`]).
+    pub fn print_to_spool(&mut self, spool: &Spool, name: &str) -> Result<u64> {
+        let data: Vec<u8> = self.instructions.iter().flat_map(|i| i.flatten_commands()).collect();
+        let id = spool.enqueue(name, &data)?;
+        self.instructions = vec![];
+
+        if self.options.get_debug_mode().is_some() {
+            debug!("[print to spool] job {id}");
+        }
+
+        Ok(id)
+    }
+
     /// Add command to instructions, write data and display debug information
     fn command(&mut self, label: &str, cmd: &[Command]) -> Result<&mut Self> {
         let instruction = Instruction::new(label, cmd, self.options.get_debug_mode());
@@ -265,6 +281,35 @@ impl<D: Driver> Printer<D> {
     }
 
     /// Text
+    ///
+    /// Characters are encoded with the selected [page code](PageCode) table if any.
+    ///
+    /// # Thai mode
+    ///
+    /// When a Thai page code is selected (e.g. [`PageCode::Thai18`] on Epson Thai models),
+    /// Thai combining vowels and tone marks are sent right after their base character in the order
+    /// expected by the firmware, which prints them above or below it (three-pass printing).
+    /// Use [`thai_display_width`] to compute the number of columns used by a Thai text.
+    ///
+    /// ```rust
+    /// use escpos::printer::Printer;
+    /// use escpos::utils::*;
+    /// use escpos::{driver::*, errors::Result};
+    ///
+    /// fn main() -> Result<()> {
+    ///     let driver = ConsoleDriver::open(false);
+    ///     let item = "กุ้งทอด";
+    ///     let padding = " ".repeat(20 - thai_display_width(item));
+    ///
+    ///     Printer::new(driver, Protocol::default(), None)
+    ///         .init()?
+    ///         .page_code(PageCode::Thai18)?
+    ///         .writeln(&format!("{item}{padding}120฿"))?
+    ///         .print_cut()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
     pub fn write(&mut self, text: &str) -> Result<&mut Self> {
         let cmd = self.protocol.text(text, self.options.get_page_code())?;
         self.command("text", &[cmd])
@@ -355,6 +400,20 @@ impl<D: Driver> Printer<D> {
     #[cfg(feature = "barcodes")]
     /// Print barcode
     fn barcode(&mut self, barcode: Barcode) -> Result<&mut Self> {
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_barcodes().contains(&barcode.system) {
+            let mut option = BarcodeImageOption::new(
+                u8::from(barcode.option.width()).into(),
+                u8::from(barcode.option.height()).into(),
+            )?;
+            if let Some((font, size)) = self.options.get_software_barcodes_hri() {
+                if !matches!(barcode.option.position(), BarcodePosition::None) {
+                    option = option.with_hri(font.clone(), *size);
+                }
+            }
+            return self.barcode_image(&barcode, &option);
+        }
+
         let commands = self.protocol.barcode(&barcode.data, barcode.system, barcode.option)?;
         self.command(&format!("print {} barcode", barcode.system), commands.as_slice())
     }
@@ -443,10 +502,109 @@ impl<D: Driver> Printer<D> {
         self.barcode(Barcode::new(BarcodeSystem::ITF, data, option)?)
     }
 
+    #[cfg(feature = "barcodes")]
+    /// Print CODE93 barcode with default option
+    pub fn code93(&mut self, data: &str) -> Result<&mut Self> {
+        self.barcode(Barcode::new(BarcodeSystem::CODE93, data, BarcodeOption::default())?)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print CODE93 barcode with option
+    pub fn code93_option(&mut self, data: &str, option: BarcodeOption) -> Result<&mut Self> {
+        self.barcode(Barcode::new(BarcodeSystem::CODE93, data, option)?)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print CODE128 barcode with default option
+    ///
+    /// The code sets are automatically chosen unless the data contains explicit code sets (`{A`, `{B` or `{C`).
+    /// Function characters are written `{1` to `{4` and the `{` character `{{`.
+    pub fn code128(&mut self, data: &str) -> Result<&mut Self> {
+        self.barcode(Barcode::new(BarcodeSystem::CODE128, data, BarcodeOption::default())?)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print CODE128 barcode with option
+    pub fn code128_option(&mut self, data: &str, option: BarcodeOption) -> Result<&mut Self> {
+        self.barcode(Barcode::new(BarcodeSystem::CODE128, data, option)?)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print GS1-128 barcode with default option
+    ///
+    /// Data is written as element strings with application identifiers between parentheses
+    /// (`(01)09501101530003(10)ABC123`). FNC1 separators are inserted after variable length element strings.
+    pub fn gs1_128(&mut self, data: &str) -> Result<&mut Self> {
+        self.barcode(Barcode::new(BarcodeSystem::GS1128, data, BarcodeOption::default())?)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print GS1-128 barcode with option
+    pub fn gs1_128_option(&mut self, data: &str, option: BarcodeOption) -> Result<&mut Self> {
+        self.barcode(Barcode::new(BarcodeSystem::GS1128, data, option)?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Check an ECI designator can be encoded (only in 2D codes printed as images)
+    fn check_eci(&self, eci: Option<u32>) -> Result<()> {
+        self.check_software_codes_2d(eci.map(|eci| format!("ECI designator {eci}")))
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Check that a feature only available in 2D codes printed as images is not used otherwise
+    fn check_software_codes_2d(&self, feature: Option<String>) -> Result<()> {
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            return Ok(());
+        }
+
+        match feature {
+            Some(feature) => Err(crate::errors::PrinterError::Input(format!(
+                "{feature} is only encoded in 2D codes printed as images (software 2D codes)"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Default QR code option (Model 2 when printed as image, Model 1 not being supported)
+    fn default_qrcode_option(&self) -> Option<QRCodeOption> {
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            let option = QRCodeOption::default();
+            return Some(QRCodeOption::new(
+                QRCodeModel::Model2,
+                option.size(),
+                option.correction_level(),
+            ));
+        }
+        None
+    }
+
     #[cfg(feature = "codes_2d")]
     /// Construct QR code
-    fn qrcode_builder(&mut self, data: &str, option: Option<QRCodeOption>) -> Result<&mut Self> {
-        let qrcode = QRCode::new(data, option)?;
+    fn qrcode_builder(&mut self, mut qrcode: QRCode) -> Result<&mut Self> {
+        self.check_eci(qrcode.option.eci())?;
+        if qrcode.option.auto_size() {
+            let size = qrcode.fit_size(self.options.get_printable_width())?;
+            let option = &qrcode.option;
+            let mut sized = QRCodeOption::new(option.model(), size, option.correction_level());
+            if let Some(eci) = option.eci() {
+                sized = sized.with_eci(eci)?;
+            }
+            qrcode.option = sized;
+        }
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            let cmd = self.protocol.cancel();
+            self.command("cancel data", &[cmd])?;
+
+            let cmd = self
+                .protocol
+                .qrcode_image(&qrcode, self.options.get_printable_width())?;
+            return self.command("print qrcode image", &[cmd]);
+        }
+
         let commands = self.protocol.qrcode(&qrcode.data, qrcode.option)?;
         self.command("print qrcode", commands.as_slice())
     }
@@ -454,13 +612,72 @@ impl<D: Driver> Printer<D> {
     #[cfg(feature = "codes_2d")]
     /// Print QR code with default option
     pub fn qrcode(&mut self, data: &str) -> Result<&mut Self> {
-        self.qrcode_builder(data, None)
+        self.qrcode_builder(QRCode::new(data, self.default_qrcode_option())?)
     }
 
     #[cfg(feature = "codes_2d")]
     /// Print QR code with option
     pub fn qrcode_option(&mut self, data: &str, option: QRCodeOption) -> Result<&mut Self> {
-        self.qrcode_builder(data, Some(option))
+        self.qrcode_builder(QRCode::new(data, Some(option))?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Print QR code from bytes with default option
+    pub fn qrcode_from_bytes(&mut self, data: &[u8]) -> Result<&mut Self> {
+        self.qrcode_builder(QRCode::from_bytes(data, self.default_qrcode_option())?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Print QR code from bytes with option
+    pub fn qrcode_from_bytes_option(&mut self, data: &[u8], option: QRCodeOption) -> Result<&mut Self> {
+        self.qrcode_builder(QRCode::from_bytes(data, Some(option))?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Print a payment QR code (EPC, Swiss QR-bill, EMVCo...), each module being `size` dots wide
+    ///
+    /// The QR code uses Model 2 with the error correction level mandated by the payment format.
+    /// With the `graphics` feature, the Swiss cross is printed in the center of Swiss QR-bill QR codes
+    /// (the QR code is printed as image).
+    pub fn payment_qrcode<P: PaymentQRCode>(&mut self, payment: &P, size: u8) -> Result<&mut Self> {
+        let qrcode = payment.qrcode(size)?;
+
+        #[cfg(feature = "graphics")]
+        if payment.swiss_cross() {
+            let cmd = self.protocol.cancel();
+            self.command("cancel data", &[cmd])?;
+
+            let cmd = self
+                .protocol
+                .swiss_qrcode_image(&qrcode, self.options.get_printable_width())?;
+            return self.command("print swiss qrcode image", &[cmd]);
+        }
+
+        self.qrcode_builder(qrcode)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Print a fiscal receipt QR code (DSFinV-K, RKSV)
+    ///
+    /// The QR code uses Model 2 with the error correction level mandated by the fiscal format and the largest size
+    /// fitting the printable width.
+    pub fn fiscal_qrcode<F: FiscalQRCode>(&mut self, receipt: &F) -> Result<&mut Self> {
+        self.qrcode_builder(receipt.qrcode()?)
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print linear GS1 DataBar with custom option
+    pub fn gs1_databar_option(&mut self, data: &str, option: GS1DataBarOption) -> Result<&mut Self> {
+        let code = GS1DataBar::new(data, option)?;
+        let label = format!("print {}", code.option.code_type());
+        let commands = self.protocol.gs1_databar(&code.data, code.option)?;
+        self.command(&label, commands.as_slice())
+    }
+
+    #[cfg(feature = "barcodes")]
+    /// Print linear GS1 DataBar Omnidirectional
+    pub fn gs1_databar(&mut self, data: &str) -> Result<&mut Self> {
+        self.gs1_databar_option(data, GS1DataBarOption::default())
     }
 
     #[cfg(feature = "codes_2d")]
@@ -478,63 +695,244 @@ impl<D: Driver> Printer<D> {
     }
 
     #[cfg(feature = "codes_2d")]
-    /// PDF417
-    pub fn pdf417_option(&mut self, data: &str, option: Pdf417Option) -> Result<&mut Self> {
-        let code = Pdf417::new(data, option);
+    /// Print GS1 Composite code with custom option
+    ///
+    /// The linear data depends on the linear element type, the composite data is written as GS1 element strings.
+    pub fn composite_option(
+        &mut self,
+        linear_data: &str,
+        composite_data: &str,
+        option: CompositeOption,
+    ) -> Result<&mut Self> {
+        let code = Composite::new(linear_data, composite_data, option)?;
+
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            #[cfg(not(feature = "barcodes"))]
+            return Err(crate::errors::PrinterError::Input(
+                "Composite codes are printed as image with the barcodes feature, disable software 2D codes to print them"
+                    .to_string(),
+            ));
+
+            #[cfg(feature = "barcodes")]
+            {
+                let cmd = self
+                    .protocol
+                    .composite_image(&code, self.options.get_printable_width())?;
+                return self.command("print composite image", &[cmd]);
+            }
+        }
+
+        let label = format!("print {} composite", code.option.linear_type());
+        let commands = self.protocol.composite(&code)?;
+        self.command(&label, commands.as_slice())
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Print GS1 Composite code with an EAN13 linear element
+    pub fn composite(&mut self, linear_data: &str, composite_data: &str) -> Result<&mut Self> {
+        self.composite_option(linear_data, composite_data, CompositeOption::default())
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Construct PDF417
+    fn pdf417_builder(&mut self, code: Pdf417) -> Result<&mut Self> {
+        self.check_eci(code.option.eci())?;
+
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            let cmd = self.protocol.cancel();
+            self.command("cancel data", &[cmd])?;
+
+            let cmd = self.protocol.pdf417_image(&code, self.options.get_printable_width())?;
+            return self.command("print PDF417 image", &[cmd]);
+        }
+
         let commands = self.protocol.pdf417(&code.data, code.option)?;
         self.command("print PDF417", commands.as_slice())
     }
 
+    #[cfg(feature = "codes_2d")]
+    /// PDF417
+    pub fn pdf417_option(&mut self, data: &str, option: Pdf417Option) -> Result<&mut Self> {
+        self.pdf417_builder(Pdf417::new(data, option))
+    }
+
     #[cfg(feature = "codes_2d")]
     /// PDF417
     pub fn pdf417(&mut self, data: &str) -> Result<&mut Self> {
-        let code = Pdf417::new(data, Pdf417Option::default());
-        self.pdf417_option(data, code.option)
+        self.pdf417_option(data, Pdf417Option::default())
     }
 
     #[cfg(feature = "codes_2d")]
-    /// MaxiCode
-    pub fn maxi_code_option(&mut self, data: &str, mode: MaxiCodeMode) -> Result<&mut Self> {
-        let code = MaxiCode::new(data, mode);
+    /// PDF417 from bytes with option
+    pub fn pdf417_from_bytes_option(&mut self, data: &[u8], option: Pdf417Option) -> Result<&mut Self> {
+        self.pdf417_builder(Pdf417::from_bytes(data, option))
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// PDF417 from bytes
+    pub fn pdf417_from_bytes(&mut self, data: &[u8]) -> Result<&mut Self> {
+        self.pdf417_from_bytes_option(data, Pdf417Option::default())
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Construct Macro PDF417
+    ///
+    /// The control blocks cannot be sent with `GS ( k`: the symbols are always printed as images.
+    fn macro_pdf417_builder(&mut self, code: MacroPdf417) -> Result<&mut Self> {
+        let count = code.segment_count();
+        for index in 0..count {
+            let cmd = self
+                .protocol
+                .macro_pdf417_image(&code, index, self.options.get_printable_width())?;
+            self.command(&format!("print Macro PDF417 segment {}/{count}", index + 1), &[cmd])?;
+        }
+        Ok(self)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Macro PDF417 (data split into a sequence of PDF417 symbols printed one after another)
+    pub fn macro_pdf417(
+        &mut self,
+        data: &str,
+        option: Pdf417Option,
+        macro_option: MacroPdf417Option,
+    ) -> Result<&mut Self> {
+        self.macro_pdf417_builder(MacroPdf417::new(data, option, macro_option)?)
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    /// Macro PDF417 from bytes
+    pub fn macro_pdf417_from_bytes(
+        &mut self,
+        data: &[u8],
+        option: Pdf417Option,
+        macro_option: MacroPdf417Option,
+    ) -> Result<&mut Self> {
+        self.macro_pdf417_builder(MacroPdf417::from_bytes(data, option, macro_option)?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Construct MaxiCode
+    fn maxi_code_builder(&mut self, code: MaxiCode) -> Result<&mut Self> {
         let commands = self.protocol.maxi_code(&code.data, code.mode)?;
         self.command("print MaxiCode", commands.as_slice())
     }
 
+    #[cfg(feature = "codes_2d")]
+    /// MaxiCode
+    pub fn maxi_code_option(&mut self, data: &str, mode: MaxiCodeMode) -> Result<&mut Self> {
+        self.maxi_code_builder(MaxiCode::new(data, mode))
+    }
+
     #[cfg(feature = "codes_2d")]
     /// MaxiCode
     pub fn maxi_code(&mut self, data: &str) -> Result<&mut Self> {
-        let code = MaxiCode::new(data, MaxiCodeMode::default());
-        self.maxi_code_option(data, code.mode)
+        self.maxi_code_option(data, MaxiCodeMode::default())
     }
 
     #[cfg(feature = "codes_2d")]
-    /// DataMatrix
-    pub fn data_matrix_option(&mut self, data: &str, option: DataMatrixOption) -> Result<&mut Self> {
-        let code = DataMatrix::new(data, option);
+    /// MaxiCode from bytes with mode
+    pub fn maxi_code_from_bytes_option(&mut self, data: &[u8], mode: MaxiCodeMode) -> Result<&mut Self> {
+        self.maxi_code_builder(MaxiCode::from_bytes(data, mode))
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// MaxiCode from bytes
+    pub fn maxi_code_from_bytes(&mut self, data: &[u8]) -> Result<&mut Self> {
+        self.maxi_code_from_bytes_option(data, MaxiCodeMode::default())
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// MaxiCode structured carrier message (mode 2 or 3 selected from the postal code)
+    pub fn maxi_code_carrier_message(&mut self, message: &MaxiCodeCarrierMessage) -> Result<&mut Self> {
+        self.maxi_code_builder(message.to_maxi_code()?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Construct DataMatrix
+    fn data_matrix_builder(&mut self, code: DataMatrix) -> Result<&mut Self> {
+        self.check_eci(code.option.eci())?;
+        self.check_software_codes_2d(code.option.gs1().then(|| "GS1 DataMatrix".to_string()))?;
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            let cmd = self.protocol.cancel();
+            self.command("cancel data", &[cmd])?;
+
+            let cmd = self
+                .protocol
+                .data_matrix_image(&code, self.options.get_printable_width())?;
+            return self.command("print DataMatrix image", &[cmd]);
+        }
+
         let commands = self.protocol.data_matrix(&code.data, code.option)?;
         self.command("print DataMatrix", commands.as_slice())
     }
 
+    #[cfg(feature = "codes_2d")]
+    /// DataMatrix
+    pub fn data_matrix_option(&mut self, data: &str, option: DataMatrixOption) -> Result<&mut Self> {
+        self.data_matrix_builder(DataMatrix::new(data, option)?)
+    }
+
     #[cfg(feature = "codes_2d")]
     /// DataMatrix
     pub fn data_matrix(&mut self, data: &str) -> Result<&mut Self> {
-        let code = DataMatrix::new(data, DataMatrixOption::default());
-        self.data_matrix_option(data, code.option)
+        self.data_matrix_option(data, DataMatrixOption::default())
     }
 
     #[cfg(feature = "codes_2d")]
-    /// Aztec code
-    pub fn aztec_option(&mut self, data: &str, option: AztecOption) -> Result<&mut Self> {
-        let code = Aztec::new(data, option);
+    /// DataMatrix from bytes with option
+    pub fn data_matrix_from_bytes_option(&mut self, data: &[u8], option: DataMatrixOption) -> Result<&mut Self> {
+        self.data_matrix_builder(DataMatrix::from_bytes(data, option)?)
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// DataMatrix from bytes
+    pub fn data_matrix_from_bytes(&mut self, data: &[u8]) -> Result<&mut Self> {
+        self.data_matrix_from_bytes_option(data, DataMatrixOption::default())
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Construct Aztec code
+    fn aztec_builder(&mut self, code: Aztec) -> Result<&mut Self> {
+        self.check_eci(code.option.eci())?;
+        #[cfg(feature = "graphics")]
+        if self.options.get_software_codes_2d() {
+            let cmd = self.protocol.cancel();
+            self.command("cancel data", &[cmd])?;
+
+            let cmd = self.protocol.aztec_image(&code, self.options.get_printable_width())?;
+            return self.command("print Aztec image", &[cmd]);
+        }
+
         let commands = self.protocol.aztec(&code.data, code.option)?;
         self.command("print Aztec", commands.as_slice())
     }
 
+    #[cfg(feature = "codes_2d")]
+    /// Aztec code
+    pub fn aztec_option(&mut self, data: &str, option: AztecOption) -> Result<&mut Self> {
+        self.aztec_builder(Aztec::new(data, option))
+    }
+
     #[cfg(feature = "codes_2d")]
     /// Aztec code
     pub fn aztec(&mut self, data: &str) -> Result<&mut Self> {
-        let code = Aztec::new(data, AztecOption::default());
-        self.aztec_option(data, code.option)
+        self.aztec_option(data, AztecOption::default())
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Aztec code from bytes with option
+    pub fn aztec_from_bytes_option(&mut self, data: &[u8], option: AztecOption) -> Result<&mut Self> {
+        self.aztec_builder(Aztec::from_bytes(data, option))
+    }
+
+    #[cfg(feature = "codes_2d")]
+    /// Aztec code from bytes
+    pub fn aztec_from_bytes(&mut self, data: &[u8]) -> Result<&mut Self> {
+        self.aztec_from_bytes_option(data, AztecOption::default())
     }
 
     #[cfg(feature = "graphics")]
@@ -569,6 +967,39 @@ impl<D: Driver> Printer<D> {
         self.bit_image_from_bytes_option(bytes, BitImageOption::default())
     }
 
+    #[cfg(feature = "graphics")]
+    /// Print text as image, rendered with a TrueType font
+    ///
+    /// Useful for scripts and symbols not available in the printer page codes.
+    /// The text is wrapped at the [printable width](PrinterOptions::printable_width).
+    pub fn text_image(
+        &mut self,
+        text: &str,
+        font: &TextImageFont,
+        size: f32,
+        justify: JustifyMode,
+    ) -> Result<&mut Self> {
+        let cmd = self.protocol.cancel();
+        self.command("cancel data", &[cmd])?;
+
+        let width = self.options.get_printable_width() / 8 * 8;
+        let cmd = self.protocol.text_image(text, font, size, justify, width)?;
+        self.command("print text image", &[cmd])
+    }
+
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    /// Print barcode as image, rendered by software
+    ///
+    /// Useful for symbologies not supported by the printer, EAN-2/EAN-5 add-ons and ITF-14 bearer bars.
+    pub fn barcode_image(&mut self, barcode: &Barcode, option: &BarcodeImageOption) -> Result<&mut Self> {
+        let cmd = self.protocol.cancel();
+        self.command("cancel data", &[cmd])?;
+
+        let width = self.options.get_printable_width();
+        let cmd = self.protocol.barcode_image(barcode, option, width)?;
+        self.command(&format!("print {} barcode image", barcode.system), &[cmd])
+    }
+
     // #[cfg(feature = "graphics")]
     // /// Print image
     // fn _image(&mut self, path: &str) -> Result<&mut Self> {
@@ -588,6 +1019,7 @@ impl<D: Driver> Printer<D> {
 mod tests {
     use super::*;
     use crate::driver::ConsoleDriver;
+    use crate::testing::MockDriver;
 
     #[test]
     fn test_command() {
@@ -605,4 +1037,272 @@ mod tests {
 
         assert_eq!(printer.instructions, expected);
     }
+
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_qrcode_auto_size() {
+        let mut options = PrinterOptions::default();
+        options.printable_width(200);
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), Some(options));
+
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::M).with_auto_size(true);
+        printer.qrcode_option("https://example.com", option).unwrap();
+        // Version 2 (25 modules and quiet zone): 6 dots per module
+        assert_eq!(printer.instructions[0].commands[1].last(), Some(&6));
+    }
+
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_payment_qrcode() {
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), None);
+        let payment = EpcPayment::new("Red Cross of Belgium", "BE72000000001616")
+            .unwrap()
+            .with_amount(100)
+            .unwrap();
+        printer.payment_qrcode(&payment, 3).unwrap();
+
+        let commands = &printer.instructions[0].commands;
+        // Model 2, size 3, error correction level M
+        assert!(commands[0].ends_with(&[50, 0]));
+        assert_eq!(commands[1].last(), Some(&3));
+        assert_eq!(commands[2].last(), Some(&49));
+        assert!(commands[3].ends_with(payment.payload().unwrap().as_bytes()));
+
+        let creditor = SwissQRBillAddress::new("Robert Schneider AG", "2501", "Biel", "CH").unwrap();
+        let bill = SwissQRBill::new("CH5800791123000889012", creditor, SwissQRBillCurrency::Chf).unwrap();
+        printer.payment_qrcode(&bill, 2).unwrap();
+        #[cfg(feature = "graphics")]
+        assert_eq!(printer.instructions[2].name, "print swiss qrcode image");
+    }
+
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_fiscal_qrcode() {
+        let mut options = PrinterOptions::default();
+        options.printable_width(512);
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), Some(options));
+        let receipt = RksvReceipt::new(
+            "AT0",
+            "DEMO-CASH-BOX524",
+            "366596",
+            FiscalDateTime::new(2015, 12, 17, 11, 23, 44).unwrap(),
+            RksvAmounts::default(),
+            "Dx/9Kmc=",
+            "3D2F6B0E",
+            "Q+2Dc0mH/e0=",
+            RKSV_SIGNATURE_DEVICE_FAILED,
+        )
+        .unwrap();
+        printer.fiscal_qrcode(&receipt).unwrap();
+
+        let commands = &printer.instructions[0].commands;
+        // Model 2, error correction level M
+        assert!(commands[0].ends_with(&[50, 0]));
+        assert!(commands[1].last().is_some_and(|size| *size > 0));
+        assert_eq!(commands[2].last(), Some(&49));
+        assert!(commands[3].ends_with(receipt.payload().unwrap().as_bytes()));
+    }
+
+    #[test]
+    fn test_print_to_spool() {
+        let dir = std::env::temp_dir().join(format!("escpos-printer-spool-{}", std::process::id()));
+        let spool = Spool::open(&dir).unwrap();
+        let driver = MockDriver::new();
+        let mut printer = Printer::new(driver.clone(), Protocol::default(), None);
+        let id = printer.init().unwrap().print_to_spool(&spool, "job").unwrap();
+
+        assert!(printer.instructions.is_empty());
+        assert!(driver.written().is_empty());
+        assert_eq!(spool.data(id).unwrap(), vec![27, 64]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_2d_codes_from_bytes() {
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), None);
+        let data = [0x00, 0xFF, 0x80];
+
+        printer
+            .qrcode_from_bytes(&data)
+            .unwrap()
+            .pdf417_from_bytes(&data)
+            .unwrap()
+            .maxi_code_from_bytes(&data)
+            .unwrap()
+            .data_matrix_from_bytes(&data)
+            .unwrap()
+            .aztec_from_bytes(&data)
+            .unwrap();
+        assert!(printer
+            .instructions
+            .iter()
+            .all(|i| i.commands.iter().any(|cmd| cmd.ends_with(&[80, 48, 0x00, 0xFF, 0x80]))));
+
+        // ECI designators need software 2D codes
+        let option = DataMatrixOption::default().with_eci(26).unwrap();
+        assert!(printer.data_matrix_from_bytes_option(&data, option).is_err());
+        let option = Pdf417Option::default().with_eci(26).unwrap();
+        assert!(printer.pdf417_option("PDF417", option).is_err());
+        let option = DataMatrixOption::default().with_gs1(true);
+        assert!(printer.data_matrix_option("(10)ABC123", option).is_err());
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    #[test]
+    fn test_software_codes_2d_eci() {
+        let mut options = PrinterOptions::default();
+        options.software_codes_2d(true);
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), Some(options));
+
+        let option = QRCodeOption::new(QRCodeModel::Model2, 4, QRCodeCorrectionLevel::M)
+            .with_eci(20)
+            .unwrap();
+        let shift_jis = [0x93, 0xFA, 0x96, 0x7B];
+        printer.qrcode_from_bytes_option(&shift_jis, option).unwrap();
+        let option = AztecOption::default().with_eci(20).unwrap();
+        printer.aztec_from_bytes_option(&shift_jis, option).unwrap();
+        let option = Pdf417Option::default().with_eci(20).unwrap();
+        printer.pdf417_from_bytes_option(&shift_jis, option).unwrap();
+        let option = DataMatrixOption::default().with_gs1(true);
+        printer
+            .data_matrix_option("(01)09501101530003(10)ABC123", option)
+            .unwrap();
+        assert_eq!(printer.instructions.len(), 8);
+    }
+
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    #[test]
+    fn test_software_barcodes() {
+        let mut options = PrinterOptions::default();
+        options.software_barcodes(vec![BarcodeSystem::CODE93], None);
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), Some(options));
+
+        printer.code93("ABC").unwrap().code39("ABC").unwrap();
+        let names: Vec<&str> = printer.instructions.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["cancel data", "print CODE93 barcode image", "print CODE39 barcode"]
+        );
+        assert_eq!(&printer.instructions[1].commands[0][..4], &[29, 118, 48, 0]);
+
+        let barcode = Barcode::new(BarcodeSystem::CODE128, &"A".repeat(40), BarcodeOption::default()).unwrap();
+        assert!(printer
+            .barcode_image(&barcode, &BarcodeImageOption::new(3, 50).unwrap())
+            .is_err());
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    #[test]
+    fn test_software_codes_2d() {
+        let mut options = PrinterOptions::default();
+        options.software_codes_2d(true);
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), Some(options));
+
+        printer
+            .qrcode("ABC")
+            .unwrap()
+            .data_matrix("ABC")
+            .unwrap()
+            .aztec("ABC")
+            .unwrap()
+            .pdf417("ABC")
+            .unwrap();
+        let names: Vec<&str> = printer.instructions.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "cancel data",
+                "print qrcode image",
+                "cancel data",
+                "print DataMatrix image",
+                "cancel data",
+                "print Aztec image",
+                "cancel data",
+                "print PDF417 image"
+            ]
+        );
+        assert!(printer
+            .instructions
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .all(|i| i.commands[0][..4] == [29, 118, 48, 0]));
+
+        assert!(printer
+            .qrcode_option(
+                "ABC",
+                QRCodeOption::new(QRCodeModel::Model1, 4, QRCodeCorrectionLevel::L)
+            )
+            .is_err());
+        assert!(printer.composite("9501101530003", "(10)ABC").is_err());
+        assert!(printer.qrcode(&"A".repeat(1500)).is_err());
+
+        #[cfg(feature = "barcodes")]
+        {
+            let option = CompositeOption::new(CompositeLinearType::GS1128, 2, CompositeFont::A).unwrap();
+            printer
+                .composite_option("(01)09501101530003", "(10)ABC", option)
+                .unwrap();
+            assert_eq!(printer.instructions.last().unwrap().name, "print composite image");
+        }
+    }
+
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_composite() {
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), None);
+        printer
+            .composite("9501101530003", "(17)250101(10)ABC123")
+            .unwrap()
+            .composite_option(
+                "(01)09501101530003",
+                "(10)ABC123",
+                CompositeOption::new(CompositeLinearType::GS1128, 2, CompositeFont::None).unwrap(),
+            )
+            .unwrap();
+        let names: Vec<&str> = printer.instructions.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["print EAN13 composite", "print GS1-128 composite"]);
+
+        assert!(printer.composite("9501101530004", "(10)ABC123").is_err());
+        assert!(printer
+            .composite(
+                "9501101530003",
+                &format!("(91){}(92){}", "a".repeat(90), "b".repeat(90))
+            )
+            .is_err());
+    }
+
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    #[test]
+    fn test_macro_pdf417() {
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), None);
+        let option = Pdf417Option::new(5, 10, 2, 3, Pdf417Type::Standard, Pdf417CorrectionLevel::Level1).unwrap();
+        let macro_option = MacroPdf417Option::new("017053").unwrap();
+        printer.macro_pdf417(&"A".repeat(80), option, macro_option).unwrap();
+
+        let names: Vec<&str> = printer.instructions.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["print Macro PDF417 segment 1/2", "print Macro PDF417 segment 2/2"]
+        );
+        assert!(printer
+            .instructions
+            .iter()
+            .all(|i| i.commands[0][..4] == [29, 118, 48, 0]));
+    }
+
+    #[cfg(feature = "codes_2d")]
+    #[test]
+    fn test_maxi_code_carrier_message() {
+        let mut printer = Printer::new(ConsoleDriver::open(false), Protocol::default(), None);
+        let message = MaxiCodeCarrierMessage::new("B1050", 56, 1).with_tracking_number("1Z00004951");
+        printer.maxi_code_carrier_message(&message).unwrap();
+        // Mode 3
+        assert_eq!(printer.instructions[0].commands[0], vec![29, 40, 107, 3, 0, 50, 65, 51]);
+
+        let message = MaxiCodeCarrierMessage::new("B1050-123", 56, 1);
+        assert!(printer.maxi_code_carrier_message(&message).is_err());
+    }
 }
@@ -1,5 +1,7 @@
 //! Printer options
 
+#[cfg(all(feature = "graphics", feature = "barcodes"))]
+use crate::domain::{BarcodeSystem, TextImageFont};
 use crate::domain::{DebugMode, PageCode};
 
 /// Printer options
@@ -13,6 +15,21 @@ pub struct PrinterOptions {
 
     /// Number of characters per line (default: 42)
     characters_per_line: u8,
+
+    /// Printable width in dots (default: 512)
+    printable_width: u32,
+
+    /// Barcode systems printed as images (default: none)
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    software_barcodes: Vec<BarcodeSystem>,
+
+    /// Font and size of the HRI characters of barcodes printed as images
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    software_barcodes_hri: Option<(TextImageFont, f32)>,
+
+    /// Print QR codes, DataMatrix and Aztec codes as images (default: false)
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    software_codes_2d: bool,
 }
 
 impl Default for PrinterOptions {
@@ -27,12 +44,20 @@ impl Default for PrinterOptions {
     /// assert_eq!(options.get_page_code(), None);
     /// assert_eq!(options.get_debug_mode(), None);
     /// assert_eq!(options.get_characters_per_line(), 42);
+    /// assert_eq!(options.get_printable_width(), 512);
     /// ```
     fn default() -> Self {
         Self {
             page_code: None,
             debug_mode: None,
             characters_per_line: 42,
+            printable_width: 512,
+            #[cfg(all(feature = "graphics", feature = "barcodes"))]
+            software_barcodes: Vec::new(),
+            #[cfg(all(feature = "graphics", feature = "barcodes"))]
+            software_barcodes_hri: None,
+            #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+            software_codes_2d: false,
         }
     }
 }
@@ -55,6 +80,13 @@ impl PrinterOptions {
             page_code,
             characters_per_line,
             debug_mode,
+            printable_width: 512,
+            #[cfg(all(feature = "graphics", feature = "barcodes"))]
+            software_barcodes: Vec::new(),
+            #[cfg(all(feature = "graphics", feature = "barcodes"))]
+            software_barcodes_hri: None,
+            #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+            software_codes_2d: false,
         }
     }
 
@@ -116,4 +148,80 @@ impl PrinterOptions {
     pub fn debug_mode(&mut self, debug_mode: Option<DebugMode>) {
         self.debug_mode = debug_mode;
     }
+
+    /// Get the printable width in dots
+    pub fn get_printable_width(&self) -> u32 {
+        self.printable_width
+    }
+
+    /// Set the printable width in dots (576 for most 80mm printers, 384 for 58mm printers)
+    ///
+    /// ```
+    /// use escpos::printer_options::PrinterOptions;
+    ///
+    /// let mut printer_options = PrinterOptions::default();
+    /// printer_options.printable_width(576);
+    ///
+    /// assert_eq!(printer_options.get_printable_width(), 576);
+    /// ```
+    pub fn printable_width(&mut self, printable_width: u32) {
+        self.printable_width = printable_width;
+    }
+
+    /// Get the barcode systems printed as images
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    pub fn get_software_barcodes(&self) -> &[BarcodeSystem] {
+        &self.software_barcodes
+    }
+
+    /// Get the font and size of the HRI characters of barcodes printed as images
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    pub fn get_software_barcodes_hri(&self) -> Option<&(TextImageFont, f32)> {
+        self.software_barcodes_hri.as_ref()
+    }
+
+    /// Set the barcode systems printed as images, for printers not supporting them
+    ///
+    /// The HRI characters are drawn below the bars with the font and size `hri`, if provided
+    /// and if the barcode HRI position is not `None`.
+    ///
+    /// ```
+    /// use escpos::printer_options::PrinterOptions;
+    /// use escpos::utils::BarcodeSystem;
+    ///
+    /// let mut printer_options = PrinterOptions::default();
+    /// printer_options.software_barcodes(vec![BarcodeSystem::CODE93, BarcodeSystem::CODE128], None);
+    ///
+    /// assert_eq!(printer_options.get_software_barcodes(), [BarcodeSystem::CODE93, BarcodeSystem::CODE128]);
+    /// ```
+    #[cfg(all(feature = "graphics", feature = "barcodes"))]
+    pub fn software_barcodes(&mut self, systems: Vec<BarcodeSystem>, hri: Option<(TextImageFont, f32)>) {
+        self.software_barcodes = systems;
+        self.software_barcodes_hri = hri;
+    }
+
+    /// Get whether 2D codes are printed as images
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    pub fn get_software_codes_2d(&self) -> bool {
+        self.software_codes_2d
+    }
+
+    /// Print QR codes, DataMatrix, Aztec codes and PDF417 as images, for printers not supporting the `GS ( k` command
+    ///
+    /// GS1 Composite codes are printed as images with a GS1-128 linear element and the `barcodes` feature.
+    /// QR code Model 1 and the other composite codes cannot be printed as images: printing them returns an error
+    /// when enabled, QR codes printed with the default option use Model 2.
+    ///
+    /// ```
+    /// use escpos::printer_options::PrinterOptions;
+    ///
+    /// let mut printer_options = PrinterOptions::default();
+    /// printer_options.software_codes_2d(true);
+    ///
+    /// assert!(printer_options.get_software_codes_2d());
+    /// ```
+    #[cfg(all(feature = "graphics", feature = "codes_2d"))]
+    pub fn software_codes_2d(&mut self, enabled: bool) {
+        self.software_codes_2d = enabled;
+    }
 }
@@ -0,0 +1,488 @@
+//! Content-based job routing to stations (kitchen, bar, receipt...)
+//!
+//! The sections of a document are routed to stations by category. Each station receives one job, printed with
+//! the shared header and the sections routed to it.
+
+use crate::{
+    driver::Driver,
+    errors::{PrinterError, Result},
+    printer::Printer,
+    printer_options::PrinterOptions,
+    utils::{JustifyMode, Protocol, UnderlineMode},
+};
+use log::warn;
+use std::rc::Rc;
+
+/// Shared header of the jobs of a document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JobHeader {
+    pub table: String,
+    pub server: String,
+    pub time: String,
+}
+
+impl JobHeader {
+    /// Create a new `JobHeader`
+    pub fn new(table: &str, server: &str, time: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            server: server.to_string(),
+            time: time.to_string(),
+        }
+    }
+}
+
+/// Header of a station job, given to the header formatter
+#[derive(Debug, Clone, Copy)]
+pub struct StationJobHeader<'a> {
+    /// Station name
+    pub station: &'a str,
+    /// Shared header of the document
+    pub header: &'a JobHeader,
+    /// Copy number (from 1)
+    pub copy: u8,
+    /// Number of copies printed on the station
+    pub copies: u8,
+}
+
+/// Header lines of a station job: the first line is the title (bold, double size and centered)
+pub type HeaderFormatter = dyn Fn(&StationJobHeader) -> Vec<String>;
+
+/// Default header: station name in upper case, table, server, time and copy number (with several copies)
+fn default_header(job: &StationJobHeader) -> Vec<String> {
+    let mut lines = vec![
+        job.station.to_uppercase(),
+        format!("Table: {}", job.header.table),
+        format!("Server: {}", job.header.server),
+        format!("Time: {}", job.header.time),
+    ];
+    if job.copies > 1 {
+        lines.push(format!("Copy {}/{}", job.copy, job.copies));
+    }
+    lines
+}
+
+/// Document section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSection {
+    pub category: String,
+    pub lines: Vec<String>,
+}
+
+/// Document made of sections with a category (starters, drinks...)
+#[derive(Debug, Clone, Default)]
+pub struct RoutedDocument {
+    header: JobHeader,
+    sections: Vec<DocumentSection>,
+}
+
+impl RoutedDocument {
+    /// Create a new `RoutedDocument`
+    pub fn new(header: JobHeader) -> Self {
+        Self {
+            header,
+            sections: vec![],
+        }
+    }
+
+    /// Add a section
+    pub fn with_section(mut self, category: &str, lines: &[&str]) -> Self {
+        self.sections.push(DocumentSection {
+            category: category.to_string(),
+            lines: lines.iter().map(|line| line.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Get header
+    pub fn header(&self) -> &JobHeader {
+        &self.header
+    }
+
+    /// Get sections
+    pub fn sections(&self) -> &[DocumentSection] {
+        &self.sections
+    }
+}
+
+/// Print result of a station
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StationReport {
+    /// Station name
+    pub station: String,
+    /// Categories printed on the station
+    pub categories: Vec<String>,
+    /// Error message if the job failed
+    pub error: Option<String>,
+}
+
+impl StationReport {
+    /// Was the job printed?
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Routing report of a document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouterReport {
+    /// One report per station receiving a job
+    pub stations: Vec<StationReport>,
+    /// Categories without route (not printed)
+    pub unrouted: Vec<String>,
+}
+
+impl RouterReport {
+    /// Were all the sections printed?
+    pub fn is_success(&self) -> bool {
+        self.unrouted.is_empty() && self.stations.iter().all(StationReport::is_success)
+    }
+
+    /// Get the report of a station
+    pub fn station(&self, name: &str) -> Option<&StationReport> {
+        self.stations.iter().find(|report| report.station == name)
+    }
+}
+
+/// Driver shared by the jobs of a station
+#[derive(Clone)]
+struct StationDriver(Rc<dyn Driver>);
+
+impl Driver for StationDriver {
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        self.0.write(data)
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.0.read(buf)
+    }
+
+    fn flush(&self) -> Result<()> {
+        self.0.flush()
+    }
+}
+
+struct Station {
+    name: String,
+    driver: StationDriver,
+    copies: u8,
+}
+
+/// Router sending the sections of a document to stations, by category
+///
+/// # Example
+///
+#[cfg_attr(feature = "testing", doc = "```rust")]
+#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+/// use escpos::router::*;
+/// use escpos::testing::MockDriver;
+/// use escpos::utils::*;
+///
+/// let (kitchen, bar) = (MockDriver::new(), MockDriver::new());
+/// let router = JobRouter::new(Protocol::default(), None)
+///     .with_station("kitchen", kitchen.clone(), 2)
+///     .unwrap()
+///     .with_station("bar", bar.clone(), 1)
+///     .unwrap()
+///     .with_route("starters", "kitchen")
+///     .unwrap()
+///     .with_route("mains", "kitchen")
+///     .unwrap()
+///     .with_route("drinks", "bar")
+///     .unwrap();
+///
+/// let document = RoutedDocument::new(JobHeader::new("12", "Alice", "19:42"))
+///     .with_section("starters", &["1x Soup"])
+///     .with_section("drinks", &["2x Lemonade"]);
+/// let report = router.route(&document);
+///
+/// assert!(report.is_success());
+/// assert_eq!(report.station("kitchen").unwrap().categories, vec!["starters"]);
+/// assert!(!bar.written().is_empty());
+/// ```
+pub struct JobRouter {
+    protocol: Protocol,
+    options: Option<PrinterOptions>,
+    stations: Vec<Station>,
+    routes: Vec<(String, usize)>,
+    default_station: Option<usize>,
+    header_formatter: Rc<HeaderFormatter>,
+}
+
+impl JobRouter {
+    /// Create a new `JobRouter`, jobs are printed with the protocol and the options
+    pub fn new(protocol: Protocol, options: Option<PrinterOptions>) -> Self {
+        Self {
+            protocol,
+            options,
+            stations: vec![],
+            routes: vec![],
+            default_station: None,
+            header_formatter: Rc::new(default_header),
+        }
+    }
+
+    /// Add a station printing `copies` copies of each job (1 - 10)
+    pub fn with_station<D: Driver + 'static>(mut self, name: &str, driver: D, copies: u8) -> Result<Self> {
+        if self.station_index(name).is_some() {
+            return Err(PrinterError::Input(format!("duplicate station: {name}")));
+        }
+        if !(1..=10).contains(&copies) {
+            return Err(PrinterError::Input(format!(
+                "invalid number of copies: {copies} (1 - 10)"
+            )));
+        }
+        self.stations.push(Station {
+            name: name.to_string(),
+            driver: StationDriver(Rc::new(driver)),
+            copies,
+        });
+        Ok(self)
+    }
+
+    /// Route a category to a station
+    pub fn with_route(mut self, category: &str, station: &str) -> Result<Self> {
+        let index = self.existing_station(station)?;
+        self.routes.retain(|(c, _)| c != category);
+        self.routes.push((category.to_string(), index));
+        Ok(self)
+    }
+
+    /// Route the categories without route to a station
+    pub fn with_default_station(mut self, station: &str) -> Result<Self> {
+        self.default_station = Some(self.existing_station(station)?);
+        Ok(self)
+    }
+
+    /// Set the header formatter (labels, language...), replacing the default English header
+    ///
+    /// The first line is printed as title (bold, double size and centered), the other lines below it.
+    pub fn with_header_formatter<F: Fn(&StationJobHeader) -> Vec<String> + 'static>(mut self, formatter: F) -> Self {
+        self.header_formatter = Rc::new(formatter);
+        self
+    }
+
+    fn station_index(&self, name: &str) -> Option<usize> {
+        self.stations.iter().position(|station| station.name == name)
+    }
+
+    fn existing_station(&self, name: &str) -> Result<usize> {
+        self.station_index(name)
+            .ok_or_else(|| PrinterError::Input(format!("unknown station: {name}")))
+    }
+
+    fn route_index(&self, category: &str) -> Option<usize> {
+        self.routes
+            .iter()
+            .find(|(c, _)| c == category)
+            .map(|(_, index)| *index)
+            .or(self.default_station)
+    }
+
+    /// Get the station of a category
+    pub fn station_of(&self, category: &str) -> Option<&str> {
+        self.route_index(category)
+            .map(|index| self.stations[index].name.as_str())
+    }
+
+    /// Print the sections of a document on their stations, one job per station
+    ///
+    /// A failing station does not prevent the other stations from printing.
+    pub fn route(&self, document: &RoutedDocument) -> RouterReport {
+        let mut report = RouterReport::default();
+        let mut jobs: Vec<(usize, Vec<&DocumentSection>)> = vec![];
+
+        for section in &document.sections {
+            match self.route_index(&section.category) {
+                Some(index) => match jobs.iter_mut().find(|(i, _)| *i == index) {
+                    Some((_, sections)) => sections.push(section),
+                    None => jobs.push((index, vec![section])),
+                },
+                None if !report.unrouted.contains(&section.category) => {
+                    warn!("[router] no station for category {}", section.category);
+                    report.unrouted.push(section.category.clone());
+                }
+                None => (),
+            }
+        }
+
+        for (index, sections) in jobs {
+            let station = &self.stations[index];
+            let mut categories: Vec<String> = vec![];
+            for section in &sections {
+                if !categories.contains(&section.category) {
+                    categories.push(section.category.clone());
+                }
+            }
+
+            let error = self.print_job(station, &document.header, &sections).err().map(|e| {
+                warn!("[router] station {} failed: {e}", station.name);
+                e.to_string()
+            });
+            report.stations.push(StationReport {
+                station: station.name.clone(),
+                categories,
+                error,
+            });
+        }
+
+        report
+    }
+
+    /// Print the job of a station: header and sections, for each copy
+    fn print_job(&self, station: &Station, header: &JobHeader, sections: &[&DocumentSection]) -> Result<()> {
+        let mut printer = Printer::new(station.driver.clone(), self.protocol.clone(), self.options.clone());
+        printer.init()?;
+
+        for copy in 1..=station.copies {
+            let lines = (self.header_formatter)(&StationJobHeader {
+                station: &station.name,
+                header,
+                copy,
+                copies: station.copies,
+            });
+            if let Some((title, lines)) = lines.split_first() {
+                printer
+                    .justify(JustifyMode::CENTER)?
+                    .bold(true)?
+                    .size(2, 2)?
+                    .writeln(title)?
+                    .reset_size()?
+                    .bold(false)?
+                    .justify(JustifyMode::LEFT)?;
+                for line in lines {
+                    printer.writeln(line)?;
+                }
+            }
+
+            for section in sections {
+                printer
+                    .feed()?
+                    .bold(true)?
+                    .underline(UnderlineMode::Single)?
+                    .writeln(&section.category)?
+                    .underline(UnderlineMode::None)?
+                    .bold(false)?;
+                for line in &section.lines {
+                    printer.writeln(line)?;
+                }
+            }
+            printer.feeds(3)?.cut()?;
+        }
+
+        printer.print()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockDriver, MockOperation};
+
+    fn router(kitchen: &MockDriver, bar: &MockDriver) -> JobRouter {
+        JobRouter::new(Protocol::default(), None)
+            .with_station("kitchen", kitchen.clone(), 1)
+            .unwrap()
+            .with_station("bar", bar.clone(), 2)
+            .unwrap()
+            .with_route("mains", "kitchen")
+            .unwrap()
+            .with_route("drinks", "bar")
+            .unwrap()
+    }
+
+    fn document() -> RoutedDocument {
+        RoutedDocument::new(JobHeader::new("12", "Alice", "19:42"))
+            .with_section("mains", &["1x Steak", "  medium rare"])
+            .with_section("drinks", &["2x Lemonade"])
+            .with_section("desserts", &["1x Tart"])
+            .with_section("mains", &["1x Risotto"])
+    }
+
+    fn count(data: &[u8], pattern: &[u8]) -> usize {
+        data.windows(pattern.len()).filter(|w| *w == pattern).count()
+    }
+
+    #[test]
+    fn test_router_configuration() {
+        let (kitchen, bar) = (MockDriver::new(), MockDriver::new());
+        let router = router(&kitchen, &bar);
+        assert_eq!(router.station_of("drinks"), Some("bar"));
+        assert_eq!(router.station_of("desserts"), None);
+        assert!(router.with_route("desserts", "pastry").is_err());
+
+        let router = JobRouter::new(Protocol::default(), None)
+            .with_station("kitchen", MockDriver::new(), 1)
+            .unwrap();
+        assert!(router.with_station("kitchen", MockDriver::new(), 1).is_err());
+        let router = JobRouter::new(Protocol::default(), None);
+        assert!(router.with_station("bar", MockDriver::new(), 0).is_err());
+    }
+
+    #[test]
+    fn test_router_route() {
+        let (kitchen, bar) = (MockDriver::new(), MockDriver::new());
+        let report = router(&kitchen, &bar).route(&document());
+
+        assert!(!report.is_success());
+        assert_eq!(report.unrouted, vec!["desserts"]);
+        assert_eq!(report.station("kitchen").unwrap().categories, vec!["mains"]);
+        assert!(report.station("bar").unwrap().is_success());
+
+        // One job (one flush) per station
+        assert_eq!((kitchen.flush_count(), bar.flush_count()), (1, 1));
+        let kitchen = kitchen.written();
+        assert_eq!(count(&kitchen, b"Table: 12"), 1);
+        assert_eq!(count(&kitchen, b"1x Steak"), 1);
+        assert_eq!(count(&kitchen, b"1x Risotto"), 1);
+        assert_eq!(count(&kitchen, b"Lemonade"), 0);
+
+        // Two copies on the bar station
+        let bar = bar.written();
+        assert_eq!(count(&bar, b"Server: Alice"), 2);
+        assert_eq!(count(&bar, b"Copy 2/2"), 1);
+        assert_eq!(count(&bar, &[0x1D, 0x56, 0x41]), 2);
+    }
+
+    #[test]
+    fn test_router_default_station_and_failure() {
+        let kitchen = MockDriver::new().with_error(MockOperation::Flush, 1, "paper jam");
+        let bar = MockDriver::new();
+        let router = router(&kitchen, &bar).with_default_station("bar").unwrap();
+        let report = router.route(&document());
+
+        assert!(report.unrouted.is_empty());
+        assert_eq!(report.station("bar").unwrap().categories, vec!["drinks", "desserts"]);
+        assert!(report.station("bar").unwrap().is_success());
+        assert_eq!(
+            report.station("kitchen").unwrap().error.as_deref(),
+            Some("IO error: paper jam")
+        );
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn test_router_header_formatter() {
+        let (kitchen, bar) = (MockDriver::new(), MockDriver::new());
+        let router = router(&kitchen, &bar).with_header_formatter(|job| {
+            vec![
+                format!("Poste {}", job.station),
+                format!("Table {} - {}", job.header.table, job.header.server),
+                format!("Exemplaire {}/{}", job.copy, job.copies),
+            ]
+        });
+        router.route(&document());
+
+        let bar = bar.written();
+        assert_eq!(count(&bar, b"Poste bar"), 2);
+        assert_eq!(count(&bar, b"Table 12 - Alice"), 2);
+        assert_eq!(count(&bar, b"Exemplaire 2/2"), 1);
+        assert_eq!(count(&bar, b"Server:"), 0);
+        assert_eq!(count(&kitchen.written(), b"Exemplaire 1/1"), 1);
+    }
+}
@@ -0,0 +1,854 @@
+//! Persistent spool queue
+//!
+//! Jobs are stored in a local directory (data and metadata files), so they survive process restarts.
+//! They are delivered with retry and backoff, by [`Spool::deliver_pending`](crate::spool::Spool::deliver_pending) or a
+//! background [`SpoolWorker`](crate::spool::SpoolWorker).
+
+use crate::{
+    driver::Driver,
+    errors::{PrinterError, Result},
+};
+use log::{debug, warn};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default maximum number of delivery attempts
+const DEFAULT_MAX_ATTEMPTS: u32 = 10;
+
+/// Default delay before the first retry, in seconds
+const DEFAULT_BACKOFF_SECONDS: u64 = 5;
+
+/// Default maximum delay between two attempts, in seconds
+const DEFAULT_MAX_BACKOFF_SECONDS: u64 = 300;
+
+/// Duration of a delivery claim, in seconds: an interrupted delivery can be resumed after this delay
+const DELIVERY_LEASE_SECONDS: u64 = 600;
+
+/// Data file extension
+const DATA_EXTENSION: &str = "bin";
+
+/// Metadata file extension
+const METADATA_EXTENSION: &str = "job";
+
+/// Lock file serializing the spool updates between threads and processes
+const LOCK_FILE: &str = "spool.lock";
+
+/// File containing the next job ID, so that IDs are never reused
+const NEXT_ID_FILE: &str = "next_id";
+
+/// Current time in milliseconds since the Unix epoch
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Write a file atomically (written to a temporary file first, then renamed)
+fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Escape a metadata value (backslashes and line breaks)
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+/// Unescape a metadata value
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(escaped) => result.push(escaped),
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+/// Spooled job status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpoolJobStatus {
+    /// Waiting for delivery (or for the next attempt)
+    Pending,
+    /// Being delivered (claimed until `next_attempt_at`)
+    InProgress,
+    /// Delivered to the printer
+    Printed,
+    /// Maximum number of attempts reached
+    Failed,
+    /// Cancelled before delivery
+    Cancelled,
+}
+
+impl SpoolJobStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::InProgress => "in_progress",
+            Self::Printed => "printed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Pending,
+            Self::InProgress,
+            Self::Printed,
+            Self::Failed,
+            Self::Cancelled,
+        ]
+        .into_iter()
+        .find(|status| status.name() == name)
+    }
+}
+
+/// Spooled job metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpoolJob {
+    /// Job ID (increasing, never reused)
+    pub id: u64,
+    /// Job name
+    pub name: String,
+    /// Creation time (milliseconds since the Unix epoch)
+    pub created_at: u64,
+    /// Status
+    pub status: SpoolJobStatus,
+    /// Number of delivery attempts
+    pub attempts: u32,
+    /// Time of the next attempt, or end of the delivery claim when in progress (milliseconds since the Unix epoch)
+    pub next_attempt_at: u64,
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+}
+
+impl SpoolJob {
+    /// Is the job waiting for delivery (pending, or in progress with an expired claim)?
+    fn is_pending(&self, now: u64) -> bool {
+        match self.status {
+            SpoolJobStatus::Pending => true,
+            SpoolJobStatus::InProgress => self.next_attempt_at <= now,
+            _ => false,
+        }
+    }
+
+    /// Metadata file content: one `key=value` line per field
+    fn to_text(&self) -> String {
+        let mut text = format!(
+            "id={}\nname={}\ncreated_at={}\nstatus={}\nattempts={}\nnext_attempt_at={}\n",
+            self.id,
+            escape(&self.name),
+            self.created_at,
+            self.status.name(),
+            self.attempts,
+            self.next_attempt_at
+        );
+        if let Some(error) = &self.last_error {
+            text.push_str(&format!("last_error={}\n", escape(error)));
+        }
+        text
+    }
+
+    fn from_text(text: &str) -> Result<Self> {
+        let fields: HashMap<&str, &str> = text.lines().filter_map(|line| line.split_once('=')).collect();
+        let invalid = |field: &str| PrinterError::Input(format!("invalid spool job metadata: {field}"));
+        let field = |name: &str| fields.get(name).copied().ok_or_else(|| invalid(name));
+        let number = |name: &str| field(name)?.parse::<u64>().map_err(|_| invalid(name));
+
+        Ok(Self {
+            id: number("id")?,
+            name: unescape(field("name")?),
+            created_at: number("created_at")?,
+            status: SpoolJobStatus::from_name(field("status")?).ok_or_else(|| invalid("status"))?,
+            attempts: u32::try_from(number("attempts")?)?,
+            next_attempt_at: number("next_attempt_at")?,
+            last_error: fields.get("last_error").map(|error| unescape(error)),
+        })
+    }
+}
+
+/// Persistent spool queue stored in a directory
+///
+/// The spool can be shared between threads ([cloned](Clone)) and processes: updates are serialized
+/// with a lock file and job IDs are never reused.
+///
+/// # Example
+///
+#[cfg_attr(feature = "testing", doc = "```rust")]
+#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+/// use escpos::printer::Printer;
+/// use escpos::spool::*;
+/// use escpos::testing::MockDriver;
+/// use escpos::utils::*;
+/// use escpos::errors::Result;
+///
+/// fn main() -> Result<()> {
+///     let dir = std::env::temp_dir().join(format!("escpos-spool-doc-{}", std::process::id()));
+///     let spool = Spool::open(&dir)?;
+///
+///     let id = Printer::new(MockDriver::new(), Protocol::default(), None)
+///         .init()?
+///         .writeln("Receipt")?
+///         .print_to_spool(&spool, "receipt 42")?;
+///
+///     let driver = MockDriver::new();
+///     assert_eq!(spool.deliver_pending(&driver)?, vec![id]);
+///     assert_eq!(spool.job(id)?.status, SpoolJobStatus::Printed);
+///     # std::fs::remove_dir_all(&dir)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Spool {
+    dir: PathBuf,
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Spool {
+    /// Open a spool directory (created if needed)
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            backoff: Duration::from_secs(DEFAULT_BACKOFF_SECONDS),
+            max_backoff: Duration::from_secs(DEFAULT_MAX_BACKOFF_SECONDS),
+        })
+    }
+
+    /// Set the maximum number of delivery attempts (> 0), the job is then failed
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Result<Self> {
+        if max_attempts == 0 {
+            return Err(PrinterError::Input(
+                "maximum number of attempts must be greater than 0".to_string(),
+            ));
+        }
+        self.max_attempts = max_attempts;
+        Ok(self)
+    }
+
+    /// Set the delay before the first retry, doubled after each attempt up to `max_backoff`
+    pub fn with_backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff.max(backoff);
+        self
+    }
+
+    /// Get the spool directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, id: u64, extension: &str) -> PathBuf {
+        self.dir.join(format!("{id:020}.{extension}"))
+    }
+
+    /// Lock the spool until the returned file is dropped
+    ///
+    /// The lock must not be taken twice by the same thread (the second call would wait forever).
+    fn lock(&self) -> Result<File> {
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.dir.join(LOCK_FILE))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    /// Reserve the next job ID (the lock must be held)
+    fn next_id(&self) -> Result<u64> {
+        let path = self.dir.join(NEXT_ID_FILE);
+        let id = match fs::read_to_string(&path) {
+            Ok(content) => content
+                .trim()
+                .parse()
+                .map_err(|_| PrinterError::Input(format!("invalid spool job counter: {content}")))?,
+            // New spool: start after the remaining job files
+            Err(e) if e.kind() == io::ErrorKind::NotFound => self
+                .file_ids(&[DATA_EXTENSION, METADATA_EXTENSION])?
+                .last()
+                .map_or(1, |id| id + 1),
+            Err(e) => return Err(e.into()),
+        };
+        write_atomic(&path, (id + 1).to_string().as_bytes())?;
+        Ok(id)
+    }
+
+    /// Write the job metadata (the lock must be held)
+    fn save(&self, job: &SpoolJob) -> Result<()> {
+        write_atomic(&self.path(job.id, METADATA_EXTENSION), job.to_text().as_bytes())
+    }
+
+    /// Read, modify and write the job metadata while holding the lock, returns the updated job
+    fn update<F>(&self, id: u64, f: F) -> Result<SpoolJob>
+    where
+        F: FnOnce(&mut SpoolJob) -> Result<()>,
+    {
+        let _lock = self.lock()?;
+        let mut job = self.job(id)?;
+        f(&mut job)?;
+        self.save(&job)?;
+        Ok(job)
+    }
+
+    /// Add a job to the queue, returns the job ID
+    pub fn enqueue(&self, name: &str, data: &[u8]) -> Result<u64> {
+        let _lock = self.lock()?;
+        let id = self.next_id()?;
+        let now = now_millis();
+
+        // The data is written first, a job exists once its metadata is written
+        File::options()
+            .write(true)
+            .create_new(true)
+            .open(self.path(id, DATA_EXTENSION))?
+            .write_all(data)?;
+        self.save(&SpoolJob {
+            id,
+            name: name.to_string(),
+            created_at: now,
+            status: SpoolJobStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+        })?;
+        debug!("[spool] job {id} ({name}) enqueued, {} bytes", data.len());
+
+        Ok(id)
+    }
+
+    /// IDs of the job files with one of the extensions, in increasing order
+    fn file_ids(&self, extensions: &[&str]) -> Result<Vec<u64>> {
+        let mut ids = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|e| extensions.iter().any(|extension| e == *extension))
+            {
+                if let Some(id) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse().ok()) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(ids)
+    }
+
+    /// List the jobs, oldest first
+    pub fn jobs(&self) -> Result<Vec<SpoolJob>> {
+        self.file_ids(&[METADATA_EXTENSION])?
+            .into_iter()
+            .map(|id| self.job(id))
+            .collect()
+    }
+
+    /// Get a job
+    pub fn job(&self, id: u64) -> Result<SpoolJob> {
+        let text = fs::read_to_string(self.path(id, METADATA_EXTENSION))
+            .map_err(|e| PrinterError::Input(format!("unknown spool job {id}: {e}")))?;
+        SpoolJob::from_text(&text)
+    }
+
+    /// Get the data of a job
+    pub fn data(&self, id: u64) -> Result<Vec<u8>> {
+        Ok(fs::read(self.path(id, DATA_EXTENSION))?)
+    }
+
+    /// Cancel a pending or failed job
+    pub fn cancel(&self, id: u64) -> Result<()> {
+        self.update(id, |job| {
+            if job.status == SpoolJobStatus::Printed {
+                return Err(PrinterError::Input(format!("spool job {id} is already printed")));
+            }
+            job.status = SpoolJobStatus::Cancelled;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Queue a job again (printed, failed or cancelled), as a new job
+    pub fn reprint(&self, id: u64) -> Result<u64> {
+        let job = self.job(id)?;
+        self.enqueue(&job.name, &self.data(id)?)
+    }
+
+    /// Delete the job files (the lock must be held)
+    fn remove_files(&self, id: u64) -> Result<()> {
+        fs::remove_file(self.path(id, METADATA_EXTENSION))?;
+        match fs::remove_file(self.path(id, DATA_EXTENSION)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Delete a job
+    pub fn remove(&self, id: u64) -> Result<()> {
+        let _lock = self.lock()?;
+        self.remove_files(id)
+    }
+
+    /// Delete the printed and cancelled jobs, returns the number of deleted jobs
+    pub fn purge(&self) -> Result<usize> {
+        let _lock = self.lock()?;
+        let mut count = 0;
+        for job in self.jobs()? {
+            if matches!(job.status, SpoolJobStatus::Printed | SpoolJobStatus::Cancelled) {
+                self.remove_files(job.id)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Delay before the next attempt after `attempts` failed attempts
+    fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Deliver a pending or failed job (whatever its next attempt time), updating its status
+    ///
+    /// The job is first claimed (in progress) while holding the lock, so that it is not delivered twice by
+    /// concurrent callers. A job already in progress is refused, unless its claim has expired
+    /// (interrupted delivery).
+    ///
+    /// The lock is not held while the data is sent, the job can be cancelled in the meantime:
+    /// a cancelled job stays cancelled if the delivery fails.
+    pub fn deliver(&self, id: u64, driver: &dyn Driver) -> Result<()> {
+        self.update(id, |job| {
+            let now = now_millis();
+            if matches!(job.status, SpoolJobStatus::Printed | SpoolJobStatus::Cancelled)
+                || (job.status == SpoolJobStatus::InProgress && !job.is_pending(now))
+            {
+                return Err(PrinterError::Input(format!("spool job {id} is {}", job.status.name())));
+            }
+            job.status = SpoolJobStatus::InProgress;
+            job.next_attempt_at = now + DELIVERY_LEASE_SECONDS * 1000;
+            Ok(())
+        })?;
+
+        let result = self
+            .data(id)
+            .and_then(|data| driver.write(&data))
+            .and_then(|_| driver.flush());
+
+        let job = self.update(id, |job| {
+            job.attempts += 1;
+            match &result {
+                Ok(()) => {
+                    job.status = SpoolJobStatus::Printed;
+                    job.last_error = None;
+                }
+                Err(e) => {
+                    job.last_error = Some(e.to_string());
+                    // A job cancelled in the meantime keeps its status
+                    if job.status == SpoolJobStatus::InProgress {
+                        if job.attempts >= self.max_attempts {
+                            job.status = SpoolJobStatus::Failed;
+                        } else {
+                            job.status = SpoolJobStatus::Pending;
+                            job.next_attempt_at = now_millis() + self.backoff(job.attempts).as_millis() as u64;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        match &result {
+            Ok(()) => debug!("[spool] job {id} ({}) printed on {}", job.name, driver.name()),
+            Err(e) => warn!("[spool] job {id} ({}) attempt {} failed: {e}", job.name, job.attempts),
+        }
+
+        result
+    }
+
+    /// Deliver the pending jobs due for an attempt, in order, returns the IDs of the printed jobs
+    ///
+    /// Delivery stops at the first job waiting for a retry, so that jobs are printed in order.
+    /// A job which has reached the maximum number of attempts, or which is delivered by another caller,
+    /// does not block the next ones.
+    pub fn deliver_pending(&self, driver: &dyn Driver) -> Result<Vec<u64>> {
+        let now = now_millis();
+        let mut printed = vec![];
+        for job in self.jobs()? {
+            if job.status == SpoolJobStatus::Pending && job.next_attempt_at > now {
+                break;
+            }
+            if !job.is_pending(now) {
+                continue;
+            }
+            if self.deliver(job.id, driver).is_ok() {
+                printed.push(job.id);
+            } else if self.job(job.id)?.status == SpoolJobStatus::Pending {
+                break;
+            }
+        }
+        Ok(printed)
+    }
+}
+
+/// Background worker delivering the jobs of a spool
+///
+/// Drivers are not thread-safe, so the worker opens its driver itself, before each delivery round
+/// (which also reconnects after a network error).
+///
+/// # Example
+///
+/// ```no_run
+/// use escpos::driver::*;
+/// use escpos::spool::*;
+/// use std::time::Duration;
+///
+/// let spool = Spool::open("./spool").unwrap();
+/// let worker = SpoolWorker::start(spool, Duration::from_secs(1), || {
+///     NetworkDriver::open("192.168.1.248", 9100, Some(Duration::from_secs(2)))
+/// });
+/// // ...
+/// worker.stop();
+/// ```
+pub struct SpoolWorker {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SpoolWorker {
+    /// Start a worker checking the spool every `interval`
+    pub fn start<D, F>(spool: Spool, interval: Duration, open_driver: F) -> Self
+    where
+        D: Driver,
+        F: Fn() -> Result<D> + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let handle = {
+            let running = running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    let now = now_millis();
+                    let has_pending = spool
+                        .jobs()
+                        .map(|jobs| jobs.iter().any(|job| job.is_pending(now)))
+                        .unwrap_or(false);
+                    if has_pending {
+                        let result = open_driver().and_then(|driver| spool.deliver_pending(&driver));
+                        if let Err(e) = result {
+                            warn!("[spool] delivery failed: {e}");
+                        }
+                    }
+                    thread::sleep(interval);
+                }
+            })
+        };
+
+        Self {
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    /// Is the worker running?
+    pub fn is_running(&self) -> bool {
+        self.handle.as_ref().is_some_and(|handle| !handle.is_finished())
+    }
+
+    /// Stop the worker, waiting for the current delivery round
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for SpoolWorker {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockDriver, MockOperation};
+
+    /// Spool in a new temporary directory
+    fn spool(name: &str) -> Spool {
+        let dir = std::env::temp_dir().join(format!("escpos-spool-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Spool::open(dir).unwrap()
+    }
+
+    #[test]
+    fn test_spool_queue() {
+        let spool = spool("queue");
+        let first = spool.enqueue("first", b"\x1B@first").unwrap();
+        let second = spool.enqueue("second", b"\x1B@second").unwrap();
+        assert_eq!((first, second), (1, 2));
+
+        // Reopened spool (process restart)
+        let reopened = Spool::open(spool.dir()).unwrap();
+        let jobs = reopened.jobs().unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(
+            (jobs[0].name.as_str(), jobs[0].status),
+            ("first", SpoolJobStatus::Pending)
+        );
+        assert_eq!(reopened.data(second).unwrap(), b"\x1B@second");
+
+        reopened.cancel(first).unwrap();
+        let driver = MockDriver::new();
+        assert_eq!(reopened.deliver_pending(&driver).unwrap(), vec![second]);
+        assert_eq!(driver.written(), b"\x1B@second");
+        assert!(reopened.cancel(second).is_err());
+
+        let third = reopened.reprint(second).unwrap();
+        assert_eq!(third, 3);
+        assert_eq!(reopened.purge().unwrap(), 2);
+        assert_eq!(
+            reopened.jobs().unwrap().iter().map(|j| j.id).collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert!(reopened.job(first).is_err());
+
+        // IDs are never reused
+        reopened.remove(third).unwrap();
+        assert_eq!(reopened.enqueue("fourth", b"fourth").unwrap(), 4);
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_spool_metadata() {
+        let job = SpoolJob {
+            id: 7,
+            name: "table 4\n\nkitchen".to_string(),
+            created_at: 1,
+            status: SpoolJobStatus::Failed,
+            attempts: 3,
+            next_attempt_at: 2,
+            last_error: Some("IO error: a=b\r\n".to_string()),
+        };
+        assert_eq!(SpoolJob::from_text(&job.to_text()).unwrap(), job);
+        assert!(SpoolJob::from_text("id=1\nname=job").is_err());
+    }
+
+    #[test]
+    fn test_spool_concurrent_enqueue() {
+        let spool = spool("concurrent");
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let spool = spool.clone();
+                thread::spawn(move || {
+                    (0..10)
+                        .map(|j| spool.enqueue("job", format!("{i}-{j}").as_bytes()).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let mut ids: Vec<u64> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+        ids.sort_unstable();
+
+        assert_eq!(ids, (1..=80).collect::<Vec<_>>());
+        assert_eq!(spool.jobs().unwrap().len(), 80);
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_spool_concurrent_delivery() {
+        let spool = spool("concurrent-delivery");
+        let ids: Vec<u64> = (0..20)
+            .map(|i| spool.enqueue("job", format!("{i:02}").as_bytes()).unwrap())
+            .collect();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let spool = spool.clone();
+                thread::spawn(move || {
+                    let driver = MockDriver::new();
+                    let printed = spool.deliver_pending(&driver).unwrap();
+                    (printed, driver.written())
+                })
+            })
+            .collect();
+        let mut printed = vec![];
+        for handle in handles {
+            let (ids, written) = handle.join().unwrap();
+            assert_eq!(written.len(), 2 * ids.len());
+            printed.extend(ids);
+        }
+        printed.sort_unstable();
+
+        // Each job is printed exactly once
+        assert_eq!(printed, ids);
+        assert!(spool
+            .jobs()
+            .unwrap()
+            .iter()
+            .all(|job| (job.status, job.attempts) == (SpoolJobStatus::Printed, 1)));
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_spool_claimed_job() {
+        let spool = spool("claimed");
+        let id = spool.enqueue("job", b"job").unwrap();
+        let claim = |until: u64| {
+            spool
+                .update(id, |job| {
+                    job.status = SpoolJobStatus::InProgress;
+                    job.next_attempt_at = until;
+                    Ok(())
+                })
+                .unwrap()
+        };
+
+        // Delivered by another caller
+        claim(now_millis() + 60_000);
+        let driver = MockDriver::new();
+        assert!(spool.deliver(id, &driver).is_err());
+        assert!(spool.deliver_pending(&driver).unwrap().is_empty());
+        assert!(driver.written().is_empty());
+
+        // Interrupted delivery (expired claim)
+        claim(0);
+        assert_eq!(spool.deliver_pending(&driver).unwrap(), vec![id]);
+        assert_eq!(spool.job(id).unwrap().status, SpoolJobStatus::Printed);
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    /// Driver cancelling the job while it is delivered
+    struct CancellingDriver(Spool, u64);
+
+    impl Driver for CancellingDriver {
+        fn name(&self) -> String {
+            "cancelling".to_string()
+        }
+
+        fn write(&self, _data: &[u8]) -> Result<()> {
+            self.0.cancel(self.1)?;
+            Err(PrinterError::Io("unreachable".to_string()))
+        }
+
+        fn read(&self, _buf: &mut [u8]) -> Result<usize> {
+            Ok(0)
+        }
+
+        fn flush(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_spool_cancel_during_delivery() {
+        let spool = spool("cancel");
+        let id = spool.enqueue("job", b"job").unwrap();
+
+        assert!(spool.deliver(id, &CancellingDriver(spool.clone(), id)).is_err());
+        let job = spool.job(id).unwrap();
+        assert_eq!((job.status, job.attempts), (SpoolJobStatus::Cancelled, 1));
+        assert!(spool.deliver(id, &MockDriver::new()).is_err());
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_spool_retry() {
+        let spool = spool("retry")
+            .with_max_attempts(2)
+            .unwrap()
+            .with_backoff(Duration::ZERO, Duration::ZERO);
+        let first = spool.enqueue("first", b"first").unwrap();
+        let second = spool.enqueue("second", b"second").unwrap();
+
+        let driver = MockDriver::new()
+            .with_error(MockOperation::Write, 1, "unreachable")
+            .with_error(MockOperation::Write, 2, "unreachable");
+
+        // Delivery stops at the first failure
+        assert!(spool.deliver_pending(&driver).unwrap().is_empty());
+        let job = spool.job(first).unwrap();
+        assert_eq!((job.status, job.attempts), (SpoolJobStatus::Pending, 1));
+        assert_eq!(job.last_error.as_deref(), Some("IO error: unreachable"));
+        assert_eq!(spool.job(second).unwrap().attempts, 0);
+
+        // Maximum number of attempts reached
+        assert_eq!(spool.deliver_pending(&driver).unwrap(), vec![second]);
+        assert_eq!(spool.job(first).unwrap().status, SpoolJobStatus::Failed);
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_spool_backoff() {
+        let spool = spool("backoff").with_backoff(Duration::from_secs(5), Duration::from_secs(30));
+        assert_eq!(spool.backoff(1), Duration::from_secs(5));
+        assert_eq!(spool.backoff(3), Duration::from_secs(20));
+        assert_eq!(spool.backoff(10), Duration::from_secs(30));
+        assert!(spool.clone().with_max_attempts(0).is_err());
+
+        // Next attempt in the future
+        let id = spool.enqueue("job", b"job").unwrap();
+        let driver = MockDriver::new().with_error(MockOperation::Write, 1, "unreachable");
+        assert!(spool.deliver_pending(&driver).unwrap().is_empty());
+        assert!(spool.deliver_pending(&driver).unwrap().is_empty());
+        assert_eq!(spool.job(id).unwrap().attempts, 1);
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_spool_worker() {
+        let spool = spool("worker");
+        let id = spool.enqueue("job", b"job").unwrap();
+
+        let worker = SpoolWorker::start(spool.clone(), Duration::from_millis(10), || Ok(MockDriver::new()));
+        assert!(worker.is_running());
+        let printed = (0..200).any(|_| {
+            thread::sleep(Duration::from_millis(10));
+            spool
+                .job(id)
+                .map(|job| job.status == SpoolJobStatus::Printed)
+                .unwrap_or(false)
+        });
+        worker.stop();
+        assert!(printed);
+
+        fs::remove_dir_all(spool.dir()).unwrap();
+    }
+}
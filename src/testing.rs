@@ -0,0 +1,526 @@
+//! Test utilities: scriptable mock driver and byte snapshots (golden files)
+
+use crate::{
+    driver::Driver,
+    errors::{PrinterError, Result},
+};
+use std::{cell::RefCell, env, fs, path::Path, rc::Rc, thread::sleep, time::Duration};
+
+/// Environment variable creating or updating golden files instead of comparing them, when set to `1`
+pub const UPDATE_GOLDEN_ENV: &str = "ESCPOS_UPDATE_GOLDEN";
+
+/// Maximum number of decoded lines compared with a line diff (first difference only above)
+const MAX_DIFF_LINES: usize = 2000;
+
+/// Number of unchanged lines displayed around a difference
+const DIFF_CONTEXT_LINES: usize = 3;
+
+// ================ Mock driver ================
+
+/// Driver operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockOperation {
+    Write,
+    Read,
+    Flush,
+}
+
+/// Event recorded by the mock driver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockEvent {
+    /// Data written
+    Write(Vec<u8>),
+    /// Data read
+    Read(Vec<u8>),
+    /// Flush
+    Flush,
+}
+
+/// Mock driver state, shared between the clones of a driver
+#[derive(Debug, Default)]
+struct MockState {
+    events: Vec<MockEvent>,
+    /// Scripted responses: request bytes and response bytes
+    responses: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Bytes waiting to be read
+    pending: Vec<u8>,
+    /// Injected errors: operation, call number (from 1) and message
+    errors: Vec<(MockOperation, usize, String)>,
+    delays: Vec<(MockOperation, Duration)>,
+    calls: [usize; 3],
+}
+
+impl MockState {
+    /// Count a call, apply the delay and return the injected error if any
+    fn call(&mut self, operation: MockOperation) -> Result<()> {
+        let calls = &mut self.calls[operation as usize];
+        *calls += 1;
+        let call = *calls;
+
+        if let Some((_, delay)) = self.delays.iter().find(|(op, _)| *op == operation) {
+            sleep(*delay);
+        }
+        match self.errors.iter().find(|(op, n, _)| *op == operation && *n == call) {
+            Some((_, _, message)) => Err(PrinterError::Io(message.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Scriptable driver for tests
+///
+/// The driver records every write and flush, replies to requests with scripted responses and can inject
+/// I/O errors or delays. Clones share the same state, so a clone can be inspected after giving the driver
+/// to a [`Printer`](crate::printer::Printer).
+///
+/// # Example
+///
+/// ```rust
+/// use escpos::printer::Printer;
+/// use escpos::testing::*;
+/// use escpos::utils::*;
+/// use escpos::driver::*;
+///
+/// // Reply 0x12 (online) to DLE EOT 1
+/// let driver = MockDriver::new().with_response(&[0x10, 0x04, 0x01], &[0x12]);
+/// Printer::new(driver.clone(), Protocol::default(), None)
+///     .real_time_status(RealTimeStatusRequest::Printer)
+///     .unwrap()
+///     .send_status()
+///     .unwrap();
+///
+/// let mut buf = [0; 1];
+/// assert_eq!(driver.read(&mut buf).unwrap(), 1);
+/// let status = RealTimeStatusResponse::parse(RealTimeStatusRequest::Printer, buf[0]).unwrap();
+/// assert_eq!(status.get(&RealTimeStatusResponse::Online), Some(&true));
+/// assert_eq!(driver.written(), vec![0x10, 0x04, 0x01, 0x00]);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MockDriver {
+    state: Rc<RefCell<MockState>>,
+}
+
+impl MockDriver {
+    /// Create a new `MockDriver`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reply `response` each time written data contains `request`
+    pub fn with_response(self, request: &[u8], response: &[u8]) -> Self {
+        self.state
+            .borrow_mut()
+            .responses
+            .push((request.to_vec(), response.to_vec()));
+        self
+    }
+
+    /// Make the `call`-th (from 1) call of the operation fail with an I/O error
+    pub fn with_error(self, operation: MockOperation, call: usize, message: &str) -> Self {
+        self.state
+            .borrow_mut()
+            .errors
+            .push((operation, call, message.to_string()));
+        self
+    }
+
+    /// Wait before each call of the operation
+    pub fn with_delay(self, operation: MockOperation, delay: Duration) -> Self {
+        self.state.borrow_mut().delays.push((operation, delay));
+        self
+    }
+
+    /// Add bytes to read, without request
+    pub fn push_read(&self, data: &[u8]) {
+        self.state.borrow_mut().pending.extend_from_slice(data);
+    }
+
+    /// Get recorded events
+    pub fn events(&self) -> Vec<MockEvent> {
+        self.state.borrow().events.clone()
+    }
+
+    /// Get all written bytes
+    pub fn written(&self) -> Vec<u8> {
+        self.writes().concat()
+    }
+
+    /// Get written data, one item per write
+    pub fn writes(&self) -> Vec<Vec<u8>> {
+        self.state
+            .borrow()
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                MockEvent::Write(data) => Some(data.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the number of flushes
+    pub fn flush_count(&self) -> usize {
+        self.state
+            .borrow()
+            .events
+            .iter()
+            .filter(|event| **event == MockEvent::Flush)
+            .count()
+    }
+
+    /// Clear recorded events and bytes waiting to be read (scripted responses and errors are kept)
+    pub fn clear(&self) {
+        let mut state = self.state.borrow_mut();
+        state.events.clear();
+        state.pending.clear();
+    }
+
+    /// Compare all written bytes to a golden file (see [`assert_golden`])
+    #[track_caller]
+    pub fn assert_golden<P: AsRef<Path>>(&self, path: P) {
+        assert_golden(&self.written(), path);
+    }
+}
+
+impl Driver for MockDriver {
+    fn name(&self) -> String {
+        "mock".to_owned()
+    }
+
+    fn write(&self, data: &[u8]) -> Result<()> {
+        let mut state = self.state.try_borrow_mut()?;
+        state.call(MockOperation::Write)?;
+
+        let responses: Vec<u8> = state
+            .responses
+            .iter()
+            .filter(|(request, _)| !request.is_empty() && data.windows(request.len()).any(|w| w == request))
+            .flat_map(|(_, response)| response.clone())
+            .collect();
+        state.pending.extend(responses);
+        state.events.push(MockEvent::Write(data.to_vec()));
+        Ok(())
+    }
+
+    fn read(&self, buf: &mut [u8]) -> Result<usize> {
+        let mut state = self.state.try_borrow_mut()?;
+        state.call(MockOperation::Read)?;
+
+        let len = buf.len().min(state.pending.len());
+        let data: Vec<u8> = state.pending.drain(..len).collect();
+        buf[..len].copy_from_slice(&data);
+        if len > 0 {
+            state.events.push(MockEvent::Read(data));
+        }
+        Ok(len)
+    }
+
+    fn flush(&self) -> Result<()> {
+        let mut state = self.state.try_borrow_mut()?;
+        state.call(MockOperation::Flush)?;
+        state.events.push(MockEvent::Flush);
+        Ok(())
+    }
+}
+
+// ================ Byte snapshots ================
+
+/// Name of an ASCII control character
+fn control_name(byte: u8) -> Option<&'static str> {
+    Some(match byte {
+        0x00 => "NUL",
+        0x04 => "EOT",
+        0x05 => "ENQ",
+        0x07 => "BEL",
+        0x08 => "BS",
+        0x09 => "HT",
+        0x0A => "LF",
+        0x0C => "FF",
+        0x0D => "CR",
+        0x10 => "DLE",
+        0x14 => "DC4",
+        0x18 => "CAN",
+        0x1B => "ESC",
+        0x1C => "FS",
+        0x1D => "GS",
+        _ => return None,
+    })
+}
+
+/// Decode ESC/POS bytes into readable lines
+///
+/// A new line starts with each command prefix (`ESC`, `GS`, `FS` and `DLE`) and after each `LF`.
+/// Printable ASCII characters are kept as is (in quotes), other bytes are written in hexadecimal.
+///
+/// ```rust
+/// use escpos::testing::decode_commands;
+///
+/// assert_eq!(
+///     decode_commands(b"\x1B@Hello\n\x1DVA\x00"),
+///     vec!["ESC '@Hello' LF", "GS 'VA' NUL"]
+/// );
+/// ```
+pub fn decode_commands(data: &[u8]) -> Vec<String> {
+    let mut lines = vec![];
+    let mut tokens: Vec<String> = vec![];
+    let mut text = String::new();
+
+    let flush_text = |text: &mut String, tokens: &mut Vec<String>| {
+        if !text.is_empty() {
+            tokens.push(format!("'{text}'"));
+            text.clear();
+        }
+    };
+
+    for byte in data {
+        if matches!(byte, 0x1B | 0x1D | 0x1C | 0x10) {
+            flush_text(&mut text, &mut tokens);
+            if !tokens.is_empty() {
+                lines.push(tokens.join(" "));
+                tokens.clear();
+            }
+        }
+
+        match (byte, control_name(*byte)) {
+            (0x20..=0x7E, _) => text.push(*byte as char),
+            (_, Some(name)) => {
+                flush_text(&mut text, &mut tokens);
+                tokens.push(name.to_string());
+            }
+            _ => {
+                flush_text(&mut text, &mut tokens);
+                tokens.push(format!("0x{byte:02X}"));
+            }
+        }
+
+        if *byte == 0x0A {
+            lines.push(tokens.join(" "));
+            tokens.clear();
+        }
+    }
+    flush_text(&mut text, &mut tokens);
+    if !tokens.is_empty() {
+        lines.push(tokens.join(" "));
+    }
+
+    lines
+}
+
+/// Line diff of decoded commands (`-` expected, `+` actual), with a few unchanged lines around differences
+fn diff_lines(expected: &[String], actual: &[String]) -> String {
+    // Common prefix and suffix are not compared
+    let prefix = expected.iter().zip(actual).take_while(|(e, a)| e == a).count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(e, a)| e == a)
+        .count();
+    let (old, new) = (
+        &expected[prefix..expected.len() - suffix],
+        &actual[prefix..actual.len() - suffix],
+    );
+
+    let mut diff: Vec<(char, &String)> = expected[prefix.saturating_sub(DIFF_CONTEXT_LINES)..prefix]
+        .iter()
+        .map(|line| (' ', line))
+        .collect();
+
+    if old.len() * new.len() > MAX_DIFF_LINES * MAX_DIFF_LINES {
+        diff.extend(old.iter().take(DIFF_CONTEXT_LINES).map(|line| ('-', line)));
+        diff.extend(new.iter().take(DIFF_CONTEXT_LINES).map(|line| ('+', line)));
+    } else {
+        // Longest common subsequence
+        let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = match old[i] == new[j] {
+                    true => lcs[i + 1][j + 1] + 1,
+                    false => lcs[i + 1][j].max(lcs[i][j + 1]),
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                diff.push((' ', &old[i]));
+                (i, j) = (i + 1, j + 1);
+            } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                diff.push(('+', &new[j]));
+                j += 1;
+            } else {
+                diff.push(('-', &old[i]));
+                i += 1;
+            }
+        }
+    }
+
+    let end = expected.len() - suffix;
+    diff.extend(
+        expected[end..(end + DIFF_CONTEXT_LINES).min(expected.len())]
+            .iter()
+            .map(|line| (' ', line)),
+    );
+
+    diff.iter()
+        .map(|(sign, line)| format!("{sign} {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Assert two byte sequences are equal, displaying a diff of the decoded commands otherwise
+///
+/// ```rust,should_panic
+/// use escpos::testing::assert_bytes_eq;
+///
+/// assert_bytes_eq(b"\x1B@Hello\n", b"\x1B@Hi\n");
+/// ```
+#[track_caller]
+pub fn assert_bytes_eq(actual: &[u8], expected: &[u8]) {
+    if actual != expected {
+        let position = actual.iter().zip(expected).take_while(|(a, e)| a == e).count();
+        panic!(
+            "bytes differ at offset {position} ({} bytes, {} expected)\n{}",
+            actual.len(),
+            expected.len(),
+            diff_lines(&decode_commands(expected), &decode_commands(actual))
+        );
+    }
+}
+
+/// Assert bytes are equal to the content of a golden file
+///
+/// The golden file is created or updated instead only when the `ESCPOS_UPDATE_GOLDEN` environment variable
+/// is `1` (`ESCPOS_UPDATE_GOLDEN=1 cargo test`), any other value is ignored.
+#[track_caller]
+pub fn assert_golden<P: AsRef<Path>>(actual: &[u8], path: P) {
+    let update = env::var(UPDATE_GOLDEN_ENV).is_ok_and(|value| value == "1");
+    check_golden(actual, path.as_ref(), update);
+}
+
+/// Compare bytes to the content of a golden file, or create or update it
+#[track_caller]
+fn check_golden(actual: &[u8], path: &Path, update: bool) {
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|e| panic!("cannot create {}: {e}", parent.display()));
+        }
+        fs::write(path, actual).unwrap_or_else(|e| panic!("cannot write golden file {}: {e}", path.display()));
+        return;
+    }
+
+    let expected = fs::read(path).unwrap_or_else(|e| {
+        panic!(
+            "cannot read golden file {}: {e} (set {UPDATE_GOLDEN_ENV}=1 to create it)",
+            path.display()
+        )
+    });
+    assert_bytes_eq(actual, &expected);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{printer::Printer, utils::*};
+
+    #[test]
+    fn test_mock_driver() {
+        let driver = MockDriver::new().with_response(&[0x10, 0x04, 0x04], &[0x12]);
+        Printer::new(driver.clone(), Protocol::default(), None)
+            .init()
+            .unwrap()
+            .real_time_status(RealTimeStatusRequest::RollPaperSensor)
+            .unwrap()
+            .print()
+            .unwrap();
+
+        assert_eq!(
+            driver.events(),
+            vec![
+                MockEvent::Write(vec![0x1B, 0x40]),
+                MockEvent::Write(vec![0x10, 0x04, 0x04, 0x00]),
+                MockEvent::Flush
+            ]
+        );
+        assert_eq!(driver.flush_count(), 1);
+
+        let mut buf = [0; 4];
+        assert_eq!(driver.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 0x12);
+        assert_eq!(driver.read(&mut buf).unwrap(), 0);
+
+        driver.push_read(&[1, 2]);
+        assert_eq!(driver.read(&mut buf[..1]).unwrap(), 1);
+        assert_eq!(driver.events().last(), Some(&MockEvent::Read(vec![1])));
+
+        driver.clear();
+        assert!(driver.events().is_empty());
+        assert_eq!(driver.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_mock_driver_errors() {
+        let driver = MockDriver::new()
+            .with_error(MockOperation::Write, 2, "connection reset")
+            .with_error(MockOperation::Flush, 1, "broken pipe")
+            .with_delay(MockOperation::Read, Duration::from_millis(1));
+
+        assert!(driver.write(b"a").is_ok());
+        assert!(matches!(driver.write(b"b"), Err(PrinterError::Io(message)) if message == "connection reset"));
+        assert!(driver.write(b"c").is_ok());
+        assert!(driver.flush().is_err());
+        assert!(driver.flush().is_ok());
+        assert_eq!(driver.written(), b"ac");
+        assert_eq!(driver.read(&mut [0; 1]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_decode_commands() {
+        assert_eq!(
+            decode_commands(b"\x1B!\x30Title\n\x1Dk\x02123\x00"),
+            vec!["ESC '!0Title' LF", "GS 'k' 0x02 '123' NUL"]
+        );
+        assert_eq!(decode_commands(&[0xE9, 0x1C, 0x2E]), vec!["0xE9", "FS '.'"]);
+        assert!(decode_commands(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            diff_lines(&lines(&["a", "b", "c", "d"]), &lines(&["a", "x", "c", "d", "e"])),
+            "  a\n+ x\n- b\n  c\n  d\n+ e"
+        );
+    }
+
+    #[test]
+    fn test_assert_bytes_eq() {
+        assert_bytes_eq(b"\x1B@", b"\x1B@");
+        let result = std::panic::catch_unwind(|| assert_bytes_eq(b"\x1B@Hi\n", b"\x1B@Hello\n"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_assert_golden() {
+        let path = env::temp_dir().join(format!("escpos-golden-{}.bin", std::process::id()));
+        fs::write(&path, b"\x1B@Hello\x1Bd\x01").unwrap();
+
+        let driver = MockDriver::new();
+        Printer::new(driver.clone(), Protocol::default(), None)
+            .init()
+            .unwrap()
+            .writeln("Hello")
+            .unwrap()
+            .print()
+            .unwrap();
+        check_golden(&driver.written(), &path, false);
+
+        let result = std::panic::catch_unwind(|| check_golden(b"\x1B@", &path, false));
+        assert!(result.is_err());
+
+        check_golden(b"\x1B@", &path, true);
+        assert_eq!(fs::read(&path).unwrap(), b"\x1B@");
+        fs::remove_file(&path).unwrap();
+    }
+}